@@ -0,0 +1,167 @@
+use crate::config::Config;
+use crate::ProblemTracker;
+use chrono::{Local, NaiveDateTime};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// `backups/` next to the database
+fn backup_dir(database: &Path) -> PathBuf {
+    database
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("backups")
+}
+
+/// Whether the last backup, going by when the backup directory last
+/// changed, is at least `interval` old
+fn is_due(dir: &Path, interval: Duration, now: SystemTime) -> bool {
+    match fs::metadata(dir).and_then(|meta| meta.modified()) {
+        Ok(modified) => now
+            .duration_since(modified)
+            .is_ok_and(|age| age >= interval),
+        Err(_) => true,
+    }
+}
+
+/// Backups of the database named `stem` in `dir`, oldest first
+fn existing_backups(dir: &Path, stem: &str) -> std::io::Result<Vec<PathBuf>> {
+    let prefix = format!("{}-", stem);
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_backup = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|rest| rest.strip_suffix(".db"))
+            .is_some_and(|timestamp| {
+                NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).is_ok()
+            });
+        if is_backup {
+            backups.push(path);
+        }
+    }
+    // Timestamps sort the same as text
+    backups.sort();
+
+    Ok(backups)
+}
+
+/// Deletes all but the newest `keep` backups of the database named `stem`
+fn prune(dir: &Path, stem: &str, keep: usize) -> std::io::Result<()> {
+    let backups = existing_backups(dir, stem)?;
+    for path in &backups[..backups.len().saturating_sub(keep)] {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// With `auto_backup` on, copies the database into `backups/` when the last
+/// backup is older than `backup_interval_hours`, then keeps only the newest
+/// `max_backups`. Returns the new backup's path if one was made.
+pub fn run_if_due(
+    tracker: &ProblemTracker,
+    database: &Path,
+    key: Option<&str>,
+    config: &Config,
+) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    if !config.auto_backup {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(database);
+    let interval = Duration::from_secs_f64(config.backup_interval_hours * 3600.0);
+    if !is_due(&dir, interval, SystemTime::now()) {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(&dir)?;
+    let stem = database
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("problems");
+    let path = dir.join(format!(
+        "{}-{}.db",
+        stem,
+        Local::now().format(TIMESTAMP_FORMAT)
+    ));
+    tracker.backup_to(&path, key)?;
+    prune(&dir, stem, config.max_backups)?;
+
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdir::TempDir;
+
+    #[test]
+    fn prune_keeps_the_newest_backups_of_that_database() {
+        let dir = TempDir::new("prune");
+        for name in [
+            "problems-20260101-090000.db",
+            "problems-20260102-090000.db",
+            "problems-20260103-090000.db",
+            "problems-old.db",
+            "work-20260101-090000.db",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        prune(dir.path(), "problems", 2).unwrap();
+
+        let mut left: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "problems-20260102-090000.db",
+                "problems-20260103-090000.db",
+                "problems-old.db",
+                "work-20260101-090000.db",
+            ]
+        );
+    }
+
+    #[test]
+    fn backup_is_due_only_after_the_interval() {
+        let dir = TempDir::new("due");
+        let hour = Duration::from_secs(3600);
+        let now = SystemTime::now();
+
+        assert!(!is_due(dir.path(), hour, now));
+        assert!(is_due(dir.path(), hour, now + hour));
+        assert!(is_due(&dir.join("missing"), hour, now));
+    }
+
+    #[test]
+    fn copies_the_database_when_enabled() {
+        let dir = TempDir::new("copy");
+        let database = dir.join("problems.db");
+        let tracker = ProblemTracker::new(&database.to_string_lossy(), false, None).unwrap();
+        tracker.add_problem(crate::Problem::new("Two Sum")).unwrap();
+        let config = Config {
+            auto_backup: true,
+            ..Config::default()
+        };
+
+        let path = run_if_due(&tracker, &database, None, &config)
+            .unwrap()
+            .unwrap();
+        let copy = ProblemTracker::new(&path.to_string_lossy(), false, None).unwrap();
+        assert_eq!(copy.get_all_problems().unwrap().len(), 1);
+        // The backup just made resets the clock
+        assert!(run_if_due(&tracker, &database, None, &config)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,146 @@
+/// Most typos a new category name may contain and still be taken for an
+/// existing one
+const SUGGEST_DISTANCE: usize = 2;
+
+/// A canonical category and how many problems use it
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    pub problems: usize,
+}
+
+/// Lowercase letters and digits only, so "Two-Pointers" and "two pointers"
+/// compare equal
+fn squash(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// First letter of each word, lowercased: "Dynamic Programming" gives "dp"
+fn initials(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|word| word.chars().next())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Whether two category names probably mean the same thing: equal but for
+/// case and punctuation, a plural of one another, or one the acronym of
+/// the other
+pub fn similar(a: &str, b: &str) -> bool {
+    let (a_squashed, b_squashed) = (squash(a), squash(b));
+    if a_squashed.is_empty() || b_squashed.is_empty() {
+        return false;
+    }
+
+    let is_acronym = |short: &str, long: &str| {
+        let initials = initials(long);
+        initials.len() > 1 && initials == squash(short)
+    };
+
+    a_squashed == b_squashed
+        || format!("{}s", a_squashed) == b_squashed
+        || format!("{}s", b_squashed) == a_squashed
+        || is_acronym(a, b)
+        || is_acronym(b, a)
+}
+
+/// Pairs of similar categories as `(merge_from, merge_into)`, keeping the
+/// one more problems use, or the older one on a tie
+pub fn similar_pairs(categories: &[Category]) -> Vec<(&Category, &Category)> {
+    let mut pairs = Vec::new();
+    for (index, a) in categories.iter().enumerate() {
+        for b in &categories[index + 1..] {
+            if !similar(&a.name, &b.name) {
+                continue;
+            }
+            if (b.problems, std::cmp::Reverse(b.id)) > (a.problems, std::cmp::Reverse(a.id)) {
+                pairs.push((a, b));
+            } else {
+                pairs.push((b, a));
+            }
+        }
+    }
+
+    pairs
+}
+
+/// The existing category a new `name` was probably meant to be: the
+/// nearest within `SUGGEST_DISTANCE` edits, and fewer than half the name's
+/// length so short names don't all look alike. `None` when `name` already
+/// exists in some capitalization.
+pub fn suggestion<'a>(name: &str, categories: &'a [Category]) -> Option<&'a Category> {
+    let name = name.trim().to_lowercase();
+    if categories
+        .iter()
+        .any(|category| category.name.to_lowercase() == name)
+    {
+        return None;
+    }
+
+    let length = name.chars().count();
+    categories
+        .iter()
+        .map(|category| {
+            let distance = strsim::levenshtein(&name, &category.name.to_lowercase());
+            (distance, category)
+        })
+        .filter(|&(distance, _)| distance <= SUGGEST_DISTANCE && distance * 2 < length)
+        .min_by_key(|&(distance, category)| (distance, std::cmp::Reverse(category.problems)))
+        .map(|(_, category)| category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_case_punctuation_plurals_and_acronyms() {
+        assert!(similar("Two Pointers", "two-pointers"));
+        assert!(similar("Array", "Arrays"));
+        assert!(similar("DP", "Dynamic Programming"));
+        assert!(similar("dynamic programming", "dp"));
+        assert!(!similar("Graphs", "Greedy"));
+        assert!(!similar("G", "Graphs"));
+        assert!(!similar("", "Arrays"));
+    }
+
+    #[test]
+    fn suggests_near_misses_only() {
+        let categories = [Category {
+            id: 1,
+            name: "Graph".to_string(),
+            problems: 3,
+        }];
+        let suggested = |name| suggestion(name, &categories).map(|c| c.name.as_str());
+
+        assert_eq!(suggested("grph"), Some("Graph"));
+        assert_eq!(suggested("Grapsh"), Some("Graph"));
+        assert_eq!(suggested("graph"), None);
+        assert_eq!(suggested("Greedy"), None);
+        assert_eq!(suggested("Gr"), None);
+    }
+
+    #[test]
+    fn keeps_the_more_used_category() {
+        let category = |id, name: &str, problems| Category {
+            id,
+            name: name.to_string(),
+            problems,
+        };
+        let categories = [
+            category(1, "DP", 2),
+            category(2, "Dynamic Programming", 5),
+            category(3, "Arrays", 1),
+            category(4, "Array", 1),
+        ];
+
+        let pairs: Vec<(&str, &str)> = similar_pairs(&categories)
+            .into_iter()
+            .map(|(from, into)| (from.name.as_str(), into.name.as_str()))
+            .collect();
+        assert_eq!(pairs, [("DP", "Dynamic Programming"), ("Array", "Arrays")]);
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+/// The zone dates are bucketed in: the system's unless the config or
+/// `$SHIKARI_TZ` names one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Zone {
+    System,
+    Named(Tz),
+}
+
+/// Most days any option or setting may reach back, about a century.
+/// Larger spans would take dates past what chrono can represent.
+pub const MAX_DAYS: u32 = 36_500;
+
+/// Set once at startup, so "today" means the same day everywhere
+static ZONE: OnceLock<Zone> = OnceLock::new();
+
+pub fn set_zone(zone: Zone) {
+    let _ = ZONE.set(zone);
+}
+
+fn zone() -> Zone {
+    ZONE.get().copied().unwrap_or(Zone::System)
+}
+
+/// Parses an IANA name such as "America/New_York"
+pub fn parse_zone(name: &str) -> Result<Tz, String> {
+    name.parse().map_err(|_| {
+        format!(
+            "unknown timezone '{}', expected a name like America/New_York",
+            name
+        )
+    })
+}
+
+impl Zone {
+    fn date_of(self, timestamp: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Zone::System => timestamp.with_timezone(&Local).date_naive(),
+            Zone::Named(tz) => timestamp.with_timezone(&tz).date_naive(),
+        }
+    }
+
+    /// The earlier instant when the wall-clock time happens twice, `None`
+    /// when it falls into a DST gap
+    fn instant_of(self, naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::System => Local
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|local| local.with_timezone(&Utc)),
+            Zone::Named(tz) => tz
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|local| local.with_timezone(&Utc)),
+        }
+    }
+
+    fn format(self, timestamp: DateTime<Utc>, format: &str) -> String {
+        match self {
+            Zone::System => timestamp.with_timezone(&Local).format(format).to_string(),
+            Zone::Named(tz) => timestamp.with_timezone(&tz).format(format).to_string(),
+        }
+    }
+}
+
+/// The calendar date `timestamp` falls on in the configured zone
+pub fn date_of(timestamp: DateTime<Utc>) -> NaiveDate {
+    zone().date_of(timestamp)
+}
+
+pub fn today() -> NaiveDate {
+    date_of(Utc::now())
+}
+
+/// A wall-clock time in the configured zone as an instant
+pub fn instant_of(naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+    zone().instant_of(naive)
+}
+
+/// `timestamp` written with a chrono format string in the configured zone
+pub fn format(timestamp: DateTime<Utc>, format: &str) -> String {
+    zone().format(timestamp, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_york() -> Zone {
+        Zone::Named(parse_zone("America/New_York").unwrap())
+    }
+
+    #[test]
+    fn dates_follow_the_named_zone() {
+        // 03:00 UTC is still the previous evening in New York
+        let timestamp = Utc.with_ymd_and_hms(2026, 10, 15, 3, 0, 0).unwrap();
+        assert_eq!(
+            new_york().date_of(timestamp),
+            NaiveDate::from_ymd_opt(2026, 10, 14).unwrap()
+        );
+        assert_eq!(
+            new_york().format(timestamp, "%Y-%m-%d %H:%M"),
+            "2026-10-14 23:00"
+        );
+    }
+
+    #[test]
+    fn wall_clock_times_map_to_instants() {
+        let midnight = NaiveDate::from_ymd_opt(2026, 10, 15)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            new_york().instant_of(&midnight),
+            Some(Utc.with_ymd_and_hms(2026, 10, 15, 4, 0, 0).unwrap())
+        );
+
+        // Clocks jump from 02:00 to 03:00 on 2026-03-08
+        let skipped = NaiveDate::from_ymd_opt(2026, 3, 8)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        assert_eq!(new_york().instant_of(&skipped), None);
+    }
+
+    #[test]
+    fn rejects_unknown_zones() {
+        assert!(parse_zone("Mars/Olympus_Mons").is_err());
+    }
+}
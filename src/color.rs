@@ -0,0 +1,121 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::ops::Range;
+use termimad::crossterm::style::Stylize;
+
+/// Byte ranges of `text` matching any of `keywords`, ignoring ASCII case as
+/// SQLite's LIKE does. Longer keywords win where several match at once, and
+/// matches never overlap.
+fn keyword_ranges(text: &str, keywords: &[String]) -> Vec<Range<usize>> {
+    let mut keywords: Vec<&str> = keywords
+        .iter()
+        .map(String::as_str)
+        .filter(|keyword| !keyword.is_empty())
+        .collect();
+    keywords.sort_by_key(|keyword| std::cmp::Reverse(keyword.len()));
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let rest = &text.as_bytes()[start..];
+        let found = keywords.iter().find(|keyword| {
+            rest.len() >= keyword.len()
+                && rest[..keyword.len()].eq_ignore_ascii_case(keyword.as_bytes())
+                && text.is_char_boundary(start + keyword.len())
+        });
+        match found {
+            Some(keyword) => {
+                ranges.push(start..start + keyword.len());
+                start += keyword.len();
+            }
+            None => start += text[start..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    ranges
+}
+
+/// Colors for marking output as added, removed, changed or matched. Plain text
+/// unless stdout is a terminal, `$NO_COLOR` is unset and `--no-color`
+/// wasn't passed.
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    pub fn new(no_color: bool) -> Self {
+        let no_color_env = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Palette {
+            enabled: !no_color && !no_color_env && io::stdout().is_terminal(),
+        }
+    }
+
+    /// A palette that colors exactly when `enabled`, wherever output goes
+    pub fn with_color(enabled: bool) -> Self {
+        Palette { enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn added(&self, text: &str) -> String {
+        self.paint(text, |text| text.green().to_string())
+    }
+
+    pub fn removed(&self, text: &str) -> String {
+        self.paint(text, |text| text.red().to_string())
+    }
+
+    pub fn changed(&self, text: &str) -> String {
+        self.paint(text, |text| text.yellow().to_string())
+    }
+
+    /// `text` with each occurrence of a keyword shown in reverse video, in
+    /// its original case
+    pub fn highlight(&self, text: &str, keywords: &[String]) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        let mut highlighted = String::new();
+        let mut end = 0;
+        for range in keyword_ranges(text, keywords) {
+            highlighted.push_str(&text[end..range.start]);
+            highlighted.push_str(&text[range.clone()].reverse().to_string());
+            end = range.end;
+        }
+        highlighted.push_str(&text[end..]);
+        highlighted
+    }
+
+    fn paint(&self, text: &str, style: fn(&str) -> String) -> String {
+        if self.enabled {
+            style(text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_only_when_enabled() {
+        assert_eq!(Palette { enabled: false }.added("new"), "new");
+        let painted = Palette { enabled: true }.removed("gone");
+        assert!(painted.starts_with('\u{1b}') && painted.contains("gone"));
+    }
+
+    #[test]
+    fn keyword_matches_ignore_case_and_prefer_longer_keywords() {
+        let keywords = ["tree".to_string(), "TREES".to_string()];
+        let text = "Binary Trees, a tree and a fréetree";
+        let matched: Vec<&str> = keyword_ranges(text, &keywords)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+        assert_eq!(matched, ["Trees", "tree", "tree"]);
+        assert_eq!(Palette { enabled: false }.highlight(text, &keywords), text);
+    }
+}
@@ -0,0 +1,374 @@
+use crate::clock::{self, Zone};
+use crate::humanize::TimeFormat;
+use crate::infer;
+use crate::ranking::Weights;
+use crate::score::Points;
+use crate::SortField;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+/// Settings read from the config file. Every key is optional and falls back
+/// to its default; unknown keys are rejected so typos don't go unnoticed.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Days for recall of a problem of middling hardness to halve
+    pub memory_half_life_days: f64,
+    /// Back the database up into `backups/` next to it on startup
+    pub auto_backup: bool,
+    /// Minimum hours between automatic backups
+    pub backup_interval_hours: f64,
+    /// Automatic backups kept; older ones are deleted
+    pub max_backups: usize,
+    /// Characters a description may have before `add` and `edit` warn
+    pub max_description_length: usize,
+    /// Category `add` uses when none is given
+    pub default_category: Option<String>,
+    /// Pattern `add` uses when none is given
+    pub default_pattern: Option<String>,
+    /// Named sets of defaults, chosen with `add --profile`
+    pub profiles: BTreeMap<String, Profile>,
+    /// Problems to solve in each category, as `targets` tracks
+    pub targets: BTreeMap<String, u32>,
+    /// Keyword to pattern pairs `import --infer-pattern` uses on top of the
+    /// built-in ones; an empty pattern turns a built-in keyword off
+    pub pattern_keywords: BTreeMap<String, String>,
+    /// "minutes" or "compact"; `--raw-minutes` switches back to minutes
+    pub time_format: TimeFormat,
+    /// IANA zone such as "America/New_York" that dates are bucketed in;
+    /// `$SHIKARI_TZ` overrides it and the system zone is used when neither
+    /// is set
+    pub timezone: Option<String>,
+    /// Points `score` gives a solved problem of each difficulty
+    pub points_easy: i64,
+    pub points_medium: i64,
+    pub points_hard: i64,
+    /// Solve times above this many minutes flag the problem for review
+    pub auto_review_minutes: Option<i64>,
+    /// `--sort-by` value `list` uses when none is given
+    pub default_list_sort: Option<SortField>,
+    /// Reverse `list`'s order when no `--sort-by` is given
+    pub default_list_desc: bool,
+    /// Problems `review` shows when no `--max` is given, most overdue first
+    pub review_max: Option<usize>,
+    /// Days a deleted problem stays in the trash before a command purges it;
+    /// kept until `empty-trash` when unset
+    pub trash_retention_days: Option<u32>,
+    /// Shell command run after a command changes the database, with the
+    /// changed problems' IDs as arguments and in `$SHIKARI_PROBLEM_IDS`
+    pub post_write_hook: Option<String>,
+    /// How much `queue` weighs staleness, the review flag, personal
+    /// difficulty and the focus list
+    pub queue_weight_staleness: f64,
+    pub queue_weight_review: f64,
+    pub queue_weight_difficulty: f64,
+    pub queue_weight_priority: f64,
+}
+
+/// A `[profiles.<name>]` table; its keys override the top-level defaults
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub default_category: Option<String>,
+    pub default_pattern: Option<String>,
+}
+
+impl Config {
+    /// The defaults `add` applies: the top-level ones, overridden by those
+    /// of the named profile
+    pub fn add_defaults(&self, profile: Option<&str>) -> Result<Profile, String> {
+        let mut defaults = Profile {
+            default_category: self.default_category.clone(),
+            default_pattern: self.default_pattern.clone(),
+        };
+        let Some(name) = profile else {
+            return Ok(defaults);
+        };
+
+        let Some(profile) = self.profiles.get(name) else {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            return Err(if known.is_empty() {
+                format!("Unknown profile '{}'; the config file defines none", name)
+            } else {
+                format!(
+                    "Unknown profile '{}'; the config file defines: {}",
+                    name,
+                    known.join(", ")
+                )
+            });
+        };
+        if profile.default_category.is_some() {
+            defaults.default_category = profile.default_category.clone();
+        }
+        if profile.default_pattern.is_some() {
+            defaults.default_pattern = profile.default_pattern.clone();
+        }
+
+        Ok(defaults)
+    }
+
+    pub fn queue_weights(&self) -> Weights {
+        Weights {
+            staleness: self.queue_weight_staleness,
+            review: self.queue_weight_review,
+            difficulty: self.queue_weight_difficulty,
+            priority: self.queue_weight_priority,
+        }
+    }
+
+    /// The built-in inference keywords with the configured ones applied
+    pub fn pattern_keywords(&self) -> BTreeMap<String, String> {
+        let mut keywords = infer::builtin_keywords();
+        for (keyword, pattern) in &self.pattern_keywords {
+            keywords.insert(keyword.to_lowercase(), pattern.clone());
+        }
+        keywords
+    }
+
+    pub fn points(&self) -> Points {
+        Points {
+            easy: self.points_easy,
+            medium: self.points_medium,
+            hard: self.points_hard,
+        }
+    }
+
+    /// The config with environment overrides applied, as `config --show`
+    /// prints it
+    pub fn effective(mut self) -> Self {
+        if let Some(name) = env::var("SHIKARI_TZ").ok().filter(|name| !name.is_empty()) {
+            self.timezone = Some(name);
+        }
+        self
+    }
+
+    /// `$SHIKARI_TZ` if set, else the configured zone, else the system's
+    pub fn zone(&self) -> Result<Zone, String> {
+        let name = env::var("SHIKARI_TZ")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .or_else(|| self.timezone.clone());
+        match name {
+            Some(name) => clock::parse_zone(&name).map(Zone::Named),
+            None => Ok(Zone::System),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let points = Points::default();
+        let weights = Weights::default();
+        Config {
+            memory_half_life_days: 14.0,
+            auto_backup: false,
+            backup_interval_hours: 24.0,
+            max_backups: 7,
+            max_description_length: 500,
+            default_category: None,
+            default_pattern: None,
+            profiles: BTreeMap::new(),
+            targets: BTreeMap::new(),
+            pattern_keywords: BTreeMap::new(),
+            time_format: TimeFormat::Minutes,
+            timezone: None,
+            points_easy: points.easy,
+            points_medium: points.medium,
+            points_hard: points.hard,
+            auto_review_minutes: None,
+            default_list_sort: None,
+            default_list_desc: false,
+            review_max: None,
+            trash_retention_days: None,
+            post_write_hook: None,
+            queue_weight_staleness: weights.staleness,
+            queue_weight_review: weights.review,
+            queue_weight_difficulty: weights.difficulty,
+            queue_weight_priority: weights.priority,
+        }
+    }
+}
+
+/// `$SHIKARI_CONFIG` if set, otherwise `shikari/config.toml` under
+/// `$XDG_CONFIG_HOME` or `~/.config`
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("SHIKARI_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("shikari").join("config.toml"))
+}
+
+pub fn parse(contents: &str) -> Result<Config, Box<dyn Error>> {
+    let config: Config = toml::from_str(contents)?;
+    if config.memory_half_life_days <= 0.0 {
+        return Err("memory_half_life_days must be positive".into());
+    }
+    // Beyond this a backup would never be due, and the Duration overflows
+    let max_interval_hours = clock::MAX_DAYS as f64 * 24.0;
+    if !(0.0..=max_interval_hours).contains(&config.backup_interval_hours) {
+        return Err(format!(
+            "backup_interval_hours must be between 0 and {}",
+            max_interval_hours
+        )
+        .into());
+    }
+    if config.max_backups == 0 {
+        return Err("max_backups must be at least 1".into());
+    }
+    if config.max_description_length == 0 {
+        return Err("max_description_length must be at least 1".into());
+    }
+    if config.points_easy < 0 || config.points_medium < 0 || config.points_hard < 0 {
+        return Err("points_easy, points_medium and points_hard cannot be negative".into());
+    }
+    if config
+        .auto_review_minutes
+        .is_some_and(|minutes| minutes <= 0)
+    {
+        return Err("auto_review_minutes must be positive".into());
+    }
+    if config.review_max == Some(0) {
+        return Err("review_max must be at least 1".into());
+    }
+    if config
+        .trash_retention_days
+        .is_some_and(|days| !(1..=clock::MAX_DAYS).contains(&days))
+    {
+        return Err(format!(
+            "trash_retention_days must be between 1 and {}",
+            clock::MAX_DAYS
+        )
+        .into());
+    }
+    if config
+        .post_write_hook
+        .as_deref()
+        .is_some_and(|hook| hook.trim().is_empty())
+    {
+        return Err("post_write_hook cannot be blank".into());
+    }
+    if config.targets.values().any(|target| *target == 0) {
+        return Err("targets must be at least 1".into());
+    }
+    if config
+        .pattern_keywords
+        .keys()
+        .any(|keyword| keyword.trim().is_empty())
+    {
+        return Err("pattern_keywords cannot have a blank keyword".into());
+    }
+    let weights = config.queue_weights();
+    if [
+        weights.staleness,
+        weights.review,
+        weights.difficulty,
+        weights.priority,
+    ]
+    .iter()
+    .any(|weight| !weight.is_finite() || *weight < 0.0)
+    {
+        return Err("queue_weight_* values must be finite and not negative".into());
+    }
+    if let Some(name) = &config.timezone {
+        clock::parse_zone(name)?;
+    }
+
+    Ok(config)
+}
+
+/// Reads the config file, or the defaults when there isn't one
+pub fn load() -> Result<Config, Box<dyn Error>> {
+    let Some(path) = path().filter(|path| path.exists()) else {
+        return Ok(Config::default());
+    };
+
+    let contents = fs::read_to_string(&path)?;
+    parse(&contents).map_err(|error| format!("{}: {}", path.display(), error).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_keys_use_defaults() {
+        let config = parse("").unwrap();
+        assert_eq!(config.memory_half_life_days, 14.0);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        assert!(parse("memory_half_life = 3").is_err());
+        assert!(parse("memory_half_life_days = 0").is_err());
+        assert!(parse("max_backups = 0").is_err());
+        assert!(parse("backup_interval_hours = -1.0").is_err());
+        assert!(parse("backup_interval_hours = nan").is_err());
+        assert!(parse("backup_interval_hours = 1e300").is_err());
+        assert!(parse("review_max = 0").is_err());
+        assert!(parse("trash_retention_days = 4000000000").is_err());
+        assert!(parse("post_write_hook = \" \"").is_err());
+        assert!(parse("targets = { graphs = 0 }").is_err());
+        assert!(parse("[pattern_keywords]\n\" \" = \"graph\"").is_err());
+        assert!(parse("time_format = \"hours\"").is_err());
+        assert!(parse("timezone = \"Eastern\"").is_err());
+        assert!(parse("points_hard = -1").is_err());
+        assert!(parse("auto_review_minutes = 0").is_err());
+        assert!(parse("queue_weight_review = -1.0").is_err());
+        assert!(parse("queue_weight_staleness = nan").is_err());
+        assert!(parse("queue_weight_priority = inf").is_err());
+        assert!(parse("default_list_sort = \"title\"").is_err());
+        assert_eq!(
+            parse("default_list_sort = \"personal-difficulty\"")
+                .unwrap()
+                .default_list_sort,
+            Some(SortField::PersonalDifficulty)
+        );
+        assert_eq!(
+            parse("queue_weight_priority = 4.0")
+                .unwrap()
+                .queue_weights()
+                .priority,
+            4.0
+        );
+        assert_eq!(parse("points_hard = 8").unwrap().points().hard, 8);
+        assert_eq!(parse("").unwrap().points().medium, 3);
+        assert!(parse("timezone = \"America/New_York\"").is_ok());
+        assert_eq!(
+            parse("time_format = \"compact\"").unwrap().time_format,
+            TimeFormat::Compact
+        );
+        assert_eq!(
+            parse("memory_half_life_days = 3.5")
+                .unwrap()
+                .memory_half_life_days,
+            3.5
+        );
+    }
+
+    #[test]
+    fn profiles_override_top_level_defaults() {
+        let config = parse(
+            "default_category = \"Arrays\"
+            default_pattern = \"two pointers\"
+
+            [profiles.graphs-week]
+            default_category = \"Graphs\"",
+        )
+        .unwrap();
+
+        let defaults = config.add_defaults(Some("graphs-week")).unwrap();
+        assert_eq!(defaults.default_category.as_deref(), Some("Graphs"));
+        assert_eq!(defaults.default_pattern.as_deref(), Some("two pointers"));
+        let defaults = config.add_defaults(None).unwrap();
+        assert_eq!(defaults.default_category.as_deref(), Some("Arrays"));
+        assert!(config.add_defaults(Some("dp-week")).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::schema::{self, SCHEMA_VERSION};
+use crate::{apply_key, MEMORY_DATABASE};
+use rusqlite::{Connection, OpenFlags};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            Status::Ok => "OK",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        };
+        write!(f, "{:<6}", format!("[{}]", label))
+    }
+}
+
+fn report(status: Status, message: impl fmt::Display) -> Status {
+    println!("{} {}", status, message);
+    status
+}
+
+pub fn resolve(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| std::env::current_dir().map(|dir| dir.join(path)))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn check_sqlite_version() -> Status {
+    match schema::check_sqlite_version() {
+        Ok(()) => report(Status::Ok, format_args!("SQLite {}", rusqlite::version())),
+        Err(error) => report(Status::Fail, error),
+    }
+}
+
+fn check_file(path: &Path) -> Status {
+    if !path.exists() {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        return if parent.is_dir() {
+            report(
+                Status::Warn,
+                "Database file does not exist yet; it will be created on first use",
+            )
+        } else {
+            report(
+                Status::Warn,
+                format_args!(
+                    "Directory {} does not exist yet; it will be created on first use",
+                    parent.display()
+                ),
+            )
+        };
+    }
+
+    match OpenOptions::new().write(true).open(path) {
+        Ok(_) => report(Status::Ok, "Database file exists and is writable"),
+        Err(error) => report(
+            Status::Fail,
+            format_args!("Database file is not writable: {}", error),
+        ),
+    }
+}
+
+fn check_contents(path: &Path, key: Option<&str>) -> Status {
+    let conn = match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| apply_key(&conn, key).map(|()| conn))
+    {
+        Ok(conn) => conn,
+        Err(error) => {
+            return report(
+                Status::Fail,
+                format_args!("Cannot open database: {}", error),
+            );
+        }
+    };
+
+    let schema = match schema::user_version(&conn) {
+        Ok(version) if version == SCHEMA_VERSION => {
+            report(Status::Ok, format_args!("Schema version {}", version))
+        }
+        Ok(version) if version < SCHEMA_VERSION => report(
+            Status::Warn,
+            format_args!(
+                "Schema version {} will be upgraded to {} on next use",
+                version, SCHEMA_VERSION
+            ),
+        ),
+        Ok(version) => report(
+            Status::Fail,
+            format_args!(
+                "Schema version {} is newer than this version of shikari supports ({})",
+                version, SCHEMA_VERSION
+            ),
+        ),
+        Err(error) => report(
+            Status::Fail,
+            format_args!("Cannot read schema version: {}", error),
+        ),
+    };
+
+    let rows = match conn.query_row("SELECT COUNT(*) FROM problems", [], |row| {
+        row.get::<_, i64>(0)
+    }) {
+        Ok(count) => report(Status::Ok, format_args!("{} problems stored", count)),
+        // A database awaiting migration may not have the table yet
+        Err(error) => report(
+            if schema == Status::Warn {
+                Status::Warn
+            } else {
+                Status::Fail
+            },
+            format_args!("Cannot count problems: {}", error),
+        ),
+    };
+
+    if schema == Status::Fail || rows == Status::Fail {
+        Status::Fail
+    } else {
+        schema
+    }
+}
+
+/// Prints each check's result and returns whether none of them failed
+pub fn run(database: &Path, key: Option<&str>) -> bool {
+    let in_memory = database.as_os_str() == MEMORY_DATABASE;
+    if !in_memory {
+        println!("Database: {}", resolve(database).display());
+    }
+
+    let mut statuses = vec![check_sqlite_version()];
+    if in_memory {
+        statuses.push(report(
+            Status::Warn,
+            "Using an in-memory database; nothing will be saved",
+        ));
+    } else {
+        statuses.push(check_file(database));
+        if database.exists() {
+            statuses.push(check_contents(database, key));
+        }
+    }
+
+    !statuses.contains(&Status::Fail)
+}
@@ -0,0 +1,888 @@
+use crate::{humanize, Problem};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+
+/// Version of the JSON export layout. Version 1 was a bare array of problems;
+/// version 3 added each problem's attempts.
+pub const EXPORT_VERSION: u32 = 3;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    /// One compact JSON object per problem per line
+    Jsonl,
+    Csv,
+    Tsv,
+    /// Front/back flashcards for Anki's text import
+    Anki,
+    /// SQL statements rebuilding the whole database, for `sqlite3 new.db < dump.sql`
+    Sql,
+    /// A self-contained web page with a sortable table of problems
+    Html,
+}
+
+/// Output format of `export-attempts`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum AttemptsFormat {
+    Csv,
+    Json,
+}
+
+/// One attempt at a problem, a row of `export-attempts`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AttemptRow {
+    pub problem_id: i64,
+    pub attempt_number: usize,
+    pub minutes: f64,
+    /// Unknown for times recorded before attempts were logged with dates
+    pub solved_at: Option<DateTime<Utc>>,
+    pub solved: bool,
+}
+
+pub const ATTEMPT_COLUMNS: [&str; 5] = [
+    "problem_id",
+    "attempt_number",
+    "minutes",
+    "solved_at",
+    "solved",
+];
+
+pub fn write_attempts(
+    format: AttemptsFormat,
+    rows: &[AttemptRow],
+    pretty: bool,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    if let AttemptsFormat::Json = format {
+        return write_value(&rows, pretty, out);
+    }
+
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(ATTEMPT_COLUMNS)?;
+    for row in rows {
+        writer.write_record([
+            row.problem_id.to_string(),
+            row.attempt_number.to_string(),
+            row.minutes.to_string(),
+            row.solved_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            (row.solved as i64).to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// How a problem listing is printed
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ListingFormat {
+    /// The usual human-readable listing
+    Plain,
+    /// A heading per problem with its details as bullets
+    Markdown,
+    /// The same document `export --format json` writes
+    Json,
+}
+
+/// Dialect settings for delimited exports, and the columns every format
+/// leaves out
+pub struct CsvOptions {
+    /// Write the row of column names first
+    pub header: bool,
+    /// Field separator for CSV; TSV always uses tabs
+    pub delimiter: u8,
+    /// `CSV_COLUMNS` indexes to omit, e.g. comments before sharing a list
+    pub exclude: Vec<usize>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            header: true,
+            delimiter: b',',
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// `CSV_COLUMNS` indexes still written, in order
+    fn columns(&self) -> Vec<usize> {
+        (0..CSV_COLUMNS.len())
+            .filter(|index| !self.exclude.contains(index))
+            .collect()
+    }
+
+    fn includes(&self, column: &str) -> bool {
+        !self
+            .exclude
+            .iter()
+            .any(|&index| CSV_COLUMNS[index] == column)
+    }
+}
+
+/// Parses a `--delimiter` value: one ASCII character, or `\t` for a tab
+pub fn parse_delimiter(value: &str) -> Result<u8, String> {
+    let delimiter = match value {
+        "\\t" => b'\t',
+        _ if value.len() == 1 => value.as_bytes()[0],
+        _ => return Err(format!("'{}' is not a single-byte delimiter", value)),
+    };
+    if matches!(delimiter, b'"' | b'\n' | b'\r') {
+        return Err(format!(
+            "{:?} cannot be used as a delimiter",
+            delimiter as char
+        ));
+    }
+
+    Ok(delimiter)
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a, T> {
+    version: u32,
+    problems: &'a [T],
+}
+
+/// Every distinct label in use, for editors and shells to offer as
+/// completions
+#[derive(Debug, Serialize)]
+pub struct Meta {
+    pub categories: Vec<String>,
+    pub patterns: Vec<String>,
+    pub tags: Vec<String>,
+    pub difficulties: Vec<String>,
+}
+
+pub const CSV_COLUMNS: [&str; 18] = [
+    "id",
+    "description",
+    "link",
+    "category",
+    "pattern",
+    "difficulty",
+    "time_to_solve_1st",
+    "time_to_solve_2nd",
+    "time_to_solve_3rd",
+    "comments",
+    "should_solve_again",
+    "company",
+    "frequency",
+    "personal_difficulty",
+    "links",
+    "locked",
+    "source",
+    "focus",
+];
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+/// The problem's values in `CSV_COLUMNS` order, empty for nulls
+pub fn csv_fields(problem: &Problem) -> Vec<String> {
+    vec![
+        optional(&problem.id),
+        problem.description.clone(),
+        optional(&problem.link),
+        optional(&problem.category),
+        optional(&problem.pattern),
+        optional(&problem.difficulty),
+        optional(&problem.time_to_solve_1st),
+        optional(&problem.time_to_solve_2nd),
+        optional(&problem.time_to_solve_3rd),
+        optional(&problem.comments),
+        (problem.should_solve_again as i64).to_string(),
+        optional(&problem.company),
+        optional(&problem.frequency),
+        optional(&problem.personal_difficulty),
+        problem
+            .all_links()
+            .iter()
+            .map(|link| format!("{}={}", link.label, link.url))
+            .collect::<Vec<_>>()
+            .join(" "),
+        (problem.locked as i64).to_string(),
+        problem.source.clone(),
+        (problem.focused as i64).to_string(),
+    ]
+}
+
+/// The problem as a JSON object without the excluded columns' keys. The
+/// attempt log goes with any of the time columns, since it repeats them.
+fn project(problem: &Problem, options: &CsvOptions) -> Result<serde_json::Value, Box<dyn Error>> {
+    let mut value = serde_json::to_value(problem)?;
+    if let Some(object) = value.as_object_mut() {
+        for &index in &options.exclude {
+            let key = match CSV_COLUMNS[index] {
+                "focus" => "focused",
+                column => column,
+            };
+            object.remove(key);
+            if key.starts_with("time_to_solve") {
+                object.remove("attempts");
+            }
+        }
+    }
+    Ok(value)
+}
+
+/// Parses a `CSV_COLUMNS` name into its index
+pub fn parse_column(name: &str) -> Result<usize, String> {
+    CSV_COLUMNS
+        .iter()
+        .position(|column| *column == name.trim())
+        .ok_or_else(|| {
+            format!(
+                "unknown column '{}', valid columns are: {}",
+                name,
+                CSV_COLUMNS.join(", ")
+            )
+        })
+}
+
+/// Writes `value` as JSON on one line, or indented when `pretty`
+pub fn write_value(
+    value: &impl Serialize,
+    pretty: bool,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    if pretty {
+        serde_json::to_writer_pretty(&mut *out, value)?;
+    } else {
+        serde_json::to_writer(&mut *out, value)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+pub fn write_json<T: Serialize>(
+    problems: &[T],
+    pretty: bool,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let export = JsonExport {
+        version: EXPORT_VERSION,
+        problems,
+    };
+    write_value(&export, pretty, out)
+}
+
+/// One problem per line, without the version wrapper, for streaming tools
+/// like `jq -c`
+pub fn write_jsonl<T: Serialize>(
+    problems: &[T],
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    for problem in problems {
+        serde_json::to_writer(&mut *out, problem)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Fields are quoted when they contain the chosen delimiter, a quote or a
+/// line break
+pub fn write_csv(
+    problems: &[Problem],
+    options: &CsvOptions,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .from_writer(out);
+    let columns = options.columns();
+    if options.header {
+        writer.write_record(columns.iter().map(|&index| CSV_COLUMNS[index]))?;
+    }
+    for problem in problems {
+        let fields = csv_fields(problem);
+        writer.write_record(columns.iter().map(|&index| &fields[index]))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A cram sheet: `title` as the heading, then a section per problem with
+/// its labels, links, times and tags as bullets and its comments quoted
+pub fn write_markdown(
+    problems: &[Problem],
+    title: &str,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "# {} ({})", title, problems.len())?;
+    for problem in problems {
+        writeln!(
+            out,
+            "\n## #{} {} ({})\n",
+            problem.id.unwrap_or(0),
+            problem.description,
+            problem.difficulty.as_deref().unwrap_or("Unknown")
+        )?;
+        for (label, value) in [
+            ("Category", &problem.category),
+            ("Pattern", &problem.pattern),
+        ] {
+            if let Some(value) = value {
+                writeln!(out, "- {}: {}", label, value)?;
+            }
+        }
+        for link in problem.all_links() {
+            writeln!(out, "- <{}> ({})", link.url, link.label)?;
+        }
+        let times: Vec<String> = [
+            problem.time_to_solve_1st,
+            problem.time_to_solve_2nd,
+            problem.time_to_solve_3rd,
+        ]
+        .iter()
+        .map(|time| time.map_or("-".to_string(), humanize::minutes))
+        .collect();
+        writeln!(out, "- Solve times: {}", times.join(", "))?;
+        if !problem.tags.is_empty() {
+            writeln!(out, "- Tags: {}", problem.tags.join(", "))?;
+        }
+        if let Some(comments) = &problem.comments {
+            writeln!(out)?;
+            for line in comments.lines() {
+                writeln!(out, "> {}", line)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// One line per problem, with tabs and newlines inside fields turned into
+/// spaces so every record splits cleanly on tabs
+pub fn write_tsv(
+    problems: &[Problem],
+    options: &CsvOptions,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let columns = options.columns();
+    if options.header {
+        let header: Vec<&str> = columns.iter().map(|&index| CSV_COLUMNS[index]).collect();
+        writeln!(out, "{}", header.join("\t"))?;
+    }
+    for problem in problems {
+        let all = csv_fields(problem);
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|&index| all[index].replace(['\t', '\r', '\n'], " "))
+            .collect();
+        writeln!(out, "{}", fields.join("\t"))?;
+    }
+    Ok(())
+}
+
+/// Escapes text for an HTML Anki field: markup characters become entities,
+/// newlines become `<br>` and tabs, which would split the field, spaces
+fn anki_field(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace("\r\n", "\n")
+        .replace(['\n', '\r'], "<br>")
+        .replace('\t', " ")
+}
+
+/// Two tab-separated fields per problem: the description and link on the
+/// front, the pattern, difficulty and comments on the back. The `#` header
+/// tells Anki the separator and that fields hold HTML.
+pub fn write_anki(
+    problems: &[Problem],
+    options: &CsvOptions,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    if options.header {
+        writeln!(out, "#separator:tab")?;
+        writeln!(out, "#html:true")?;
+        writeln!(out, "#columns:Front\tBack")?;
+    }
+    for problem in problems {
+        let mut front = Vec::new();
+        if options.includes("description") {
+            front.push(anki_field(&problem.description));
+        }
+        if let Some(url) = problem.problem_url().filter(|_| options.includes("link")) {
+            front.push(anki_field(&url));
+        }
+
+        let mut back = Vec::new();
+        if let Some(pattern) = problem
+            .pattern
+            .as_ref()
+            .filter(|_| options.includes("pattern"))
+        {
+            back.push(format!("Pattern: {}", anki_field(pattern)));
+        }
+        if let Some(difficulty) = problem
+            .difficulty
+            .as_ref()
+            .filter(|_| options.includes("difficulty"))
+        {
+            back.push(format!("Difficulty: {}", anki_field(difficulty)));
+        }
+        if let Some(comments) = problem
+            .comments
+            .as_ref()
+            .filter(|_| options.includes("comments"))
+        {
+            back.push(anki_field(comments));
+        }
+
+        writeln!(out, "{}\t{}", front.join("<br>"), back.join("<br>"))?;
+    }
+    Ok(())
+}
+
+/// Escapes text for HTML element content and quoted attribute values
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Whether a link is safe to follow from the page: anything but http(s),
+/// such as `javascript:`, is shown as text instead
+fn is_web_url(url: &str) -> bool {
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    matches!(scheme.as_deref(), Some("http" | "https"))
+}
+
+/// `CSV_COLUMNS` shown in the HTML table, with their headings
+const HTML_COLUMNS: [(&str, &str); 9] = [
+    ("id", "#"),
+    ("description", "Problem"),
+    ("category", "Category"),
+    ("pattern", "Pattern"),
+    ("difficulty", "Difficulty"),
+    ("time_to_solve_1st", "1st"),
+    ("time_to_solve_2nd", "2nd"),
+    ("time_to_solve_3rd", "3rd"),
+    ("should_solve_again", "Review"),
+];
+
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Shikari progress</title>
+<style>
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 0.4rem 0.6rem; border-bottom: 1px solid #ddd; text-align: left; }
+th { cursor: pointer; user-select: none; background: #f4f4f4; }
+th[aria-sort="ascending"]::after { content: " \25B2"; }
+th[aria-sort="descending"]::after { content: " \25BC"; }
+tr:hover td { background: #fafafa; }
+</style>
+</head>
+<body>
+"#;
+
+/// Sorts by a column when its heading is clicked, numerically when every
+/// value is a number, and the other way round on a second click
+const HTML_SCRIPT: &str = r#"<script>
+document.querySelectorAll("th").forEach((th, column) => th.addEventListener("click", () => {
+  const body = th.closest("table").tBodies[0];
+  const ascending = th.getAttribute("aria-sort") !== "ascending";
+  const key = row => row.cells[column].dataset.sort ?? row.cells[column].textContent;
+  const rows = Array.from(body.rows);
+  const numeric = rows.every(row => key(row) === "" || !isNaN(key(row)));
+  rows.sort((a, b) => {
+    const [x, y] = [key(a), key(b)];
+    if (x === "" || y === "") return (x === "") - (y === "");
+    const order = numeric ? x - y : x.localeCompare(y);
+    return ascending ? order : -order;
+  });
+  th.parentNode.querySelectorAll("th").forEach(other => other.removeAttribute("aria-sort"));
+  th.setAttribute("aria-sort", ascending ? "ascending" : "descending");
+  body.append(...rows);
+}));
+</script>
+"#;
+
+/// A self-contained page with a table of the problems that sorts by any
+/// column when its heading is clicked. Everything from the data is escaped.
+pub fn write_html(
+    problems: &[Problem],
+    options: &CsvOptions,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let columns: Vec<(&str, &str)> = HTML_COLUMNS
+        .into_iter()
+        .filter(|(column, _)| options.includes(column))
+        .collect();
+
+    write!(out, "{}", HTML_HEAD)?;
+    writeln!(
+        out,
+        "<h1>Shikari progress ({} problems)</h1>",
+        problems.len()
+    )?;
+    writeln!(out, "<table>\n<thead><tr>")?;
+    for (_, heading) in &columns {
+        writeln!(out, "<th>{}</th>", heading)?;
+    }
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    for problem in problems {
+        writeln!(out, "<tr>")?;
+        for (column, _) in &columns {
+            let time = |minutes: Option<f64>| match minutes {
+                Some(minutes) => format!(
+                    "<td data-sort=\"{}\">{}</td>",
+                    minutes,
+                    html_escape(&humanize::minutes(minutes))
+                ),
+                None => "<td data-sort=\"\"></td>".to_string(),
+            };
+            let text = |value: &Option<String>| {
+                format!(
+                    "<td>{}</td>",
+                    html_escape(value.as_deref().unwrap_or_default())
+                )
+            };
+            let cell = match *column {
+                "id" => format!("<td>{}</td>", optional(&problem.id)),
+                "description" => match problem.problem_url().filter(|_| options.includes("link")) {
+                    Some(url) if is_web_url(&url) => format!(
+                        "<td><a href=\"{}\">{}</a></td>",
+                        html_escape(&url),
+                        html_escape(&problem.description)
+                    ),
+                    Some(url) => format!(
+                        "<td>{} ({})</td>",
+                        html_escape(&problem.description),
+                        html_escape(&url)
+                    ),
+                    None => format!("<td>{}</td>", html_escape(&problem.description)),
+                },
+                "category" => text(&problem.category),
+                "pattern" => text(&problem.pattern),
+                "difficulty" => text(&problem.difficulty),
+                "time_to_solve_1st" => time(problem.time_to_solve_1st),
+                "time_to_solve_2nd" => time(problem.time_to_solve_2nd),
+                "time_to_solve_3rd" => time(problem.time_to_solve_3rd),
+                _ => format!(
+                    "<td>{}</td>",
+                    if problem.should_solve_again {
+                        "yes"
+                    } else {
+                        ""
+                    }
+                ),
+            };
+            writeln!(out, "{}", cell)?;
+        }
+        writeln!(out, "</tr>")?;
+    }
+    writeln!(out, "</tbody>\n</table>")?;
+    write!(out, "{}", HTML_SCRIPT)?;
+    writeln!(out, "</body>\n</html>")?;
+    Ok(())
+}
+
+/// A value as an SQL literal: text quoted with its quotes doubled, blobs
+/// in hex, and infinities as out-of-range reals the way `sqlite3 .dump`
+/// writes them
+fn sql_literal(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => "NULL".to_string(),
+        ValueRef::Integer(number) => number.to_string(),
+        ValueRef::Real(number) if number.is_nan() => "NULL".to_string(),
+        ValueRef::Real(number) if number.is_infinite() => {
+            if number > 0.0 { "1e999" } else { "-1e999" }.to_string()
+        }
+        // Debug keeps the ".0" that tells SQLite it's a real
+        ValueRef::Real(number) => format!("{:?}", number),
+        ValueRef::Text(text) => format!("'{}'", String::from_utf8_lossy(text).replace('\'', "''")),
+        ValueRef::Blob(bytes) => {
+            let hex: String = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            format!("X'{}'", hex)
+        }
+    }
+}
+
+fn sql_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Writes the statements that rebuild `conn`'s database: its tables, their
+/// rows, then indexes and triggers (so none fire while rows go in), and
+/// the schema version so shikari opens the copy without migrating it
+pub fn write_sql(conn: &Connection, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "PRAGMA foreign_keys=OFF;")?;
+    writeln!(out, "BEGIN TRANSACTION;")?;
+
+    let mut stmt = conn.prepare(
+        "SELECT type, name, sql FROM sqlite_master
+        WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+        ORDER BY type = 'table' DESC, rowid",
+    )?;
+    let entries = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for (kind, name, sql) in &entries {
+        writeln!(out, "{};", sql)?;
+        if kind != "table" {
+            continue;
+        }
+
+        let mut rows = conn.prepare(&format!("SELECT * FROM {}", sql_identifier(name)))?;
+        let columns = rows.column_count();
+        let mut rows = rows.query([])?;
+        while let Some(row) = rows.next()? {
+            let values = (0..columns)
+                .map(|index| row.get_ref(index).map(sql_literal))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            writeln!(
+                out,
+                "INSERT INTO {} VALUES({});",
+                sql_identifier(name),
+                values.join(",")
+            )?;
+        }
+    }
+
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    writeln!(out, "PRAGMA user_version={};", version)?;
+    writeln!(out, "COMMIT;")?;
+    Ok(())
+}
+
+/// `pretty` indents JSON; JSON Lines stays one problem per line
+pub fn write(
+    format: ExportFormat,
+    problems: &[Problem],
+    options: &CsvOptions,
+    pretty: bool,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        ExportFormat::Json | ExportFormat::Jsonl if !options.exclude.is_empty() => {
+            let projected = problems
+                .iter()
+                .map(|problem| project(problem, options))
+                .collect::<Result<Vec<_>, _>>()?;
+            match format {
+                ExportFormat::Json => write_json(&projected, pretty, out),
+                _ => write_jsonl(&projected, out),
+            }
+        }
+        ExportFormat::Json => write_json(problems, pretty, out),
+        ExportFormat::Jsonl => write_jsonl(problems, out),
+        ExportFormat::Csv => write_csv(problems, options, out),
+        ExportFormat::Tsv => write_tsv(problems, options, out),
+        ExportFormat::Anki => write_anki(problems, options, out),
+        ExportFormat::Html => write_html(problems, options, out),
+        ExportFormat::Sql => {
+            Err("SQL dumps are written from the database, not a problem list".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_byte_delimiters() {
+        assert_eq!(parse_delimiter(";"), Ok(b';'));
+        assert_eq!(parse_delimiter("\\t"), Ok(b'\t'));
+        assert!(parse_delimiter(";;").is_err());
+        assert!(parse_delimiter("§").is_err());
+        assert!(parse_delimiter("\"").is_err());
+    }
+
+    #[test]
+    fn jsonl_writes_one_problem_per_line() {
+        let problems = [Problem::new("Two Sum"), Problem::new("Three Sum")];
+
+        let mut out = Vec::new();
+        write_jsonl(&problems, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let second: Problem = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second.description, "Three Sum");
+    }
+
+    #[test]
+    fn json_is_compact_unless_pretty() {
+        let problems = [Problem::new("Two Sum"), Problem::new("Three Sum")];
+        let written = |format, pretty| {
+            let mut out = Vec::new();
+            write(format, &problems, &CsvOptions::default(), pretty, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let compact = written(ExportFormat::Json, false);
+        assert_eq!(compact.lines().count(), 1);
+        let pretty = written(ExportFormat::Json, true);
+        assert!(pretty.lines().count() > 2);
+        assert!(pretty.contains("\n  \"version\""));
+        let parsed = |text: &str| serde_json::from_str::<serde_json::Value>(text).unwrap();
+        assert_eq!(parsed(&compact), parsed(&pretty));
+
+        // JSON Lines keeps a problem per line either way
+        assert_eq!(written(ExportFormat::Jsonl, true).lines().count(), 2);
+    }
+
+    #[test]
+    fn anki_cards_escape_html_tabs_and_newlines() {
+        let mut problem = Problem::new("Two Sum <easy>");
+        problem.link = Some("https://leetcode.com/problems/two-sum/".to_string());
+        problem.pattern = Some("hash map".to_string());
+        problem.comments = Some("store\tcomplements\nin one pass".to_string());
+
+        let mut out = Vec::new();
+        write_anki(&[problem], &CsvOptions::default(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let card = text.lines().last().unwrap();
+
+        assert_eq!(
+            card,
+            "Two Sum &lt;easy&gt;<br>https://leetcode.com/problems/two-sum/\t\
+            Pattern: hash map<br>store complements<br>in one pass"
+        );
+    }
+
+    #[test]
+    fn html_pages_escape_user_fields() {
+        let mut problem = Problem::new("<script>alert(1)</script>");
+        problem.link = Some("https://example.com/?a=1&b=\"2\"".to_string());
+        problem.pattern = Some("R&D".to_string());
+        problem.time_to_solve_1st = Some(12.0);
+
+        let mut out = Vec::new();
+        write_html(&[problem], &CsvOptions::default(), &mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+
+        assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!page.contains("<script>alert"));
+        assert!(page.contains("href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\""));
+        assert!(page.contains("<td>R&amp;D</td>"));
+        assert!(page.contains("<td data-sort=\"12\">"));
+        assert!(page.trim_end().ends_with("</html>"));
+
+        let mut problem = Problem::new("Two Sum");
+        problem.link = Some("JavaScript:alert(1)".to_string());
+        let mut out = Vec::new();
+        write_html(&[problem], &CsvOptions::default(), &mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+        assert!(!page.contains("href=\"JavaScript"));
+        assert!(page.contains("<td>Two Sum (JavaScript:alert(1))</td>"));
+    }
+
+    #[test]
+    fn excluded_columns_are_left_out_of_every_format() {
+        let mut problem = Problem::new("Two Sum");
+        problem.comments = Some("private".to_string());
+        problem.time_to_solve_1st = Some(12.0);
+        let options = CsvOptions {
+            exclude: vec![
+                parse_column("comments").unwrap(),
+                parse_column("time_to_solve_1st").unwrap(),
+            ],
+            ..CsvOptions::default()
+        };
+
+        for format in [
+            ExportFormat::Json,
+            ExportFormat::Jsonl,
+            ExportFormat::Csv,
+            ExportFormat::Tsv,
+            ExportFormat::Anki,
+        ] {
+            let mut out = Vec::new();
+            write(
+                format,
+                std::slice::from_ref(&problem),
+                &options,
+                false,
+                &mut out,
+            )
+            .unwrap();
+            let text = String::from_utf8(out).unwrap();
+            assert!(!text.contains("private"), "{:?}: {}", format, text);
+            assert!(!text.contains("12"), "{:?}: {}", format, text);
+            assert!(text.contains("Two Sum"));
+        }
+    }
+
+    #[test]
+    fn quotes_fields_containing_the_chosen_delimiter() {
+        let mut problem = Problem::new("Two Sum; hash map, easy");
+        problem.id = Some(1);
+        let options = CsvOptions {
+            header: false,
+            delimiter: b';',
+            ..CsvOptions::default()
+        };
+
+        let mut out = Vec::new();
+        write_csv(&[problem], &options, &mut out).unwrap();
+        let line = String::from_utf8(out).unwrap();
+
+        assert!(line.starts_with("1;\"Two Sum; hash map, easy\";"));
+    }
+
+    #[test]
+    fn sql_dump_rebuilds_the_database() {
+        let source = Connection::open_in_memory().unwrap();
+        source
+            .execute_batch(
+                "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT, score REAL, raw BLOB);
+                CREATE INDEX notes_body ON notes (body);
+                CREATE TRIGGER notes_frozen BEFORE DELETE ON notes BEGIN SELECT RAISE(ABORT, 'no'); END;
+                PRAGMA user_version = 7;",
+            )
+            .unwrap();
+        source
+            .execute(
+                "INSERT INTO notes VALUES (1, ?, 1.0, x'00ff'), (2, NULL, NULL, NULL)",
+                ["it's \"quoted\";\nnext line"],
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        write_sql(&source, &mut out).unwrap();
+        let dump = String::from_utf8(out).unwrap();
+        assert!(dump.contains("VALUES(1,'it''s \"quoted\";\nnext line',1.0,X'00FF');"));
+        assert!(dump.contains("VALUES(2,NULL,NULL,NULL);"));
+
+        let copy = Connection::open_in_memory().unwrap();
+        copy.execute_batch(&dump).unwrap();
+        let (body, score): (String, f64) = copy
+            .query_row("SELECT body, score FROM notes WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(body, "it's \"quoted\";\nnext line");
+        assert_eq!(score, 1.0);
+        let version: i64 = copy
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 7);
+        assert!(copy.execute("DELETE FROM notes", []).is_err());
+    }
+}
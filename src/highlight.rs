@@ -0,0 +1,273 @@
+use termimad::crossterm::style::Stylize;
+
+/// What a stretch of code is, as far as coloring goes
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Plain,
+    Keyword,
+    Str,
+    Number,
+    Comment,
+}
+
+/// Just enough of a language's lexical rules to color it
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comment: &'static str,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+const C_FAMILY: &[&str] = &[
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "float",
+    "for",
+    "if",
+    "implements",
+    "import",
+    "int",
+    "long",
+    "namespace",
+    "new",
+    "nullptr",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "unsigned",
+    "using",
+    "var",
+    "vector",
+    "void",
+    "while",
+];
+
+/// The syntax for `language` as given to `set-solution --lang`, for the
+/// languages solutions are usually written in
+fn syntax(language: &str) -> Option<Syntax> {
+    let syntax = match language {
+        "rust" | "rs" => Syntax {
+            keywords: &[
+                "as", "break", "const", "continue", "else", "enum", "false", "fn", "for", "if",
+                "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+                "self", "Self", "struct", "trait", "true", "type", "use", "where", "while",
+            ],
+            line_comment: "//",
+            block_comment: Some(("/*", "*/")),
+            // Single quotes also start lifetimes, so only chars are missed
+            quotes: &['"'],
+        },
+        "python" | "python3" | "py" => Syntax {
+            keywords: &[
+                "and", "as", "break", "class", "continue", "def", "elif", "else", "except",
+                "False", "for", "from", "if", "import", "in", "is", "lambda", "None", "not", "or",
+                "pass", "return", "self", "True", "try", "while", "with", "yield",
+            ],
+            line_comment: "#",
+            block_comment: None,
+            quotes: &['"', '\''],
+        },
+        "go" | "golang" => Syntax {
+            keywords: &[
+                "break", "case", "const", "continue", "default", "defer", "else", "false", "for",
+                "func", "go", "if", "import", "map", "nil", "package", "range", "return", "struct",
+                "switch", "true", "type", "var",
+            ],
+            line_comment: "//",
+            block_comment: Some(("/*", "*/")),
+            quotes: &['"', '\'', '`'],
+        },
+        "javascript" | "js" | "typescript" | "ts" => Syntax {
+            keywords: &[
+                "break",
+                "case",
+                "class",
+                "const",
+                "continue",
+                "else",
+                "false",
+                "for",
+                "function",
+                "if",
+                "in",
+                "let",
+                "new",
+                "null",
+                "of",
+                "return",
+                "switch",
+                "this",
+                "true",
+                "undefined",
+                "var",
+                "while",
+            ],
+            line_comment: "//",
+            block_comment: Some(("/*", "*/")),
+            quotes: &['"', '\'', '`'],
+        },
+        "c" | "cpp" | "c++" | "cc" | "java" | "kotlin" | "csharp" | "c#" => Syntax {
+            keywords: C_FAMILY,
+            line_comment: "//",
+            block_comment: Some(("/*", "*/")),
+            quotes: &['"', '\''],
+        },
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+/// Splits `code` into colorable stretches, in order, so that joining them
+/// gives `code` back
+fn spans<'a>(code: &'a str, syntax: &Syntax) -> Vec<(Kind, &'a str)> {
+    let mut spans = Vec::new();
+    let mut plain = 0;
+    let mut start = 0;
+    while let Some(c) = code[start..].chars().next() {
+        let rest = &code[start..];
+        let (kind, len) = if rest.starts_with(syntax.line_comment) {
+            (Kind::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else if let Some((open, close)) = syntax.block_comment
+            && rest.starts_with(open)
+        {
+            let end = rest[open.len()..]
+                .find(close)
+                .map_or(rest.len(), |end| open.len() + end + close.len());
+            (Kind::Comment, end)
+        } else if syntax.quotes.contains(&c) {
+            (Kind::Str, string_len(rest, c))
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            (Kind::Number, len)
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            match syntax.keywords.contains(&&rest[..len]) {
+                true => (Kind::Keyword, len),
+                false => (Kind::Plain, len),
+            }
+        } else {
+            (Kind::Plain, c.len_utf8())
+        };
+
+        if kind != Kind::Plain {
+            if plain < start {
+                spans.push((Kind::Plain, &code[plain..start]));
+            }
+            spans.push((kind, &rest[..len]));
+            plain = start + len;
+        }
+        start += len;
+    }
+    if plain < code.len() {
+        spans.push((Kind::Plain, &code[plain..]));
+    }
+    spans
+}
+
+/// Length of the string literal at the start of `rest`, which opens with
+/// `quote`: up to its closing quote, or the end of the line if it has none.
+/// Tripled quotes, as in Python docstrings, run to the next triple.
+fn string_len(rest: &str, quote: char) -> usize {
+    let triple = quote.to_string().repeat(3);
+    if rest.starts_with(&triple) {
+        return rest[3..].find(&triple).map_or(rest.len(), |end| end + 6);
+    }
+
+    let mut escaped = false;
+    for (index, c) in rest.char_indices().skip(1) {
+        match c {
+            '\n' => return index,
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return index + 1,
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+/// `code` with keywords, strings, numbers and comments colored for a
+/// terminal. Languages without a syntax here come back unchanged.
+pub fn code(code: &str, language: Option<&str>) -> String {
+    let Some(syntax) = language.and_then(|language| syntax(&language.to_lowercase())) else {
+        return code.to_string();
+    };
+    spans(code, &syntax)
+        .into_iter()
+        .map(|(kind, text)| match kind {
+            Kind::Plain => text.to_string(),
+            Kind::Keyword => text.magenta().bold().to_string(),
+            Kind::Str => text.green().to_string(),
+            Kind::Number => text.cyan().to_string(),
+            Kind::Comment => text.dark_grey().to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_are_found_outside_strings_and_comments() {
+        let code = "def f(x):  # return x\n    return \"if\" + 'it\\'s' * 2\n";
+        let syntax = syntax("python").unwrap();
+        let spans = spans(code, &syntax);
+        assert_eq!(
+            spans.iter().map(|(_, text)| *text).collect::<String>(),
+            code
+        );
+        let colored: Vec<(Kind, &str)> = spans
+            .into_iter()
+            .filter(|(kind, _)| *kind != Kind::Plain)
+            .collect();
+        assert_eq!(
+            colored,
+            [
+                (Kind::Keyword, "def"),
+                (Kind::Comment, "# return x"),
+                (Kind::Keyword, "return"),
+                (Kind::Str, "\"if\""),
+                (Kind::Str, "'it\\'s'"),
+                (Kind::Number, "2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_languages_stay_plain() {
+        assert_eq!(code("let x = 1;", None), "let x = 1;");
+        assert_eq!(code("let x = 1;", Some("brainfuck")), "let x = 1;");
+        assert_ne!(code("let x = 1;", Some("Rust")), "let x = 1;");
+    }
+}
@@ -0,0 +1,236 @@
+use crate::clock;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How solve times are written
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeFormat {
+    /// "80min"
+    #[default]
+    Minutes,
+    /// "45m" under an hour, "1h20m" from an hour up
+    Compact,
+}
+
+/// Set once from the config and `--raw-minutes`, so `Display` impls can
+/// follow it without threading it through every call
+static COMPACT_TIMES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_time_format(format: TimeFormat) {
+    COMPACT_TIMES.store(format == TimeFormat::Compact, Ordering::Relaxed);
+}
+
+fn time_format() -> TimeFormat {
+    if COMPACT_TIMES.load(Ordering::Relaxed) {
+        TimeFormat::Compact
+    } else {
+        TimeFormat::Minutes
+    }
+}
+
+/// Minutes to at most two decimals, without trailing zeros: "12", "2.5"
+fn decimal(minutes: f64) -> String {
+    let text = format!("{:.2}", minutes);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Writes a number of minutes in the given format, keeping fractions of a
+/// minute; compact times of an hour or more round to whole minutes
+pub fn format_minutes(minutes: f64, format: TimeFormat) -> String {
+    let whole = minutes.round() as i64;
+    match format {
+        TimeFormat::Minutes => format!("{}min", decimal(minutes)),
+        TimeFormat::Compact if minutes < 59.995 => format!("{}m", decimal(minutes)),
+        TimeFormat::Compact if whole % 60 == 0 => format!("{}h", whole / 60),
+        TimeFormat::Compact => format!("{}h{}m", whole / 60, whole % 60),
+    }
+}
+
+/// Writes an average to a tenth of a minute; compact averages of an hour
+/// or more round to whole minutes, since "1h20.5m" reads badly
+pub fn format_average(minutes: f64, format: TimeFormat) -> String {
+    match format {
+        TimeFormat::Minutes => format!("{:.1}min", minutes),
+        TimeFormat::Compact if minutes < 59.95 => format!("{:.1}m", minutes),
+        TimeFormat::Compact => format_minutes(minutes.round(), format),
+    }
+}
+
+/// `format_minutes` in the format chosen for this run
+pub fn minutes(minutes: f64) -> String {
+    format_minutes(minutes, time_format())
+}
+
+/// `format_average` in the format chosen for this run
+pub fn average(minutes: f64) -> String {
+    format_average(minutes, time_format())
+}
+
+/// A long span of minutes in words, e.g. "84 hours 5 minutes"
+pub fn hours_and_minutes(minutes: i64) -> String {
+    let count = |count: i64, unit: &str| match count {
+        1 => format!("1 {}", unit),
+        _ => format!("{} {}s", count, unit),
+    };
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => count(minutes, "minute"),
+        (hours, 0) => count(hours, "hour"),
+        (hours, minutes) => format!("{} {}", count(hours, "hour"), count(minutes, "minute")),
+    }
+}
+
+/// A file size in the largest unit that keeps it at 1 or more, e.g. "48.0 KB"
+pub fn file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} bytes", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// The closing line of a bulk command: each count after its label, then
+/// how long the whole run took, e.g. "Imported 42, skipped 3 (in 0.4s)"
+pub fn bulk_summary(counts: &[(&str, usize)], elapsed: Duration) -> String {
+    let counts: Vec<String> = counts
+        .iter()
+        .map(|(label, count)| format!("{} {}", label, count))
+        .collect();
+    format!("{} (in {:.1}s)", counts.join(", "), elapsed.as_secs_f64())
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", count, unit)
+    }
+}
+
+/// Describes how long before `now` the timestamp `then` was, e.g. "3 days ago"
+pub fn time_ago(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - then;
+    let minutes = elapsed.num_minutes();
+    let hours = elapsed.num_hours();
+    let days = elapsed.num_days();
+
+    if minutes < 1 {
+        "just now".to_string()
+    } else if minutes < 60 {
+        plural(minutes, "minute")
+    } else if hours < 24 {
+        plural(hours, "hour")
+    } else if days < 7 {
+        plural(days, "day")
+    } else if days < 30 {
+        plural(days / 7, "week")
+    } else if days < 365 {
+        plural(days / 30, "month")
+    } else {
+        plural(days / 365, "year")
+    }
+}
+
+/// Relative by default, or `YYYY-MM-DD HH:MM` in the configured zone when
+/// `absolute`
+pub fn format_timestamp(timestamp: DateTime<Utc>, absolute: bool) -> String {
+    if absolute {
+        clock::format(timestamp, "%Y-%m-%d %H:%M")
+    } else {
+        time_ago(timestamp, Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn ago(elapsed: Duration) -> String {
+        let now = Utc::now();
+        time_ago(now - elapsed, now)
+    }
+
+    #[test]
+    fn compact_minutes_boundaries() {
+        let compact = |minutes| format_minutes(minutes, TimeFormat::Compact);
+        assert_eq!(compact(0.0), "0m");
+        assert_eq!(compact(2.5), "2.5m");
+        assert_eq!(compact(59.0), "59m");
+        assert_eq!(compact(60.0), "1h");
+        assert_eq!(compact(61.0), "1h1m");
+        assert_eq!(compact(80.4), "1h20m");
+        assert_eq!(compact(600.0), "10h");
+        assert_eq!(format_minutes(80.0, TimeFormat::Minutes), "80min");
+        assert_eq!(format_minutes(2.25, TimeFormat::Minutes), "2.25min");
+        assert_eq!(format_minutes(1.0 / 3.0, TimeFormat::Minutes), "0.33min");
+    }
+
+    #[test]
+    fn hours_and_minutes_spell_out_units() {
+        assert_eq!(hours_and_minutes(0), "0 minutes");
+        assert_eq!(hours_and_minutes(1), "1 minute");
+        assert_eq!(hours_and_minutes(120), "2 hours");
+        assert_eq!(hours_and_minutes(84 * 60 + 5), "84 hours 5 minutes");
+    }
+
+    #[test]
+    fn file_sizes_pick_a_unit() {
+        assert_eq!(file_size(512), "512 bytes");
+        assert_eq!(file_size(49152), "48.0 KB");
+        assert_eq!(file_size(3 * 1024 * 1024 + 512 * 1024), "3.5 MB");
+    }
+
+    #[test]
+    fn bulk_summaries_list_counts_then_duration() {
+        let summary = bulk_summary(
+            &[("Imported", 42), ("skipped", 3), ("failed", 1)],
+            std::time::Duration::from_millis(420),
+        );
+        assert_eq!(summary, "Imported 42, skipped 3, failed 1 (in 0.4s)");
+    }
+
+    #[test]
+    fn compact_averages_round_from_an_hour() {
+        let compact = |minutes| format_average(minutes, TimeFormat::Compact);
+        assert_eq!(compact(12.25), "12.2m");
+        assert_eq!(compact(59.96), "1h");
+        assert_eq!(compact(80.4), "1h20m");
+        assert_eq!(format_average(80.4, TimeFormat::Minutes), "80.4min");
+    }
+
+    #[test]
+    fn time_ago_boundaries() {
+        assert_eq!(ago(Duration::seconds(59)), "just now");
+        assert_eq!(ago(Duration::minutes(1)), "1 minute ago");
+        assert_eq!(ago(Duration::minutes(59)), "59 minutes ago");
+        assert_eq!(ago(Duration::hours(1)), "1 hour ago");
+        assert_eq!(ago(Duration::hours(23)), "23 hours ago");
+        assert_eq!(ago(Duration::days(1)), "1 day ago");
+        assert_eq!(ago(Duration::days(6)), "6 days ago");
+        assert_eq!(ago(Duration::days(7)), "1 week ago");
+        assert_eq!(ago(Duration::days(29)), "4 weeks ago");
+        assert_eq!(ago(Duration::days(30)), "1 month ago");
+        assert_eq!(ago(Duration::days(365)), "1 year ago");
+    }
+
+    #[test]
+    fn timestamps_are_relative_unless_absolute() {
+        assert_eq!(ago(Duration::days(61)), "2 months ago");
+        assert_eq!(ago(Duration::days(800)), "2 years ago");
+
+        let timestamp = Utc::now() - Duration::days(3);
+        assert_eq!(format_timestamp(timestamp, false), "3 days ago");
+        let absolute = format_timestamp(timestamp, true);
+        assert_eq!(absolute, clock::format(timestamp, "%Y-%m-%d %H:%M"));
+        assert_eq!(absolute.len(), "2026-10-15 03:00".len());
+    }
+}
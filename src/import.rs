@@ -0,0 +1,936 @@
+use crate::export::{self, EXPORT_VERSION};
+use crate::infer;
+use crate::{
+    link_slug, link_source, normalize_companies, parse_link, Problem, ProblemTracker,
+    DEFAULT_SOURCE,
+};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum DedupeField {
+    Link,
+    Description,
+    Slug,
+}
+
+impl DedupeField {
+    fn name(self) -> &'static str {
+        match self {
+            DedupeField::Link => "link",
+            DedupeField::Description => "description",
+            DedupeField::Slug => "slug",
+        }
+    }
+
+    /// The value compared for duplicates, if the problem has one
+    fn value(self, problem: &Problem) -> Option<String> {
+        match self {
+            DedupeField::Link => problem.link.clone(),
+            DedupeField::Description => Some(problem.description.trim().to_lowercase()),
+            DedupeField::Slug => problem
+                .link
+                .as_deref()
+                .and_then(link_slug)
+                .map(|slug| format!("{}/{}", problem.source, slug)),
+        }
+    }
+
+    /// The field `problem` is matched on: `dedupe_on` if given, else the
+    /// link when it has one and the description when it doesn't
+    fn for_problem(dedupe_on: Option<DedupeField>, problem: &Problem) -> DedupeField {
+        dedupe_on.unwrap_or(if problem.link.is_some() {
+            DedupeField::Link
+        } else {
+            DedupeField::Description
+        })
+    }
+}
+
+/// How to handle an incoming problem whose dedupe value already exists
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ConflictStrategy {
+    /// Keep the existing problem untouched
+    Skip,
+    /// Overwrite the existing problem's fields
+    Replace,
+    /// Fill only the existing problem's empty fields
+    Merge,
+}
+
+impl ConflictStrategy {
+    fn past_tense(self) -> &'static str {
+        match self {
+            ConflictStrategy::Skip => "Skipped",
+            ConflictStrategy::Replace => "Replaced",
+            ConflictStrategy::Merge => "Merged",
+        }
+    }
+}
+
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub replaced: usize,
+    pub merged: usize,
+    /// Problems whose missing pattern was guessed from their description
+    pub inferred: usize,
+    pub collisions: Vec<String>,
+    /// Every problem the file added or matched, in file order
+    pub ids: Vec<i64>,
+}
+
+/// Copies each of `incoming`'s fields into `existing` where it is empty
+fn fill_missing(existing: &mut Problem, incoming: Problem) {
+    let labels: Vec<String> = existing
+        .all_links()
+        .into_iter()
+        .map(|link| link.label)
+        .collect();
+    for link in incoming.all_links() {
+        if !labels.contains(&link.label) {
+            existing.set_link(link);
+        }
+    }
+    for tag in &incoming.tags {
+        existing.add_tag(tag);
+    }
+    existing.link = existing.link.take().or(incoming.link);
+    existing.category = existing.category.take().or(incoming.category);
+    existing.pattern = existing.pattern.take().or(incoming.pattern);
+    existing.difficulty = existing.difficulty.take().or(incoming.difficulty);
+    existing.time_to_solve_1st = existing.time_to_solve_1st.or(incoming.time_to_solve_1st);
+    existing.time_to_solve_2nd = existing.time_to_solve_2nd.or(incoming.time_to_solve_2nd);
+    existing.time_to_solve_3rd = existing.time_to_solve_3rd.or(incoming.time_to_solve_3rd);
+    existing.comments = existing.comments.take().or(incoming.comments);
+    existing.company = existing.company.take().or(incoming.company);
+    existing.frequency = existing.frequency.or(incoming.frequency);
+    existing.personal_difficulty = existing
+        .personal_difficulty
+        .or(incoming.personal_difficulty);
+    if existing.solution.is_none() {
+        existing.solution = incoming.solution;
+        existing.solution_language = incoming.solution_language;
+    }
+}
+
+fn parse_time(
+    value: Option<String>,
+    line: u64,
+    column: &str,
+) -> Result<Option<f64>, Box<dyn Error>> {
+    match value {
+        Some(value) => match value.parse::<f64>() {
+            Ok(minutes) if minutes.is_finite() => Ok(Some(minutes)),
+            _ => Err(format!("line {}: invalid {} '{}'", line, column, value).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_frequency(value: Option<String>, line: u64) -> Result<Option<f64>, Box<dyn Error>> {
+    match value {
+        Some(value) => match value.trim_end_matches('%').parse() {
+            Ok(frequency) => Ok(Some(frequency)),
+            Err(_) => Err(format!("line {}: invalid frequency '{}'", line, value).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_rating(value: Option<String>, line: u64) -> Result<Option<i64>, Box<dyn Error>> {
+    match value {
+        Some(value) => match value.parse() {
+            Ok(rating @ 1..=5) => Ok(Some(rating)),
+            _ => Err(format!(
+                "line {}: invalid personal_difficulty '{}', expected 1 to 5",
+                line, value
+            )
+            .into()),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_flag(value: Option<String>) -> bool {
+    matches!(
+        value.as_deref().map(str::to_lowercase).as_deref(),
+        Some("1" | "true" | "yes" | "y")
+    )
+}
+
+/// Imports larger than this show a progress bar
+const PROGRESS_THRESHOLD: usize = 500;
+
+/// Records between progress bar updates
+const PROGRESS_STEP: usize = 50;
+
+/// Problems parsed from an import file, each labelled with where it came
+/// from (e.g. `line 3`) for reporting
+type Records = Vec<(String, Problem)>;
+
+/// JSON exports are either the versioned wrapper written by `export` or the
+/// bare array written before versioning
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonImport {
+    Versioned {
+        version: u32,
+        problems: Vec<Problem>,
+    },
+    Legacy(Vec<Problem>),
+}
+
+fn read_json(contents: &str) -> Result<Records, Box<dyn Error>> {
+    let problems = match serde_json::from_str(contents)? {
+        JsonImport::Versioned { version, problems } => {
+            if version > EXPORT_VERSION {
+                return Err(format!(
+                    "Export version {} is newer than the supported version {}",
+                    version, EXPORT_VERSION
+                )
+                .into());
+            }
+            problems
+        }
+        JsonImport::Legacy(problems) => problems,
+    };
+
+    Ok(problems
+        .into_iter()
+        .enumerate()
+        .map(|(index, problem)| (format!("record {}", index + 1), problem))
+        .collect())
+}
+
+/// A `--map` pair such as `difficulty=Level`: a problem field and the CSV
+/// header to read it from
+pub fn parse_mapping(pair: &str) -> Result<(String, String), String> {
+    let (field, header) = pair
+        .split_once('=')
+        .ok_or_else(|| format!("expected field=Column, got '{}'", pair))?;
+    let index = export::parse_column(field)?;
+    if export::CSV_COLUMNS[index] == "id" {
+        return Err("id can't be mapped: imported problems get new IDs".to_string());
+    }
+    if header.trim().is_empty() {
+        return Err(format!("no column given for {}", field.trim()));
+    }
+    Ok((field.trim().to_string(), header.trim().to_string()))
+}
+
+/// Reads problems from CSV with a header row naming the problem columns, or
+/// the columns `mapping` names for them. Only `description` is required;
+/// unknown columns are ignored.
+fn read_csv(contents: &str, mapping: &[(String, String)]) -> Result<Records, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_reader(contents.as_bytes());
+    let headers = reader.headers()?.clone();
+    for (index, (field, header)) in mapping.iter().enumerate() {
+        if mapping[..index].iter().any(|(earlier, _)| earlier == field) {
+            return Err(format!("--map gives {} more than once", field).into());
+        }
+        if !headers.iter().any(|known| known.trim() == header) {
+            return Err(format!("CSV has no '{}' column to read {} from", header, field).into());
+        }
+    }
+    let column = |name: &str| {
+        let name = mapping
+            .iter()
+            .find(|(field, _)| field == name)
+            .map_or(name, |(_, header)| header.as_str());
+        headers.iter().position(|header| header.trim() == name)
+    };
+
+    let description_column = column("description")
+        .ok_or("CSV is missing a 'description' column; name one with --map description=Column")?;
+
+    let mut records = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let field = |name: &str| {
+            column(name)
+                .and_then(|index| record.get(index))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let mut problem = Problem::new(record.get(description_column).unwrap_or("").trim());
+        problem.link = field("link");
+        problem.category = field("category");
+        problem.pattern = field("pattern");
+        problem.difficulty = field("difficulty");
+        problem.time_to_solve_1st =
+            parse_time(field("time_to_solve_1st"), line, "time_to_solve_1st")?;
+        problem.time_to_solve_2nd =
+            parse_time(field("time_to_solve_2nd"), line, "time_to_solve_2nd")?;
+        problem.time_to_solve_3rd =
+            parse_time(field("time_to_solve_3rd"), line, "time_to_solve_3rd")?;
+        problem.comments = field("comments");
+        problem.should_solve_again = parse_flag(field("should_solve_again"));
+        problem.company = field("company").as_deref().and_then(normalize_companies);
+        problem.frequency = parse_frequency(field("frequency"), line)?;
+        problem.personal_difficulty = parse_rating(field("personal_difficulty"), line)?;
+        problem.locked = parse_flag(field("locked"));
+        problem.focused = parse_flag(field("focus"));
+        if let Some(source) = field("source") {
+            problem.source = source.to_lowercase();
+        }
+        for value in field("links")
+            .iter()
+            .flat_map(|links| links.split_whitespace())
+        {
+            let link = parse_link(value).map_err(|error| format!("line {}: {}", line, error))?;
+            problem.set_link(link);
+        }
+
+        records.push((format!("line {}", line), problem));
+    }
+
+    Ok(records)
+}
+
+/// Imports a JSON export or a CSV file, detected from the file's contents.
+/// `mapping` renames CSV columns and is an error for JSON. Without
+/// `dedupe_on`, each record is matched on its link, or on its description
+/// when it has no link.
+pub fn import_file(
+    tracker: &ProblemTracker,
+    path: &Path,
+    mapping: &[(String, String)],
+    dedupe_on: Option<DedupeField>,
+    on_conflict: ConflictStrategy,
+    infer_with: Option<&BTreeMap<String, String>>,
+    quiet: bool,
+) -> Result<ImportSummary, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let records = if contents.trim_start().starts_with(['[', '{']) {
+        if !mapping.is_empty() {
+            return Err("--map only applies to CSV files".into());
+        }
+        read_json(&contents)?
+    } else {
+        read_csv(&contents, mapping)?
+    };
+
+    let fields = match dedupe_on {
+        Some(field) => vec![field],
+        None => vec![DedupeField::Link, DedupeField::Description],
+    };
+    let mut existing: HashMap<(&str, String), i64> = HashMap::new();
+    for problem in tracker.get_all_problems()? {
+        for field in &fields {
+            if let (Some(value), Some(id)) = (field.value(&problem), problem.id) {
+                existing.insert((field.name(), value), id);
+            }
+        }
+    }
+
+    let mut summary = ImportSummary {
+        imported: 0,
+        skipped: 0,
+        replaced: 0,
+        merged: 0,
+        inferred: 0,
+        collisions: Vec::new(),
+        ids: Vec::new(),
+    };
+
+    let progress = if !quiet && records.len() > PROGRESS_THRESHOLD && io::stderr().is_terminal() {
+        let bar = ProgressBar::new(records.len() as u64);
+        bar.set_style(ProgressStyle::with_template(
+            "{bar:40} {pos}/{len} records (ETA {eta})",
+        )?);
+        Some(bar)
+    } else {
+        None
+    };
+
+    for (index, (source, mut problem)) in records.into_iter().enumerate() {
+        if let Some(bar) = &progress
+            && index % PROGRESS_STEP == 0
+        {
+            bar.set_position(index as u64);
+        }
+        // Only ever fills a blank pattern
+        if let Some(keywords) = infer_with
+            && problem
+                .pattern
+                .as_deref()
+                .is_none_or(|pattern| pattern.trim().is_empty())
+            && let Some(pattern) = infer::infer_pattern(&problem.description, keywords)
+        {
+            problem.pattern = Some(pattern.to_string());
+            summary.inferred += 1;
+        }
+
+        let field = DedupeField::for_problem(dedupe_on, &problem);
+        let value = field.value(&problem);
+        let Some(&id) = value
+            .as_ref()
+            .and_then(|value| existing.get(&(field.name(), value.clone())))
+        else {
+            let keys: Vec<(&str, String)> = fields
+                .iter()
+                .filter_map(|field| Some((field.name(), field.value(&problem)?)))
+                .collect();
+            let id = tracker.add_problem(problem)?;
+            for key in keys {
+                existing.insert(key, id);
+            }
+            summary.imported += 1;
+            summary.ids.push(id);
+            continue;
+        };
+        summary.ids.push(id);
+
+        if !matches!(on_conflict, ConflictStrategy::Skip) && tracker.get_problem(id)?.locked {
+            summary
+                .collisions
+                .push(format!("Skipped {}: #{} is locked", source, id));
+            summary.skipped += 1;
+            continue;
+        }
+
+        summary.collisions.push(format!(
+            "{} {}: {} '{}' already exists as #{}",
+            on_conflict.past_tense(),
+            source,
+            field.name(),
+            value.unwrap_or_default(),
+            id
+        ));
+
+        match on_conflict {
+            ConflictStrategy::Skip => summary.skipped += 1,
+            ConflictStrategy::Replace => {
+                problem.id = Some(id);
+                // CSV files carry no solution, so replacing keeps the saved one
+                if problem.solution.is_none() {
+                    let current = tracker.get_problem(id)?;
+                    problem.solution = current.solution;
+                    problem.solution_language = current.solution_language;
+                }
+                tracker.update_imported(&problem)?;
+                summary.replaced += 1;
+            }
+            ConflictStrategy::Merge => {
+                let mut current = tracker.get_problem(id)?;
+                let attempts = std::mem::take(&mut problem.attempts);
+                fill_missing(&mut current, problem);
+                // Dates the attempts behind any times filled in
+                current.attempts = attempts;
+                tracker.update_imported(&current)?;
+                summary.merged += 1;
+            }
+        }
+    }
+
+    if let Some(bar) = progress {
+        bar.finish_and_clear();
+    }
+
+    Ok(summary)
+}
+
+pub struct UrlImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+    /// Why each skipped line was skipped
+    pub notes: Vec<String>,
+}
+
+/// Adds a problem per URL in a plain list, one per line, with the URL's
+/// slug as its description and its site as its source. Blank lines and
+/// `#` comments are ignored, and URLs whose slug the same source already
+/// has are skipped.
+pub fn import_urls(
+    tracker: &ProblemTracker,
+    path: &Path,
+) -> Result<UrlImportSummary, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut existing: HashMap<String, i64> = tracker
+        .get_all_problems()?
+        .iter()
+        .filter_map(|problem| Some((DedupeField::Slug.value(problem)?, problem.id?)))
+        .collect();
+
+    let mut summary = UrlImportSummary {
+        added: 0,
+        skipped: 0,
+        notes: Vec::new(),
+    };
+    for (index, line) in contents.lines().enumerate() {
+        let url = line.trim();
+        if url.is_empty() || url.starts_with('#') {
+            continue;
+        }
+
+        let Some(slug) = link_slug(url) else {
+            summary.skipped += 1;
+            summary.notes.push(format!(
+                "Skipped line {}: no problem slug in '{}'",
+                index + 1,
+                url
+            ));
+            continue;
+        };
+        let source = link_source(url).unwrap_or(DEFAULT_SOURCE);
+        let key = format!("{}/{}", source, slug);
+        if let Some(id) = existing.get(&key) {
+            summary.skipped += 1;
+            summary.notes.push(format!(
+                "Skipped line {}: '{}' already exists as #{}",
+                index + 1,
+                slug,
+                id
+            ));
+            continue;
+        }
+
+        let mut problem = Problem::new(&slug);
+        problem.link = Some(url.to_string());
+        problem.source = source.to_string();
+        let id = tracker.add_problem(problem)?;
+        existing.insert(key, id);
+        summary.added += 1;
+    }
+
+    Ok(summary)
+}
+
+/// One row of a solve-time CSV
+pub struct SolveTime {
+    pub line: u64,
+    pub id: i64,
+    pub attempt: usize,
+    pub minutes: f64,
+}
+
+/// Reads `id,attempt,minutes` rows under a header naming those columns.
+/// Rows that don't parse come back as messages rather than failing the
+/// whole file.
+fn read_times(contents: &str) -> Result<(Vec<SolveTime>, Vec<String>), Box<dyn Error>> {
+    let mut reader = csv::Reader::from_reader(contents.as_bytes());
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim() == name)
+            .ok_or(format!("CSV is missing the '{}' column", name))
+    };
+    let (id_column, attempt_column, minutes_column) =
+        (column("id")?, column("attempt")?, column("minutes")?);
+
+    let mut rows = Vec::new();
+    let mut failures = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let field = |index: usize| record.get(index).unwrap_or("").trim();
+
+        let row = match (
+            field(id_column).parse(),
+            field(attempt_column).parse(),
+            field(minutes_column).parse::<f64>(),
+        ) {
+            (Err(_), _, _) => Err(format!("invalid id '{}'", field(id_column))),
+            (_, Ok(attempt), _) if !(1..=3).contains(&attempt) => {
+                Err("Attempt must be 1, 2, or 3".to_string())
+            }
+            (_, Err(_), _) => Err(format!("invalid attempt '{}'", field(attempt_column))),
+            (_, _, Ok(minutes)) if !minutes.is_finite() || minutes <= 0.0 => {
+                Err("Minutes must be greater than zero".to_string())
+            }
+            (_, _, Err(_)) => Err(format!("invalid minutes '{}'", field(minutes_column))),
+            (Ok(id), Ok(attempt), Ok(minutes)) => Ok(SolveTime {
+                line,
+                id,
+                attempt,
+                minutes,
+            }),
+        };
+        match row {
+            Ok(row) => rows.push(row),
+            Err(message) => failures.push(format!("line {}: {}", line, message)),
+        }
+    }
+
+    Ok((rows, failures))
+}
+
+pub struct TimesImportSummary {
+    /// The solves logged; none whenever any row failed
+    pub logged: Vec<SolveTime>,
+    /// Why each failing row failed
+    pub failures: Vec<String>,
+}
+
+/// Logs a solve for each row of an `id,attempt,minutes` CSV, dated
+/// `attempted_at`. Nothing is logged unless every row succeeds.
+pub fn import_times(
+    tracker: &ProblemTracker,
+    path: &Path,
+    attempted_at: DateTime<Utc>,
+    force: bool,
+) -> Result<TimesImportSummary, Box<dyn Error>> {
+    let (rows, mut failures) = read_times(&fs::read_to_string(path)?)?;
+    if failures.is_empty() {
+        failures = tracker.log_solve_times(&rows, attempted_at, force)?;
+    }
+
+    Ok(TimesImportSummary {
+        logged: if failures.is_empty() {
+            rows
+        } else {
+            Vec::new()
+        },
+        failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export;
+    use crate::testdir::TempDir;
+    use proptest::prelude::*;
+    use proptest::string::string_regex;
+
+    /// Text exercising CSV quoting: commas, quotes, newlines and non-ASCII.
+    /// Import trims fields and reads blanks as null, so generated values are
+    /// trimmed and empty ones become `None`.
+    fn text() -> impl Strategy<Value = Option<String>> {
+        string_regex("[a-zA-Z0-9 ,;\"'\n\r\téß漢😀]{0,24}")
+            .unwrap()
+            .prop_map(|value| Some(value.trim().to_string()).filter(|value| !value.is_empty()))
+    }
+
+    fn problem() -> impl Strategy<Value = Problem> {
+        (
+            (text(), text(), text(), text(), text(), text()),
+            proptest::array::uniform3(proptest::option::of(-1e9..1e9f64)),
+            (
+                any::<bool>(),
+                proptest::option::of(string_regex("[A-Za-z ]{1,8}(,[A-Za-z ]{1,8}){0,2}").unwrap()),
+                proptest::option::of(-1e9..1e9f64),
+                proptest::option::of(1..=5i64),
+                any::<bool>(),
+                proptest::collection::btree_map(
+                    "[a-z]{1,8}",
+                    "https://[a-z]{1,10}\\.com/[a-z0-9?=&%-]{0,12}",
+                    0..3,
+                ),
+            ),
+        )
+            .prop_map(
+                |(
+                    (description, link, category, pattern, difficulty, comments),
+                    times,
+                    (review, company, frequency, rating, locked, links),
+                )| {
+                    let mut problem = Problem::new(&description.unwrap_or_else(|| "x".into()));
+                    problem.link = link.map(|link| link.replace(char::is_whitespace, ""));
+                    problem.category = category;
+                    problem.pattern = pattern;
+                    problem.difficulty = difficulty;
+                    [
+                        problem.time_to_solve_1st,
+                        problem.time_to_solve_2nd,
+                        problem.time_to_solve_3rd,
+                    ] = times;
+                    problem.comments = comments;
+                    problem.should_solve_again = review;
+                    problem.company = company.as_deref().and_then(normalize_companies);
+                    problem.frequency = frequency;
+                    problem.personal_difficulty = rating;
+                    problem.locked = locked;
+                    for (label, url) in links {
+                        if label != crate::PROBLEM_LINK_LABEL {
+                            problem.set_link(crate::Link { label, url });
+                        }
+                    }
+                    problem
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn csv_export_then_import_is_lossless(problems in proptest::collection::vec(problem(), 0..8)) {
+            let mut out = Vec::new();
+            export::write_csv(&problems, &export::CsvOptions::default(), &mut out).unwrap();
+            let records = read_csv(&String::from_utf8(out).unwrap(), &[]).unwrap();
+
+            prop_assert_eq!(records.len(), problems.len());
+            for ((_, imported), original) in records.iter().zip(&problems) {
+                prop_assert_eq!(&imported.description, &original.description);
+                prop_assert_eq!(&imported.link, &original.link);
+                prop_assert_eq!(&imported.category, &original.category);
+                prop_assert_eq!(&imported.pattern, &original.pattern);
+                prop_assert_eq!(&imported.difficulty, &original.difficulty);
+                prop_assert_eq!(imported.time_to_solve_1st, original.time_to_solve_1st);
+                prop_assert_eq!(imported.time_to_solve_2nd, original.time_to_solve_2nd);
+                prop_assert_eq!(imported.time_to_solve_3rd, original.time_to_solve_3rd);
+                prop_assert_eq!(&imported.comments, &original.comments);
+                prop_assert_eq!(imported.should_solve_again, original.should_solve_again);
+                prop_assert_eq!(&imported.company, &original.company);
+                prop_assert_eq!(imported.frequency, original.frequency);
+                prop_assert_eq!(imported.personal_difficulty, original.personal_difficulty);
+                prop_assert_eq!(imported.locked, original.locked);
+                prop_assert_eq!(imported.all_links(), original.all_links());
+            }
+        }
+    }
+
+    #[test]
+    fn json_export_then_import_keeps_attempts_tags_and_solution() {
+        use crate::Attempt;
+        use chrono::{TimeZone, Utc};
+
+        let open = || {
+            ProblemTracker::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+                .unwrap()
+        };
+        let original = open();
+        let mut problem = Problem::new("Word Ladder");
+        problem.add_tag("graphs");
+        problem.add_tag("bfs");
+        let id = original.add_problem(problem).unwrap();
+        for (day, minutes, solved, note) in [
+            (1, 45.0, false, Some("missed the bidirectional trick")),
+            (8, 30.0, true, None),
+        ] {
+            let attempt = Attempt {
+                attempt: 1,
+                minutes,
+                attempted_at: Utc.with_ymd_and_hms(2026, 3, day, 9, 0, 0).unwrap(),
+                solved,
+                note: note.map(str::to_string),
+            };
+            original.log_attempt(id, &attempt, false).unwrap();
+        }
+        original
+            .set_solution(id, Some("bfs()\n"), Some("python"), false)
+            .unwrap();
+
+        let dir = TempDir::new("history");
+        let path = dir.join("history.json");
+        let problems = original
+            .with_attempts(original.get_all_problems().unwrap())
+            .unwrap();
+        let mut file = fs::File::create(&path).unwrap();
+        export::write_json(&problems, false, &mut file).unwrap();
+
+        let restored = open();
+        let summary = import_file(
+            &restored,
+            &path,
+            &[],
+            Some(DedupeField::Link),
+            ConflictStrategy::Skip,
+            None,
+            true,
+        )
+        .unwrap();
+
+        let new_id = summary.ids[0];
+        assert_eq!(
+            restored.get_attempts(new_id).unwrap(),
+            original.get_attempts(id).unwrap()
+        );
+        assert_eq!(restored.get_tags(new_id).unwrap(), ["bfs", "graphs"]);
+        let imported = restored.get_problem(new_id).unwrap();
+        assert_eq!(imported.time_to_solve_1st, Some(30.0));
+        assert_eq!(imported.solution.as_deref(), Some("bfs()\n"));
+        assert_eq!(imported.solution_language.as_deref(), Some("python"));
+    }
+
+    #[test]
+    fn mapped_columns_replace_the_standard_names() {
+        let csv = "Name,Level,difficulty\nTwo Sum,Easy,Hard\n";
+        let mapping = [
+            parse_mapping("description=Name").unwrap(),
+            parse_mapping("difficulty = Level").unwrap(),
+        ];
+        let records = read_csv(csv, &mapping).unwrap();
+        assert_eq!(records[0].1.description, "Two Sum");
+        assert_eq!(records[0].1.difficulty.as_deref(), Some("Easy"));
+
+        assert!(read_csv(csv, &[]).is_err());
+        assert!(read_csv(csv, &[parse_mapping("description=Title").unwrap()]).is_err());
+        assert!(parse_mapping("title=Name").is_err());
+        assert!(parse_mapping("description").is_err());
+    }
+
+    #[test]
+    fn records_without_a_link_are_matched_on_description() {
+        let tracker =
+            ProblemTracker::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+                .unwrap();
+        let mut known = Problem::new("Two Sum");
+        known.link = Some("https://leetcode.com/problems/two-sum/".to_string());
+        let known_id = tracker.add_problem(known).unwrap();
+
+        let dir = TempDir::new("dedupe");
+        let path = dir.join("problems.csv");
+        fs::write(
+            &path,
+            "description,link\n\
+            two sum,\n\
+            Two Sum,https://leetcode.com/problems/two-sum-ii/\n\
+            Three Sum,\n",
+        )
+        .unwrap();
+        let import = |dedupe_on| {
+            import_file(
+                &tracker,
+                &path,
+                &[],
+                dedupe_on,
+                ConflictStrategy::Skip,
+                None,
+                true,
+            )
+            .unwrap()
+        };
+
+        let summary = import(None);
+        assert_eq!((summary.imported, summary.skipped), (2, 1));
+        assert_eq!(
+            summary.collisions,
+            [format!(
+                "Skipped line 2: description 'two sum' already exists as #{}",
+                known_id
+            )]
+        );
+
+        // Every record has a description, so all three now collide
+        let summary = import(Some(DedupeField::Description));
+        assert_eq!((summary.imported, summary.skipped), (0, 3));
+    }
+
+    #[test]
+    fn replaced_and_merged_times_are_logged_as_attempts() {
+        let tracker =
+            ProblemTracker::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+                .unwrap();
+        let mut known = Problem::new("Two Sum");
+        known.link = Some("https://leetcode.com/problems/two-sum/".to_string());
+        let id = tracker.add_problem(known).unwrap();
+
+        let dir = TempDir::new("conflicts");
+        let path = dir.join("problems.csv");
+        let import = |time: f64, on_conflict| {
+            fs::write(
+                &path,
+                format!(
+                    "description,link,time_to_solve_1st\n\
+                    Two Sum,https://leetcode.com/problems/two-sum/,{}\n",
+                    time
+                ),
+            )
+            .unwrap();
+            import_file(&tracker, &path, &[], None, on_conflict, None, true).unwrap();
+        };
+        let logged = || -> Vec<(usize, f64)> {
+            tracker
+                .get_attempts(id)
+                .unwrap()
+                .iter()
+                .map(|attempt| (attempt.attempt, attempt.minutes))
+                .collect()
+        };
+
+        import(20.0, ConflictStrategy::Merge);
+        assert_eq!(logged(), [(1, 20.0)]);
+        // Merging never overwrites, and an unchanged time logs nothing
+        import(25.0, ConflictStrategy::Merge);
+        import(20.0, ConflictStrategy::Replace);
+        assert_eq!(logged(), [(1, 20.0)]);
+
+        import(15.0, ConflictStrategy::Replace);
+        assert_eq!(logged(), [(1, 20.0), (1, 15.0)]);
+        assert_eq!(
+            tracker.get_problem(id).unwrap().time_to_solve_1st,
+            Some(15.0)
+        );
+    }
+
+    #[test]
+    fn url_list_adds_new_slugs_and_skips_known_ones() {
+        let tracker =
+            ProblemTracker::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+                .unwrap();
+        let mut known = Problem::new("Two Sum");
+        known.link = Some("https://leetcode.com/problems/two-sum/".to_string());
+        tracker.add_problem(known).unwrap();
+
+        let dir = TempDir::new("urls");
+        let path = dir.join("urls.txt");
+        fs::write(
+            &path,
+            "# week 1\n\
+            https://leetcode.com/problems/two-sum/description/\n\
+            \n\
+            https://leetcode.com/problems/valid-parentheses/\n\
+            https://leetcode.com/problems/valid-parentheses/?tab=hints\n\
+            https://atcoder.jp/contests/abc300/tasks/two-sum\n",
+        )
+        .unwrap();
+
+        let summary = import_urls(&tracker, &path).unwrap();
+
+        assert_eq!((summary.added, summary.skipped), (2, 2));
+        let problems = tracker.get_all_problems().unwrap();
+        assert_eq!(problems[1].description, "valid-parentheses");
+        // Same slug, different judge
+        assert_eq!(problems[2].description, "two-sum");
+        assert_eq!(problems[2].source, "atcoder");
+    }
+
+    #[test]
+    fn solve_times_log_only_when_every_row_is_valid() {
+        let tracker =
+            ProblemTracker::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+                .unwrap();
+        let id = tracker.add_problem(Problem::new("Two Sum")).unwrap();
+        let now = Utc::now();
+
+        let dir = TempDir::new("times");
+        let path = dir.join("times.csv");
+        fs::write(
+            &path,
+            "id,attempt,minutes\n1,1,30\n1,3,12\n99,1,10\n1,4,5\n",
+        )
+        .unwrap();
+        let summary = import_times(&tracker, &path, now, false).unwrap();
+        assert_eq!(summary.failures, ["line 5: Attempt must be 1, 2, or 3"]);
+
+        fs::write(&path, "id,attempt,minutes\n1,1,30\n1,3,12\n99,1,10\n").unwrap();
+        let summary = import_times(&tracker, &path, now, false).unwrap();
+        assert_eq!(summary.failures.len(), 2);
+        assert!(summary.failures[0].starts_with("line 3: Attempt 3 needs"));
+        assert_eq!(summary.failures[1], "line 4: Problem with ID 99 not found");
+        assert!(summary.logged.is_empty());
+        assert_eq!(tracker.get_problem(id).unwrap().time_to_solve_1st, None);
+
+        fs::write(&path, "minutes,id,attempt\n30,1,1\n12,1,2\n").unwrap();
+        let summary = import_times(&tracker, &path, now, false).unwrap();
+        assert!(summary.failures.is_empty());
+        assert_eq!(summary.logged.len(), 2);
+        let problem = tracker.get_problem(id).unwrap();
+        assert_eq!(
+            (problem.time_to_solve_1st, problem.time_to_solve_2nd),
+            (Some(30.0), Some(12.0))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+/// The built-in keyword map, shipped as a data file
+const BUILTIN_KEYWORDS: &str = include_str!("pattern_keywords.toml");
+
+/// Keyword to pattern pairs of `pattern_keywords.toml`
+pub fn builtin_keywords() -> BTreeMap<String, String> {
+    toml::from_str(BUILTIN_KEYWORDS).expect("pattern_keywords.toml is valid")
+}
+
+/// Lowercased words of `text`, each surrounded by single spaces so a
+/// keyword only matches whole words
+fn padded_words(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+/// The pattern of the longest keyword found in `description` as whole
+/// words, ignoring case; ties go to the keyword that sorts first. Keywords
+/// mapped to an empty pattern are ignored.
+pub fn infer_pattern<'a>(
+    description: &str,
+    keywords: &'a BTreeMap<String, String>,
+) -> Option<&'a str> {
+    let words = padded_words(description);
+    keywords
+        .iter()
+        .filter(|(_, pattern)| !pattern.trim().is_empty())
+        .filter(|(keyword, _)| {
+            let keyword = padded_words(keyword);
+            keyword.trim() != "" && words.contains(&keyword)
+        })
+        .max_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then(b.cmp(a)))
+        .map(|(_, pattern)| pattern.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_whole_word_keyword_wins() {
+        let mut keywords = builtin_keywords();
+        assert_eq!(
+            infer_pattern("Maximum Subarray", &keywords),
+            Some("sliding window")
+        );
+        assert_eq!(
+            infer_pattern("Validate Binary Search Tree", &keywords),
+            Some("tree")
+        );
+        assert_eq!(infer_pattern("Street Lights", &keywords), None);
+
+        // A config entry overrides the built-in one, and an empty one
+        // turns it off
+        keywords.insert("subarray".to_string(), "prefix sum".to_string());
+        keywords.insert("tree".to_string(), String::new());
+        assert_eq!(
+            infer_pattern("Maximum Subarray", &keywords),
+            Some("prefix sum")
+        );
+        assert_eq!(infer_pattern("Invert Tree", &keywords), None);
+    }
+}
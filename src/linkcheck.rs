@@ -0,0 +1,113 @@
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// One stored link to try
+pub struct Target {
+    pub problem_id: i64,
+    pub label: String,
+    pub url: String,
+}
+
+/// A link that didn't answer with a 2xx status
+pub struct Broken {
+    pub target: Target,
+    /// The status code, or why no response came back
+    pub reason: String,
+}
+
+/// Runs `check` over `items` on at most `concurrency` threads, keeping the
+/// results in input order
+fn run_pool<T: Sync, R: Send>(
+    items: &[T],
+    concurrency: usize,
+    check: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = check(item);
+                results.lock().unwrap().push((index, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// `None` for a 2xx answer, otherwise what went wrong. Some sites refuse
+/// HEAD outright, so a 405 gets a second try with GET.
+fn check_url(client: &Client, url: &str) -> Option<String> {
+    let status = match client.head(url).send() {
+        Ok(response) if response.status() == StatusCode::METHOD_NOT_ALLOWED => {
+            client.get(url).send().map(|response| response.status())
+        }
+        response => response.map(|response| response.status()),
+    };
+    match status {
+        Ok(status) if status.is_success() => None,
+        Ok(status) => Some(status.to_string()),
+        Err(error) if error.is_timeout() => Some("timed out".to_string()),
+        // The URL is already printed alongside, so say what went wrong
+        // underneath instead
+        Err(error) => Some(match std::error::Error::source(&error) {
+            Some(cause) => cause.to_string(),
+            None => error.without_url().to_string(),
+        }),
+    }
+}
+
+/// Sends a HEAD request to every target, `concurrency` at a time, and
+/// returns the ones that failed
+pub fn find_broken(
+    targets: Vec<Target>,
+    concurrency: usize,
+    timeout: Duration,
+) -> reqwest::Result<Vec<Broken>> {
+    let client = Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("shikari/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let reasons = run_pool(&targets, concurrency, |target| {
+        check_url(&client, &target.url)
+    });
+
+    Ok(targets
+        .into_iter()
+        .zip(reasons)
+        .filter_map(|(target, reason)| {
+            Some(Broken {
+                target,
+                reason: reason?,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_keeps_input_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let doubled = run_pool(&items, 4, |item| {
+            // Later items finish first
+            thread::sleep(Duration::from_millis(20 - item));
+            item * 2
+        });
+        assert_eq!(doubled, (0..20).map(|item| item * 2).collect::<Vec<_>>());
+        assert!(run_pool(&Vec::<u64>::new(), 4, |item| *item).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::Problem;
+
+/// One kind of inconsistency a problem can have
+pub struct Check {
+    pub name: &'static str,
+    /// What's wrong, as printed next to the problem's ID
+    pub issue: &'static str,
+    applies: fn(&Problem) -> bool,
+    /// Repairs the problem in place, for issues with only one sensible fix
+    fix: Option<fn(&mut Problem)>,
+}
+
+impl Check {
+    pub fn is_fixable(&self) -> bool {
+        self.fix.is_some()
+    }
+}
+
+const DIFFICULTIES: [&str; 3] = ["Easy", "Medium", "Hard"];
+
+fn times(problem: &Problem) -> [Option<f64>; 3] {
+    [
+        problem.time_to_solve_1st,
+        problem.time_to_solve_2nd,
+        problem.time_to_solve_3rd,
+    ]
+}
+
+/// A later attempt has a time while an earlier one doesn't
+fn has_time_gap(problem: &Problem) -> bool {
+    times(problem)
+        .windows(2)
+        .any(|pair| pair[0].is_none() && pair[1].is_some())
+}
+
+fn canonical_difficulty(difficulty: &str) -> Option<&'static str> {
+    DIFFICULTIES
+        .into_iter()
+        .find(|known| known.eq_ignore_ascii_case(difficulty.trim()))
+}
+
+fn has_miscased_difficulty(problem: &Problem) -> bool {
+    problem.difficulty.as_deref().is_some_and(|difficulty| {
+        canonical_difficulty(difficulty).is_some_and(|known| known != difficulty)
+    })
+}
+
+fn fix_difficulty_case(problem: &mut Problem) {
+    if let Some(known) = problem.difficulty.as_deref().and_then(canonical_difficulty) {
+        problem.difficulty = Some(known.to_string());
+    }
+}
+
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "blank-description",
+        issue: "description is blank",
+        applies: |problem| problem.description.trim().is_empty(),
+        fix: None,
+    },
+    Check {
+        name: "difficulty-without-category",
+        issue: "has a difficulty but no category",
+        applies: |problem| problem.difficulty.is_some() && problem.category.is_none(),
+        fix: None,
+    },
+    Check {
+        name: "unknown-difficulty",
+        issue: "difficulty isn't Easy, Medium or Hard",
+        applies: |problem| {
+            problem
+                .difficulty
+                .as_deref()
+                .is_some_and(|difficulty| canonical_difficulty(difficulty).is_none())
+        },
+        fix: None,
+    },
+    Check {
+        name: "difficulty-case",
+        issue: "difficulty is spelt in the wrong case",
+        applies: has_miscased_difficulty,
+        fix: Some(fix_difficulty_case),
+    },
+    Check {
+        name: "time-gap",
+        issue: "has a later attempt's time without an earlier one",
+        applies: has_time_gap,
+        // Moving the times down would leave the attempt log disagreeing, and
+        // the gap may be a real unsolved attempt
+        fix: None,
+    },
+    Check {
+        name: "non-positive-time",
+        issue: "has a solve time of zero or less",
+        applies: |problem| times(problem).into_iter().flatten().any(|time| time <= 0.0),
+        fix: None,
+    },
+];
+
+/// The checks `problem` fails, in `CHECKS` order
+pub fn issues(problem: &Problem) -> Vec<&'static Check> {
+    CHECKS
+        .iter()
+        .filter(|check| (check.applies)(problem))
+        .collect()
+}
+
+/// Applies every fix for the checks `problem` fails and returns the checks
+/// fixed
+pub fn fix(problem: &mut Problem) -> Vec<&'static Check> {
+    let mut fixed = Vec::new();
+    for check in CHECKS {
+        if let Some(fix) = check.fix
+            && (check.applies)(problem)
+        {
+            fix(problem);
+            fixed.push(check);
+        }
+    }
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(checks: &[&Check]) -> Vec<&'static str> {
+        checks.iter().map(|check| check.name).collect()
+    }
+
+    #[test]
+    fn reports_each_inconsistency() {
+        let mut problem = Problem::new("Two Sum");
+        assert!(issues(&problem).is_empty());
+
+        problem.difficulty = Some("Tricky".to_string());
+        problem.time_to_solve_2nd = Some(0.0);
+        assert_eq!(
+            names(&issues(&problem)),
+            [
+                "difficulty-without-category",
+                "unknown-difficulty",
+                "time-gap",
+                "non-positive-time"
+            ]
+        );
+    }
+
+    #[test]
+    fn fixes_difficulty_case_but_only_reports_time_gaps() {
+        let mut problem = Problem::new("Two Sum");
+        problem.category = Some("Arrays".to_string());
+        problem.difficulty = Some("medium".to_string());
+        problem.time_to_solve_2nd = Some(20.0);
+        problem.time_to_solve_3rd = Some(12.0);
+
+        assert_eq!(names(&fix(&mut problem)), ["difficulty-case"]);
+        assert_eq!(problem.difficulty.as_deref(), Some("Medium"));
+        assert_eq!(times(&problem), [None, Some(20.0), Some(12.0)]);
+        assert_eq!(names(&issues(&problem)), ["time-gap"]);
+    }
+}
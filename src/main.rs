@@ -1,10 +1,63 @@
-use clap::{Parser, Subcommand};
-use rusqlite::{params, Connection, Result, Row};
+mod backup;
+mod categories;
+mod clock;
+mod color;
+mod config;
+mod doctor;
+mod export;
+#[cfg(feature = "highlight")]
+mod highlight;
+mod humanize;
+mod import;
+mod infer;
+#[cfg(feature = "network")]
+mod linkcheck;
+mod lint;
+mod memory;
+mod plan;
+mod prereq;
+mod ranking;
+mod readiness;
+mod report;
+mod schema;
+mod score;
+mod stats;
+mod template;
+#[cfg(test)]
+mod testdir;
+#[cfg(feature = "tui")]
+mod tui;
+mod weak;
+mod wizard;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::Config;
+use rusqlite::limits::Limit;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{
+    params, params_from_iter, Connection, ErrorCode, OpenFlags, OptionalExtension, Result, Row,
+    ToSql, Transaction, TransactionBehavior,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
-use std::path::PathBuf;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime};
+
+/// Label of a problem's own page among its links
+const PROBLEM_LINK_LABEL: &str = "problem";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Link {
+    label: String,
+    url: String,
+}
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 struct Problem {
     id: Option<i64>,
     description: String,
@@ -12,11 +65,58 @@ struct Problem {
     category: Option<String>,
     pattern: Option<String>,
     difficulty: Option<String>,
-    time_to_solve_1st: Option<i64>,
-    time_to_solve_2nd: Option<i64>,
-    time_to_solve_3rd: Option<i64>,
+    time_to_solve_1st: Option<f64>,
+    time_to_solve_2nd: Option<f64>,
+    time_to_solve_3rd: Option<f64>,
     comments: Option<String>,
+    #[serde(default)]
     should_solve_again: bool,
+    company: Option<String>,
+    frequency: Option<f64>,
+    /// The user's own 1-5 rating, as opposed to the site's `difficulty`
+    personal_difficulty: Option<i64>,
+    /// Labelled links, e.g. an editorial or a walkthrough. `link` mirrors the
+    /// one labelled "problem".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    links: Vec<Link>,
+    /// Locked problems refuse edits, time updates and deletion unless forced
+    #[serde(default)]
+    locked: bool,
+    /// On the focus list, the current study shortlist
+    #[serde(default)]
+    focused: bool,
+    /// Free-form lowercase labels, kept sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Judge the problem comes from, e.g. "leetcode" or "codeforces";
+    /// slugs only need to be unique within one
+    #[serde(default = "default_source")]
+    source: String,
+    /// Code of the user's own solution; see `set_solution`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    solution: Option<String>,
+    /// Lowercase language `solution` is written in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    solution_language: Option<String>,
+    /// Logged attempts, oldest first. Only filled in for JSON exports, so
+    /// that importing one restores them; see `with_attempts`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attempts: Vec<Attempt>,
+    /// Derived when listing, never stored; see `annotate`
+    #[serde(skip)]
+    comfort: Option<stats::Comfort>,
+    /// Logged attempts, derived when listing like `comfort`
+    #[serde(skip)]
+    attempt_count: Option<usize>,
+    /// Minutes of the earliest attempt, derived like `comfort`
+    #[serde(skip)]
+    first_time: Option<f64>,
+    /// Fewest minutes across all attempts, derived like `comfort`
+    #[serde(skip)]
+    best_time: Option<f64>,
+    /// New, learning or mastered, derived like `comfort`
+    #[serde(skip)]
+    mastery: Option<stats::Mastery>,
 }
 
 impl Problem {
@@ -33,52 +133,382 @@ impl Problem {
             time_to_solve_3rd: None,
             comments: None,
             should_solve_again: false,
+            company: None,
+            frequency: None,
+            personal_difficulty: None,
+            locked: false,
+            focused: false,
+            links: Vec::new(),
+            tags: Vec::new(),
+            source: default_source(),
+            solution: None,
+            solution_language: None,
+            comfort: None,
+            attempt_count: None,
+            first_time: None,
+            best_time: None,
+            mastery: None,
+            attempts: Vec::new(),
+        }
+    }
+
+    /// Every link, with `link` included as "problem" if `links` lacks one.
+    /// The problem's own page comes first, the rest by label.
+    fn all_links(&self) -> Vec<Link> {
+        let mut links = self.links.clone();
+        if let Some(url) = &self.link
+            && !links.iter().any(|link| link.label == PROBLEM_LINK_LABEL)
+        {
+            links.push(Link {
+                label: PROBLEM_LINK_LABEL.to_string(),
+                url: url.clone(),
+            });
+        }
+        links.sort_by(|a, b| {
+            (a.label != PROBLEM_LINK_LABEL, &a.label)
+                .cmp(&(b.label != PROBLEM_LINK_LABEL, &b.label))
+        });
+        links
+    }
+
+    /// URL of the link labelled "problem"
+    fn problem_url(&self) -> Option<String> {
+        self.all_links()
+            .into_iter()
+            .find(|link| link.label == PROBLEM_LINK_LABEL)
+            .map(|link| link.url)
+    }
+
+    /// Adds the link, replacing any existing link with the same label
+    fn set_link(&mut self, link: Link) {
+        if link.label == PROBLEM_LINK_LABEL {
+            self.link = Some(link.url.clone());
+        }
+        match self
+            .links
+            .iter_mut()
+            .find(|existing| existing.label == link.label)
+        {
+            Some(existing) => *existing = link,
+            None => self.links.push(link),
+        }
+    }
+
+    /// Removes the link with this label, returning whether there was one
+    fn remove_link(&mut self, label: &str) -> bool {
+        let had_link = self.all_links().iter().any(|link| link.label == label);
+        if label == PROBLEM_LINK_LABEL {
+            self.link = None;
+        }
+        self.links.retain(|link| link.label != label);
+        had_link
+    }
+
+    fn add_tag(&mut self, tag: &str) {
+        if let Err(index) = self
+            .tags
+            .binary_search_by(|existing| existing.as_str().cmp(tag))
+        {
+            self.tags.insert(index, tag.to_string());
+        }
+    }
+
+    /// Removes the tag, returning whether the problem had it
+    fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|existing| existing != tag);
+        self.tags.len() != before
+    }
+
+    /// The first attempt without a solve time, or `None` once all three
+    /// have one
+    fn next_attempt(&self) -> Option<usize> {
+        [
+            self.time_to_solve_1st,
+            self.time_to_solve_2nd,
+            self.time_to_solve_3rd,
+        ]
+        .iter()
+        .position(Option::is_none)
+        .map(|index| index + 1)
+    }
+
+    /// The problem as someone else would start it: what it is and where to
+    /// find it, without times, notes, ratings or flags
+    fn into_template(self) -> Problem {
+        Problem {
+            id: None,
+            time_to_solve_1st: None,
+            time_to_solve_2nd: None,
+            time_to_solve_3rd: None,
+            comments: None,
+            should_solve_again: false,
+            personal_difficulty: None,
+            locked: false,
+            focused: false,
+            solution: None,
+            solution_language: None,
+            attempts: Vec::new(),
+            ..self
+        }
+    }
+}
+
+fn default_source() -> String {
+    DEFAULT_SOURCE.to_string()
+}
+
+/// Judges recognized from a link's host, for when no source is given
+const KNOWN_SOURCES: [(&str, &str); 3] = [
+    ("leetcode.com", "leetcode"),
+    ("codeforces.com", "codeforces"),
+    ("atcoder.jp", "atcoder"),
+];
+
+/// The judge a link points at, if it is one of `KNOWN_SOURCES`
+fn link_source(link: &str) -> Option<&'static str> {
+    let host = link
+        .split("://")
+        .nth(1)
+        .unwrap_or(link)
+        .split(['/', '?', '#'])
+        .next()?
+        .to_lowercase();
+    KNOWN_SOURCES
+        .iter()
+        .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+        .map(|(_, source)| *source)
+}
+
+fn parse_source(value: &str) -> std::result::Result<String, String> {
+    let source = value.trim().to_lowercase();
+    if source.is_empty() {
+        return Err("source cannot be empty".to_string());
+    }
+
+    Ok(source)
+}
+
+/// Parses a solve time: a positive number of minutes, whole or not
+fn parse_minutes(value: &str) -> std::result::Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(minutes) if minutes.is_finite() && minutes > 0.0 => Ok(minutes),
+        _ => Err(format!(
+            "'{}' is not a positive number of minutes, e.g. 12 or 2.5",
+            value
+        )),
+    }
+}
+
+/// Tags are trimmed and lowercased so "Graphs " and "graphs" are one tag
+fn parse_tag(value: &str) -> std::result::Result<String, String> {
+    let tag = value.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err("tags cannot be empty".to_string());
+    }
+
+    Ok(tag)
+}
+
+/// Parses `label=url`, or a bare URL as the "problem" link
+fn parse_link(value: &str) -> std::result::Result<Link, String> {
+    match value.split_once('=') {
+        Some((label, url))
+            if label
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' ')) =>
+        {
+            if label.trim().is_empty() || url.trim().is_empty() {
+                return Err(format!("expected label=url, got '{}'", value));
+            }
+            Ok(Link {
+                label: label.trim().to_lowercase(),
+                url: url.trim().to_string(),
+            })
+        }
+        _ => Ok(Link {
+            label: PROBLEM_LINK_LABEL.to_string(),
+            url: value.trim().to_string(),
+        }),
+    }
+}
+
+/// Which parts of a problem its text form shows, and whether its markers
+/// are colored. `Display` uses the options set for this run; other output,
+/// such as the TUI, passes its own to `Problem::format_with`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct DisplayOptions {
+    /// The [REVIEW NEEDED] marker
+    review_marker: bool,
+    /// The [LOCKED] and [FOCUS] markers
+    status_markers: bool,
+    links: bool,
+    comments: bool,
+    /// Color the markers; only set when writing to a terminal
+    color: bool,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            review_marker: true,
+            status_markers: true,
+            links: true,
+            comments: true,
+            color: false,
+        }
+    }
+}
+
+/// Filled in from the command line at startup; left unset, as in tests,
+/// problems show everything uncolored
+static DISPLAY_OPTIONS: OnceLock<DisplayOptions> = OnceLock::new();
+
+impl DisplayOptions {
+    fn current() -> Self {
+        DISPLAY_OPTIONS.get().copied().unwrap_or_default()
+    }
+}
+
+/// A problem written out with the given `DisplayOptions`
+struct FormattedProblem<'a> {
+    problem: &'a Problem,
+    options: DisplayOptions,
+}
+
+impl Problem {
+    fn format_with(&self, options: DisplayOptions) -> FormattedProblem<'_> {
+        FormattedProblem {
+            problem: self,
+            options,
         }
     }
 }
 
 impl fmt::Display for Problem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.format_with(DisplayOptions::current()).fmt(f)
+    }
+}
+
+impl fmt::Display for FormattedProblem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = self.options;
+        let problem = self.problem;
         write!(
             f,
             "Problem #{}: {} ({})",
-            self.id.unwrap_or(0),
-            self.description,
-            self.difficulty.as_deref().unwrap_or("Unknown")
+            problem.id.unwrap_or(0),
+            problem.description,
+            problem.difficulty.as_deref().unwrap_or("Unknown")
         )?;
 
-        if let Some(category) = &self.category {
+        if let Some(category) = &problem.category {
             write!(f, " - Category: {}", category)?;
         }
 
-        if let Some(pattern) = &self.pattern {
+        if let Some(pattern) = &problem.pattern {
             write!(f, " - Pattern: {}", pattern)?;
         }
 
+        if problem.source != DEFAULT_SOURCE {
+            write!(f, " - Source: {}", problem.source)?;
+        }
+
         write!(f, "\n  ")?;
 
-        if let Some(link) = &self.link {
-            write!(f, "Link: {}", link)?;
+        let links = if options.links {
+            problem.all_links()
+        } else {
+            Vec::new()
+        };
+        for (index, link) in links.iter().enumerate() {
+            if index > 0 {
+                write!(f, "\n  ")?;
+            }
+            if link.label == PROBLEM_LINK_LABEL {
+                write!(f, "Link: {}", link.url)?;
+            } else {
+                write!(f, "Link ({}): {}", link.label, link.url)?;
+            }
+        }
+
+        if let Some(company) = &problem.company {
+            write!(f, "\n  Companies: {}", company.replace(',', ", "))?;
+        }
+
+        if !problem.tags.is_empty() {
+            write!(f, "\n  Tags: {}", problem.tags.join(", "))?;
+        }
+
+        if let Some(frequency) = problem.frequency {
+            write!(f, "\n  Frequency: {}", frequency)?;
+        }
+
+        if let Some(rating) = problem.personal_difficulty {
+            write!(f, "\n  Personal difficulty: {}/5", rating)?;
         }
 
         write!(f, "\n  Solve times: ")?;
         match (
-            self.time_to_solve_1st,
-            self.time_to_solve_2nd,
-            self.time_to_solve_3rd,
+            problem.time_to_solve_1st,
+            problem.time_to_solve_2nd,
+            problem.time_to_solve_3rd,
         ) {
-            (Some(t1), Some(t2), Some(t3)) => write!(f, "{}min, {}min, {}min", t1, t2, t3)?,
-            (Some(t1), Some(t2), None) => write!(f, "{}min, {}min, -", t1, t2)?,
-            (Some(t1), None, None) => write!(f, "{}min, -, -", t1)?,
+            (Some(t1), Some(t2), Some(t3)) => write!(
+                f,
+                "{}, {}, {}",
+                humanize::minutes(t1),
+                humanize::minutes(t2),
+                humanize::minutes(t3)
+            )?,
+            (Some(t1), Some(t2), None) => {
+                write!(f, "{}, {}, -", humanize::minutes(t1), humanize::minutes(t2))?
+            }
+            (Some(t1), None, None) => write!(f, "{}, -, -", humanize::minutes(t1))?,
             _ => write!(f, "Not attempted")?,
         }
+        if let Some(comfort) = problem.comfort {
+            write!(f, " ({})", comfort)?;
+        }
+
+        if let Some(count) = problem.attempt_count.filter(|count| *count > 0) {
+            write!(f, "\n  Attempts: {}", count)?;
+        }
+
+        if let (Some(first), Some(best)) = (problem.first_time, problem.best_time) {
+            write!(
+                f,
+                "\n  First solve: {}, best: {}",
+                humanize::minutes(first),
+                humanize::minutes(best)
+            )?;
+        }
+
+        if let Some(mastery) = problem.mastery {
+            write!(f, "\n  Mastery: {}", mastery)?;
+        }
 
-        if let Some(comments) = &self.comments {
-            write!(f, "\n  Comments: {}", comments)?;
+        if let Some(comments) = problem.comments.as_ref().filter(|_| options.comments) {
+            // Keep appended notes aligned under the first line
+            write!(
+                f,
+                "\n  Comments: {}",
+                comments.replace('\n', "\n            ")
+            )?;
         }
 
-        if self.should_solve_again {
-            write!(f, "\n  [REVIEW NEEDED]")?;
+        let palette = color::Palette::with_color(options.color);
+        let markers = [
+            (
+                options.review_marker && problem.should_solve_again,
+                "[REVIEW NEEDED]",
+            ),
+            (options.status_markers && problem.locked, "[LOCKED]"),
+            (options.status_markers && problem.focused, "[FOCUS]"),
+        ];
+        for (_, marker) in markers.iter().filter(|(shown, _)| *shown) {
+            write!(f, "\n  {}", palette.changed(marker))?;
         }
 
         Ok(())
@@ -98,455 +528,9890 @@ fn from_row(row: &Row) -> Result<Problem> {
         time_to_solve_3rd: row.get(8)?,
         comments: row.get(9)?,
         should_solve_again: row.get::<_, i64>(10)? != 0,
+        company: row.get(11)?,
+        frequency: row.get(12)?,
+        personal_difficulty: row.get(13)?,
+        locked: row.get::<_, i64>(14)? != 0,
+        // 15 is category_id, resolved into `category` on write
+        source: row.get(16)?,
+        focused: row.get::<_, i64>(17)? != 0,
+        // 18 is deleted_at, which only `get_trashed` reads
+        solution: row.get(19)?,
+        solution_language: row.get(20)?,
+        links: Vec::new(),
+        tags: Vec::new(),
+        comfort: None,
+        attempt_count: None,
+        first_time: None,
+        best_time: None,
+        mastery: None,
+        attempts: Vec::new(),
     })
 }
 
-struct ProblemTracker {
-    conn: Connection,
-}
-
-impl ProblemTracker {
-    fn new(db_path: &str) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-
-        // Create table if it doesn't exist
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS problems (
-                id INTEGER PRIMARY KEY,
-                description TEXT NOT NULL,
-                link TEXT,
-                category TEXT,
-                pattern TEXT,
-                difficulty TEXT,
-                time_to_solve_1st INTEGER,
-                time_to_solve_2nd INTEGER,
-                time_to_solve_3rd INTEGER,
-                comments TEXT,
-                should_solve_again INTEGER NOT NULL DEFAULT 0
-            )",
-            [],
-        )?;
+/// A `SELECT` of whole problem rows and its parameters, run as-is by
+/// `query_problems` or paged in SQL by `get_listing`
+type ProblemQuery = (String, Vec<Value>);
 
-        Ok(ProblemTracker { conn })
+/// Problems meeting the SQL `condition` and every given filter, by ID.
+/// Label filters ignore case, like category lookups; time bounds leave out
+/// problems without a first solve time.
+fn filtered_query(condition: &str, filters: &FilterArgs) -> ProblemQuery {
+    let mut query = format!(
+        "SELECT * FROM problems WHERE ({}) AND deleted_at IS NULL",
+        condition
+    );
+    let mut values = Vec::new();
+    for (column, value) in [
+        ("category", &filters.category),
+        ("pattern", &filters.pattern),
+        ("difficulty", &filters.difficulty),
+        ("source", &filters.source),
+    ] {
+        if let Some(value) = value {
+            query.push_str(&format!(" AND {} = ? COLLATE NOCASE", column));
+            values.push(Value::Text(value.clone()));
+        }
+    }
+    match (filters.min_time, filters.max_time) {
+        (Some(min), Some(max)) => {
+            query.push_str(" AND time_to_solve_1st BETWEEN ? AND ?");
+            values.extend([Value::Real(min), Value::Real(max)]);
+        }
+        (Some(min), None) => {
+            query.push_str(" AND time_to_solve_1st >= ?");
+            values.push(Value::Real(min));
+        }
+        (None, Some(max)) => {
+            query.push_str(" AND time_to_solve_1st <= ?");
+            values.push(Value::Real(max));
+        }
+        (None, None) => {}
     }
+    query.push_str(" ORDER BY id");
 
-    fn add_problem(&self, problem: Problem) -> Result<i64> {
-        self.conn.execute(
-            "INSERT INTO problems (
-                description, link, category, pattern, difficulty,
-                time_to_solve_1st, time_to_solve_2nd, time_to_solve_3rd,
-                comments, should_solve_again
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                problem.description,
-                problem.link,
-                problem.category,
-                problem.pattern,
-                problem.difficulty,
-                problem.time_to_solve_1st,
-                problem.time_to_solve_2nd,
-                problem.time_to_solve_3rd,
-                problem.comments,
-                problem.should_solve_again as i64
-            ],
-        )?;
+    (query, values)
+}
 
-        Ok(self.conn.last_insert_rowid())
-    }
+/// Problems with no solve time and no logged attempt, optionally narrowed
+/// by `filters`
+fn unattempted_query(filters: &FilterArgs) -> ProblemQuery {
+    filtered_query(
+        "time_to_solve_1st IS NULL
+        AND time_to_solve_2nd IS NULL
+        AND time_to_solve_3rd IS NULL
+        AND NOT EXISTS (SELECT 1 FROM attempts WHERE problem_id = problems.id)",
+        filters,
+    )
+}
 
-    fn update_solve_time(&self, id: i64, attempt: usize, minutes: i64) -> Result<()> {
-        let column = match attempt {
-            1 => "time_to_solve_1st",
-            2 => "time_to_solve_2nd",
-            3 => "time_to_solve_3rd",
-            _ => {
-                return Err(rusqlite::Error::InvalidParameterName(
-                    "Attempt must be 1, 2, or 3".to_string(),
-                ))
-            }
-        };
+/// Problems with no problem link, optionally narrowed by `filters`
+fn without_link_query(filters: &FilterArgs) -> ProblemQuery {
+    filtered_query("(link IS NULL OR link = '')", filters)
+}
 
-        let query = format!("UPDATE problems SET {} = ? WHERE id = ?", column);
-        self.conn.execute(&query, params![minutes, id])?;
+/// Problems whose only condition is `condition`, on the single `value`
+fn single_value_query(condition: &str, value: Value) -> ProblemQuery {
+    (
+        format!(
+            "SELECT * FROM problems WHERE {} AND deleted_at IS NULL ORDER BY id",
+            condition
+        ),
+        vec![value],
+    )
+}
 
-        Ok(())
-    }
+fn category_query(category: &str) -> ProblemQuery {
+    single_value_query(
+        "category = ? COLLATE NOCASE",
+        Value::Text(category.to_string()),
+    )
+}
 
-    fn toggle_review_flag(&self, id: i64) -> Result<()> {
-        self.conn.execute(
-            "UPDATE problems SET should_solve_again = NOT should_solve_again WHERE id = ?",
-            params![id],
-        )?;
+fn pattern_query(pattern: &str) -> ProblemQuery {
+    single_value_query("pattern = ?", Value::Text(pattern.to_string()))
+}
 
-        Ok(())
-    }
+/// Problems whose solution is in `language`, in any case
+fn language_query(language: &str) -> ProblemQuery {
+    single_value_query(
+        "solution_language = ? COLLATE NOCASE",
+        Value::Text(language.trim().to_string()),
+    )
+}
 
-    fn get_problem(&self, id: i64) -> Result<Problem> {
-        self.conn
-            .query_row("SELECT * FROM problems WHERE id = ?", params![id], |row| {
-                from_row(row)
-            })
-    }
+fn difficulty_query(difficulty: &str) -> ProblemQuery {
+    single_value_query("difficulty = ?", Value::Text(difficulty.to_string()))
+}
 
-    fn get_all_problems(&self) -> Result<Vec<Problem>> {
-        let mut stmt = self.conn.prepare("SELECT * FROM problems ORDER BY id")?;
-        let problem_iter = stmt.query_map([], |row| from_row(row))?;
+fn company_query(company: &str) -> ProblemQuery {
+    single_value_query(
+        "',' || company || ',' LIKE ? ESCAPE '\\'",
+        Value::Text(format!("%,{},%", escape_like(company.trim()))),
+    )
+}
 
-        let mut problems = Vec::new();
-        for problem_result in problem_iter {
-            problems.push(problem_result?);
+/// `text` with LIKE's wildcards and the escape character itself escaped, so
+/// it only matches literally under `ESCAPE '\'`
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
         }
-
-        Ok(problems)
+        escaped.push(c);
     }
+    escaped
+}
 
-    fn get_problems_to_review(&self) -> Result<Vec<Problem>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT * FROM problems WHERE should_solve_again = 1")?;
-        let problem_iter = stmt.query_map([], |row| from_row(row))?;
+/// Problems with every one of `tags`, or with any of them unless `all`
+fn tags_query(tags: &[String], all: bool) -> ProblemQuery {
+    let mut tags = tags.to_vec();
+    tags.sort();
+    tags.dedup();
+    let query = format!(
+        "SELECT * FROM problems WHERE id IN (
+            SELECT problem_id FROM tags WHERE tag IN ({}) GROUP BY problem_id
+            HAVING COUNT(DISTINCT tag) >= {}
+        ) AND deleted_at IS NULL ORDER BY id",
+        vec!["?"; tags.len()].join(", "),
+        if all { tags.len() } else { 1 }
+    );
 
-        let mut problems = Vec::new();
-        for problem_result in problem_iter {
-            problems.push(problem_result?);
-        }
+    (query, tags.into_iter().map(Value::Text).collect())
+}
 
-        Ok(problems)
-    }
+/// Problems where each keyword matches the description, category, pattern
+/// or comments; `mode` decides whether all or any must match
+fn search_query(keywords: &[String], mode: SearchMode) -> ProblemQuery {
+    let (condition, search_patterns) = search_condition(keywords, mode);
+    (
+        format!(
+            "SELECT * FROM problems WHERE ({}) AND deleted_at IS NULL ORDER BY id",
+            condition
+        ),
+        search_patterns.into_iter().map(Value::Text).collect(),
+    )
+}
 
-    fn get_problems_by_category(&self, category: &str) -> Result<Vec<Problem>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT * FROM problems WHERE category = ?")?;
-        let problem_iter = stmt.query_map(params![category], |row| from_row(row))?;
+fn focus_query() -> ProblemQuery {
+    (
+        "SELECT * FROM problems WHERE focus = 1 AND deleted_at IS NULL ORDER BY id".to_string(),
+        Vec::new(),
+    )
+}
 
-        let mut problems = Vec::new();
-        for problem_result in problem_iter {
-            problems.push(problem_result?);
-        }
+/// The `WHERE` condition for a keyword search and its LIKE patterns
+fn search_condition(keywords: &[String], mode: SearchMode) -> (String, Vec<String>) {
+    let clause = "(description LIKE ? ESCAPE '\\' OR category LIKE ? ESCAPE '\\' \
+        OR pattern LIKE ? ESCAPE '\\' OR comments LIKE ? ESCAPE '\\')";
+    let separator = match mode {
+        SearchMode::And => " AND ",
+        SearchMode::Or => " OR ",
+    };
+    let patterns = keywords
+        .iter()
+        .flat_map(|keyword| vec![format!("%{}%", escape_like(keyword)); 4])
+        .collect();
 
-        Ok(problems)
-    }
+    (vec![clause; keywords.len()].join(separator), patterns)
+}
 
-    fn get_problems_by_pattern(&self, pattern: &str) -> Result<Vec<Problem>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT * FROM problems WHERE pattern = ?")?;
-        let problem_iter = stmt.query_map(params![pattern], |row| from_row(row))?;
+/// Opens `initial` in `$VISUAL`, `$EDITOR` or vi and returns the text once
+/// the editor exits
+fn edit_text(initial: &str) -> Result<String, Box<dyn Error>> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // Editors such as "code --wait" come with arguments of their own
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
 
-        let mut problems = Vec::new();
-        for problem_result in problem_iter {
-            problems.push(problem_result?);
-        }
+    let (path, mut file) = create_temp_file("txt")?;
+    file.write_all(initial.as_bytes())?;
+    drop(file);
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status();
+    let text = std::fs::read_to_string(&path);
+    std::fs::remove_file(&path)?;
 
-        Ok(problems)
+    let status = status.map_err(|error| format!("Cannot run editor '{}': {}", editor, error))?;
+    if !status.success() {
+        return Err(format!("Editor '{}' exited with {}", editor, status).into());
     }
+    Ok(text?)
+}
 
-    fn get_problems_by_difficulty(&self, difficulty: &str) -> Result<Vec<Problem>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT * FROM problems WHERE difficulty = ?")?;
-        let problem_iter = stmt.query_map(params![difficulty], |row| from_row(row))?;
-
-        let mut problems = Vec::new();
-        for problem_result in problem_iter {
-            problems.push(problem_result?);
+/// A new, randomly named file in the temp directory. Creating it fails
+/// rather than opening whatever is already at the path, so a file or link
+/// put there by someone else is never written through; another name is
+/// tried instead.
+fn create_temp_file(extension: &str) -> std::io::Result<(PathBuf, std::fs::File)> {
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "shikari-{}-{:016x}.{}",
+            std::process::id(),
+            rand::random::<u64>(),
+            extension
+        ));
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
         }
+    }
+}
 
-        Ok(problems)
+/// Stable identity for matching a problem across databases: its link when
+/// present, otherwise its case-folded description
+fn problem_key(problem: &Problem) -> String {
+    match &problem.link {
+        Some(link) => link.trim().to_string(),
+        None => problem.description.trim().to_lowercase(),
     }
+}
 
-    fn search_problems(&self, keyword: &str) -> Result<Vec<Problem>> {
-        let search_pattern = format!("%{}%", keyword);
-        let mut stmt = self.conn.prepare(
-            "SELECT * FROM problems WHERE 
-            description LIKE ? OR 
-            category LIKE ? OR 
-            pattern LIKE ? OR 
-            comments LIKE ?",
-        )?;
+/// The clipboard's text as the problem link, or why it couldn't be read. Headless
+/// sessions have no clipboard at all, so that gets its own message.
+#[cfg(feature = "clipboard")]
+fn clipboard_link() -> std::result::Result<Link, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|error| {
+        format!(
+            "No clipboard available ({}); pass the link with --link instead",
+            error
+        )
+    })?;
+    let text = clipboard
+        .get_text()
+        .map_err(|error| format!("Couldn't read text from the clipboard: {}", error))?;
+    match text.trim() {
+        "" => Err("The clipboard is empty".to_string()),
+        url if url.contains(char::is_whitespace) => {
+            Err(format!("The clipboard doesn't hold a link: '{}'", url))
+        }
+        url => Ok(Link {
+            label: PROBLEM_LINK_LABEL.to_string(),
+            url: url.to_string(),
+        }),
+    }
+}
 
-        let problem_iter = stmt.query_map(
-            params![
-                search_pattern,
-                search_pattern,
-                search_pattern,
-                search_pattern
-            ],
-            |row| from_row(row),
-        )?;
+/// The problem's slug, e.g. `two-sum` for
+/// `https://leetcode.com/problems/two-sum/description/`, or `4A` for
+/// Codeforces' `problemset/problem/4/A` and `contest/4/problem/A`
+fn link_slug(link: &str) -> Option<String> {
+    let path = link.split(['?', '#']).next().unwrap_or(link);
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
 
-        let mut problems = Vec::new();
-        for problem_result in problem_iter {
-            problems.push(problem_result?);
+    if path.contains("/problems/") {
+        segments
+            .by_ref()
+            .find(|segment| *segment == "problems")
+            .and_then(|_| segments.next())
+            .map(str::to_string)
+    } else if path.contains("/problem/") {
+        let segments: Vec<&str> = segments.collect();
+        let at = segments.iter().position(|segment| *segment == "problem")?;
+        match (segments.get(at.wrapping_sub(1)), segments.get(at + 1..)) {
+            (Some(&"problemset"), Some([contest, index, ..])) => {
+                Some(format!("{}{}", contest, index))
+            }
+            (Some(contest), Some([index, ..])) => Some(format!("{}{}", contest, index)),
+            _ => segments.last().map(|segment| segment.to_string()),
         }
-
-        Ok(problems)
+    } else {
+        segments.next_back().map(str::to_string)
     }
+}
 
-    fn delete_problem(&self, id: i64) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM problems WHERE id = ?", params![id])?;
-        Ok(())
-    }
+/// The slug stored for `(source, slug)` uniqueness: only links to a known
+/// judge have one, as other sites' paths say little about the problem
+fn stored_slug(problem: &Problem) -> Option<String> {
+    problem.problem_url().and_then(|url| judge_slug(&url))
 }
 
-#[derive(Parser)]
-#[command(author, version, about, long_about = None)]
-#[command(propagate_version = true)]
-struct Cli {
-    /// Path to the SQLite database file
-    #[arg(short, long, default_value = "problems.db")]
-    database: PathBuf,
+/// `link`'s slug if it points at one of `KNOWN_SOURCES`
+fn judge_slug(link: &str) -> Option<String> {
+    link_source(link).and(link_slug(link))
+}
 
-    #[command(subcommand)]
-    command: Commands,
+/// Names of the fields (other than the ID) whose values differ
+fn differing_fields(a: &Problem, b: &Problem) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if a.description != b.description {
+        fields.push("description");
+    }
+    if a.all_links() != b.all_links() {
+        fields.push("links");
+    }
+    if a.tags != b.tags {
+        fields.push("tags");
+    }
+    if a.category != b.category {
+        fields.push("category");
+    }
+    if a.pattern != b.pattern {
+        fields.push("pattern");
+    }
+    if a.difficulty != b.difficulty {
+        fields.push("difficulty");
+    }
+    if a.time_to_solve_1st != b.time_to_solve_1st
+        || a.time_to_solve_2nd != b.time_to_solve_2nd
+        || a.time_to_solve_3rd != b.time_to_solve_3rd
+    {
+        fields.push("solve times");
+    }
+    if a.comments != b.comments {
+        fields.push("comments");
+    }
+    if a.should_solve_again != b.should_solve_again {
+        fields.push("review flag");
+    }
+    if a.company != b.company {
+        fields.push("company");
+    }
+    if a.frequency != b.frequency {
+        fields.push("frequency");
+    }
+    if a.personal_difficulty != b.personal_difficulty {
+        fields.push("personal difficulty");
+    }
+    if a.locked != b.locked {
+        fields.push("locked");
+    }
+    if a.source != b.source {
+        fields.push("source");
+    }
+    if a.focused != b.focused {
+        fields.push("focus");
+    }
+    fields
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// Add a new problem
+/// A field as `differing_fields` names it, formatted for showing before
+/// and after values; "-" when empty
+fn field_value(problem: &Problem, field: &str) -> String {
+    let optional = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    let minutes = |value: Option<f64>| value.map_or_else(|| "-".to_string(), humanize::minutes);
+    let yes_no = |set: bool| if set { "yes" } else { "no" }.to_string();
+    match field {
+        "description" => problem.description.clone(),
+        "links" => problem
+            .all_links()
+            .iter()
+            .map(|link| format!("{}={}", link.label, link.url))
+            .collect::<Vec<_>>()
+            .join(" "),
+        "tags" => problem.tags.join(", "),
+        "category" => optional(&problem.category),
+        "pattern" => optional(&problem.pattern),
+        "difficulty" => optional(&problem.difficulty),
+        "solve times" => format!(
+            "{}, {}, {}",
+            minutes(problem.time_to_solve_1st),
+            minutes(problem.time_to_solve_2nd),
+            minutes(problem.time_to_solve_3rd)
+        ),
+        "comments" => optional(&problem.comments),
+        "review flag" => yes_no(problem.should_solve_again),
+        "company" => optional(&problem.company),
+        "frequency" => optional(&problem.frequency.map(|frequency| frequency.to_string())),
+        "personal difficulty" => {
+            optional(&problem.personal_difficulty.map(|rating| rating.to_string()))
+        }
+        "locked" => yes_no(problem.locked),
+        "source" => problem.source.clone(),
+        "focus" => yes_no(problem.focused),
+        _ => unreachable!("differing_fields names no field '{}'", field),
+    }
+}
+
+struct ProblemDiff {
+    only_here: Vec<Problem>,
+    only_there: Vec<Problem>,
+    changed: Vec<(Problem, Problem, Vec<&'static str>)>,
+}
+
+fn diff_problems(here: Vec<Problem>, there: Vec<Problem>) -> ProblemDiff {
+    let mut there: BTreeMap<String, Problem> = there
+        .into_iter()
+        .map(|problem| (problem_key(&problem), problem))
+        .collect();
+
+    let mut diff = ProblemDiff {
+        only_here: Vec::new(),
+        only_there: Vec::new(),
+        changed: Vec::new(),
+    };
+
+    for problem in here {
+        match there.remove(&problem_key(&problem)) {
+            Some(other) => {
+                let fields = differing_fields(&problem, &other);
+                if !fields.is_empty() {
+                    diff.changed.push((problem, other, fields));
+                }
+            }
+            None => diff.only_here.push(problem),
+        }
+    }
+    diff.only_there = there.into_values().collect();
+
+    diff
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Attempt {
+    attempt: usize,
+    minutes: f64,
+    attempted_at: DateTime<Utc>,
+    /// False when the attempt ended in a give-up rather than a solve
+    solved: bool,
+    note: Option<String>,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "attempt {}, {}, {}",
+            self.attempt,
+            humanize::minutes(self.minutes),
+            if self.solved { "solved" } else { "gave up" }
+        )?;
+        if let Some(note) = &self.note {
+            write!(f, " - {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// One change recorded in the audit log
+struct AuditEntry {
+    logged_at: DateTime<Utc>,
+    command: String,
+    problem_id: Option<i64>,
+    description: String,
+}
+
+/// Companies are stored as one comma-separated value (e.g. `Google,Meta`).
+/// That keeps the schema flat and lets filters use a simple `LIKE`, at the
+/// cost of a join table's exact matching and per-company indexing.
+fn normalize_companies(companies: &str) -> Option<String> {
+    let names: Vec<&str> = companies
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(","))
+    }
+}
+
+struct PatternStats {
+    pattern: Option<String>,
+    count: i64,
+    avg_first_time: Option<f64>,
+    review_ratio: f64,
+}
+
+/// `--database` value for a throwaway in-memory database
+const MEMORY_DATABASE: &str = ":memory:";
+
+/// Source of problems added without one and of rows from before sources
+const DEFAULT_SOURCE: &str = "leetcode";
+
+/// Widest a value column of `compare` gets before values are cut short
+const COMPARE_WIDTH: usize = 40;
+
+/// Output width when neither --width nor a terminal gives one
+const DEFAULT_WIDTH: usize = 80;
+
+/// Narrowest a description is cut to, however little room the line leaves
+const MIN_DESCRIPTION_WIDTH: usize = 12;
+
+/// Views kept in the `recent_views` log; older ones are pruned
+const RECENT_VIEWS_KEPT: i64 = 100;
+
+/// How often `--watch` checks the database file for changes
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How long a statement waits on another process's lock before failing
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Times a write transaction is started before giving up on a lock
+const BUSY_ATTEMPTS: u32 = 5;
+
+/// Pause after the first failed attempt; doubles after each one
+const BUSY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// What a command reports once `BUSY_ATTEMPTS` are used up
+const DATABASE_LOCKED: &str =
+    "The database is locked by another shikari process; try again once it finishes";
+
+struct ProblemTracker {
+    conn: Connection,
+}
+
+/// Runs `op`, retrying with exponential backoff while another connection
+/// holds the database lock. Gives up after `BUSY_ATTEMPTS` with a
+/// `DatabaseBusy` error carrying `DATABASE_LOCKED`.
+fn retry_when_busy<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut backoff = BUSY_BACKOFF;
+    for attempt in 1..=BUSY_ATTEMPTS {
+        match op() {
+            Err(rusqlite::Error::SqliteFailure(error, _))
+                if matches!(
+                    error.code,
+                    ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked
+                ) =>
+            {
+                if attempt == BUSY_ATTEMPTS {
+                    return Err(rusqlite::Error::SqliteFailure(
+                        error,
+                        Some(DATABASE_LOCKED.to_string()),
+                    ));
+                }
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+    unreachable!("the last attempt always returns")
+}
+
+/// Whether `error` is the one `retry_when_busy` gives up with
+fn is_locked_error(error: &(dyn Error + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<rusqlite::Error>(),
+        Some(rusqlite::Error::SqliteFailure(_, Some(message))) if message == DATABASE_LOCKED
+    )
+}
+
+/// Unlocks an SQLCipher database with `key`, then reads from it so a wrong
+/// key fails here instead of on the command's first query
+fn apply_key(conn: &Connection, key: Option<&str>) -> Result<()> {
+    let Some(key) = key else {
+        return Ok(());
+    };
+    if !cfg!(feature = "encryption") {
+        return Err(rusqlite::Error::InvalidParameterName(
+            "An encryption key was given, but this build of shikari has no encryption \
+            support; rebuild it with --features encryption"
+                .to_string(),
+        ));
+    }
+
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|_| {
+            rusqlite::Error::InvalidParameterName(
+                "Wrong encryption key, or the database is not encrypted".to_string(),
+            )
+        })
+}
+
+/// The value of the query option `name` in a SQLite URI
+fn uri_option<'a>(uri: &'a str, name: &str) -> Option<&'a str> {
+    let (_, query) = uri.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|option| option.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Whether the URI's own options keep writes out: `mode=ro` or `immutable`
+fn uri_is_read_only(uri: &str) -> bool {
+    uri_option(uri, "mode") == Some("ro")
+        || uri_option(uri, "immutable")
+            .is_some_and(|value| matches!(value, "1" | "yes" | "true" | "on"))
+}
+
+/// Rejects what SQLite would fail on less clearly: a plain path, an unknown
+/// `mode`, and a writable `mode` alongside --read-only
+fn check_uri(uri: &str, read_only: bool) -> std::result::Result<(), String> {
+    if !uri.starts_with("file:") {
+        return Err(format!(
+            "--uri expects a file: URI such as file:problems.db?mode=ro, got '{}'",
+            uri
+        ));
+    }
+    match uri_option(uri, "mode") {
+        Some(mode @ ("rw" | "rwc")) if read_only => Err(format!(
+            "--read-only conflicts with mode={} in the URI",
+            mode
+        )),
+        Some("ro" | "rw" | "rwc" | "memory") | None => Ok(()),
+        Some(mode) => Err(format!(
+            "Unknown URI mode '{}', expected ro, rw, rwc or memory",
+            mode
+        )),
+    }
+}
+
+impl ProblemTracker {
+    /// Opens or creates the database, first creating its parent directory
+    /// when `create_dirs` is set
+    fn new(db_path: &str, create_dirs: bool, key: Option<&str>) -> Result<Self> {
+        schema::check_sqlite_version()?;
+
+        let conn = if db_path == MEMORY_DATABASE {
+            Connection::open_in_memory()?
+        } else {
+            if let Some(parent) = std::path::Path::new(db_path).parent()
+                && !parent.as_os_str().is_empty()
+                && !parent.exists()
+            {
+                if !create_dirs {
+                    return Err(rusqlite::Error::InvalidParameterName(format!(
+                        "Directory {} does not exist; create it or drop --no-create-dirs",
+                        parent.display()
+                    )));
+                }
+                std::fs::create_dir_all(parent).map_err(|error| {
+                    rusqlite::Error::InvalidParameterName(format!(
+                        "Could not create directory {}: {}",
+                        parent.display(),
+                        error
+                    ))
+                })?;
+            }
+            Connection::open(db_path)?
+        };
+        apply_key(&conn, key)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        Self::from_connection(conn)
+    }
+
+    /// Starts a transaction that takes the write lock up front, so waiting
+    /// on another process happens here rather than halfway through
+    fn begin_write(&self) -> Result<Transaction<'_>> {
+        retry_when_busy(|| Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate))
+    }
+
+    /// Wraps an already-open connection, migrating it to the current schema
+    fn from_connection(conn: Connection) -> Result<Self> {
+        schema::migrate(&conn)?;
+
+        Ok(ProblemTracker { conn })
+    }
+
+    /// Opens an existing database without write access or migrations
+    fn open_read_only(db_path: &str, key: Option<&str>) -> Result<Self> {
+        schema::check_sqlite_version()?;
+
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        apply_key(&conn, key)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        Ok(ProblemTracker { conn })
+    }
+
+    /// Opens a SQLite `file:` URI, whose query options (`mode`, `cache`,
+    /// `immutable`, ...) SQLite applies itself. Read-only opens skip the
+    /// migrations, as `open_read_only` does.
+    fn open_uri(uri: &str, read_only: bool, key: Option<&str>) -> Result<Self> {
+        schema::check_sqlite_version()?;
+
+        let access = if read_only {
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+        } else {
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE
+        };
+        let conn = Connection::open_with_flags(
+            uri,
+            access | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        apply_key(&conn, key)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        if read_only {
+            Ok(ProblemTracker { conn })
+        } else {
+            Self::from_connection(conn)
+        }
+    }
+
+    /// Copies the whole database to `path` with SQLite's online backup API,
+    /// encrypted with `key` like the original
+    fn backup_to(&self, path: &Path, key: Option<&str>) -> Result<()> {
+        let mut dest = Connection::open(path)?;
+        apply_key(&dest, key)?;
+        let backup = rusqlite::backup::Backup::new(&self.conn, &mut dest)?;
+        backup.run_to_completion(256, std::time::Duration::ZERO, None)
+    }
+
+    /// Adds a handful of well-known problems for demos and experiments
+    fn seed_samples(&self) -> Result<()> {
+        let samples = [
+            (
+                "Two Sum",
+                "two-sum",
+                "Arrays",
+                "hash map",
+                "Easy",
+                Some(10.0),
+                false,
+            ),
+            (
+                "Valid Parentheses",
+                "valid-parentheses",
+                "Stacks",
+                "stack",
+                "Easy",
+                Some(8.0),
+                false,
+            ),
+            (
+                "Longest Substring Without Repeating Characters",
+                "longest-substring-without-repeating-characters",
+                "Strings",
+                "sliding window",
+                "Medium",
+                Some(35.0),
+                true,
+            ),
+            (
+                "Number of Islands",
+                "number-of-islands",
+                "Graphs",
+                "dfs",
+                "Medium",
+                Some(25.0),
+                false,
+            ),
+            (
+                "Merge k Sorted Lists",
+                "merge-k-sorted-lists",
+                "Linked Lists",
+                "heap",
+                "Hard",
+                None,
+                true,
+            ),
+        ];
+
+        for (description, slug, category, pattern, difficulty, time, review) in samples {
+            let mut problem = Problem::new(description);
+            problem.link = Some(format!("https://leetcode.com/problems/{}/", slug));
+            problem.category = Some(category.to_string());
+            problem.pattern = Some(pattern.to_string());
+            problem.difficulty = Some(difficulty.to_string());
+            problem.time_to_solve_1st = time;
+            problem.should_solve_again = review;
+            self.add_problem(problem)?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends an entry to the audit log; called once per mutating method
+    fn log_change(&self, command: &str, problem_id: Option<i64>, description: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO audit_log (logged_at, command, problem_id, description)
+            VALUES (?, ?, ?, ?)",
+            params![Utc::now(), command, problem_id, description],
+        )?;
+
+        Ok(())
+    }
+
+    /// Other problems sharing at least one tag with problem `id`, with how
+    /// many they share, most shared first
+    fn get_similar(&self, id: i64, limit: usize) -> Result<Vec<(Problem, usize)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT other.problem_id, COUNT(*) AS shared FROM tags mine
+            JOIN tags other ON other.tag = mine.tag AND other.problem_id != mine.problem_id
+            JOIN problems ON problems.id = other.problem_id AND problems.deleted_at IS NULL
+            WHERE mine.problem_id = ?
+            GROUP BY other.problem_id
+            ORDER BY shared DESC, other.problem_id
+            LIMIT ?",
+        )?;
+        let row_iter = stmt.query_map(params![id, limit as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        let mut similar = Vec::new();
+        for row_result in row_iter {
+            let (other, shared) = row_result?;
+            similar.push((self.get_problem(other)?, shared));
+        }
+
+        Ok(similar)
+    }
+
+    /// Distinct non-blank values of a text column, ignoring case when sorting
+    fn get_distinct(&self, table: &str, column: &str) -> Result<Vec<String>> {
+        let live = match table {
+            "problems" => "deleted_at IS NULL",
+            _ => "problem_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)",
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT DISTINCT {column} FROM {table} WHERE TRIM({column}) != '' AND {live}
+            ORDER BY {column} COLLATE NOCASE"
+        ))?;
+        let value_iter = stmt.query_map([], |row| row.get(0))?;
+
+        let mut values = Vec::new();
+        for value_result in value_iter {
+            values.push(value_result?);
+        }
+
+        Ok(values)
+    }
+
+    /// Categories, patterns, tags and difficulties in use
+    fn get_meta(&self) -> Result<export::Meta> {
+        Ok(export::Meta {
+            categories: self.get_distinct("problems", "category")?,
+            patterns: self.get_distinct("problems", "pattern")?,
+            tags: self.get_distinct("tags", "tag")?,
+            difficulties: self.get_distinct("problems", "difficulty")?,
+        })
+    }
+
+    /// Every canonical category with its problem count, by name
+    fn get_categories(&self) -> Result<Vec<categories::Category>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT categories.id, categories.name, COUNT(problems.id) FROM categories
+            LEFT JOIN problems ON problems.category_id = categories.id
+                AND problems.deleted_at IS NULL
+            GROUP BY categories.id ORDER BY categories.name",
+        )?;
+        let category_iter = stmt.query_map([], |row| {
+            Ok(categories::Category {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                problems: row.get::<_, i64>(2)? as usize,
+            })
+        })?;
+
+        let mut categories = Vec::new();
+        for category_result in category_iter {
+            categories.push(category_result?);
+        }
+
+        Ok(categories)
+    }
+
+    /// Categories with when any of their problems was last attempted, in
+    /// `sort` order. Never-practiced categories go last for `recent` and
+    /// first for `least-recent`.
+    fn get_categories_by_practice(
+        &self,
+        sort: CategorySort,
+    ) -> Result<Vec<(categories::Category, Option<DateTime<Utc>>)>> {
+        let order = match sort {
+            CategorySort::Name => "categories.name",
+            CategorySort::Recent => {
+                "last_attempted_at IS NULL, last_attempted_at DESC, categories.name"
+            }
+            CategorySort::LeastRecent => {
+                "last_attempted_at IS NOT NULL, last_attempted_at, categories.name"
+            }
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT categories.id, categories.name, COUNT(DISTINCT problems.id),
+                MAX(attempts.attempted_at) AS last_attempted_at
+            FROM categories
+            LEFT JOIN problems ON problems.category_id = categories.id
+                AND problems.deleted_at IS NULL
+            LEFT JOIN attempts ON attempts.problem_id = problems.id
+            GROUP BY categories.id ORDER BY {}",
+            order
+        ))?;
+        let category_iter = stmt.query_map([], |row| {
+            Ok((
+                categories::Category {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    problems: row.get::<_, i64>(2)? as usize,
+                },
+                row.get(3)?,
+            ))
+        })?;
+
+        let mut categories = Vec::new();
+        for category_result in category_iter {
+            categories.push(category_result?);
+        }
+
+        Ok(categories)
+    }
+
+    /// Moves every problem from one category into another and drops the
+    /// emptied one. This renames the category rather than editing problems,
+    /// so locked problems move too.
+    fn merge_categories(
+        &self,
+        from: &categories::Category,
+        into: &categories::Category,
+    ) -> Result<usize> {
+        let tx = self.begin_write()?;
+
+        let moved = self.conn.execute(
+            "UPDATE problems SET category_id = ?, category = ? WHERE category_id = ?",
+            params![into.id, into.name, from.id],
+        )?;
+        self.conn
+            .execute("DELETE FROM categories WHERE id = ?", params![from.id])?;
+        self.log_change(
+            "categories",
+            None,
+            &format!("Merged category '{}' into '{}'", from.name, into.name),
+        )?;
+
+        tx.commit()?;
+        Ok(moved)
+    }
+
+    /// Sets the difficulty of every problem matching `filters`, leaving
+    /// locked ones alone unless `force`. Returns how many changed and how
+    /// many were skipped for being locked.
+    fn bulk_set_difficulty(
+        &self,
+        filters: &FilterArgs,
+        difficulty: &str,
+        force: bool,
+    ) -> Result<(usize, usize)> {
+        let tx = self.begin_write()?;
+
+        let (mut changed, mut skipped) = (0, 0);
+        for problem in self.get_matching(filters)? {
+            if problem.locked && !force {
+                skipped += 1;
+                continue;
+            }
+            changed += self.conn.execute(
+                "UPDATE problems SET difficulty = ?
+                WHERE id = ? AND difficulty IS NOT ? AND deleted_at IS NULL",
+                params![difficulty, problem.id, difficulty],
+            )?;
+        }
+        if changed > 0 {
+            self.log_change(
+                "set-difficulty",
+                None,
+                &format!(
+                    "Set the difficulty of {} problems to '{}'",
+                    changed, difficulty
+                ),
+            )?;
+        }
+
+        tx.commit()?;
+        Ok((changed, skipped))
+    }
+
+    /// Adds `tag` to, or with `remove` takes it off, every problem the search
+    /// finds. Returns how many problems changed and how many were skipped
+    /// for being locked.
+    fn tag_search_results(
+        &self,
+        keywords: &[String],
+        mode: SearchMode,
+        tag: &str,
+        remove: bool,
+        force: bool,
+    ) -> Result<(usize, usize)> {
+        let tx = self.begin_write()?;
+
+        let (mut changed, mut skipped) = (0, 0);
+        for problem in self.search_problems(keywords, mode)? {
+            if problem.locked && !force {
+                skipped += 1;
+                continue;
+            }
+            changed += if remove {
+                self.conn.execute(
+                    "DELETE FROM tags WHERE problem_id = ? AND tag = ?",
+                    params![problem.id, tag],
+                )?
+            } else {
+                self.conn.execute(
+                    "INSERT OR IGNORE INTO tags (problem_id, tag) VALUES (?, ?)",
+                    params![problem.id, tag],
+                )?
+            };
+        }
+        if changed > 0 {
+            let description = if remove {
+                format!("Removed tag '{}' from {} problems", tag, changed)
+            } else {
+                format!("Tagged {} problems '{}'", changed, tag)
+            };
+            self.log_change("tag-search", None, &description)?;
+        }
+
+        tx.commit()?;
+        Ok((changed, skipped))
+    }
+
+    /// Runs a statement with writes switched off, returning a header line of
+    /// column names then one tab-separated line per row. With `explain`,
+    /// returns SQLite's query plan instead, indented by nesting, without
+    /// running anything.
+    fn run_query(&self, sql: &str, explain: bool) -> Result<Vec<String>> {
+        if self.count_statements(sql) > 1 {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "query runs a single statement".to_string(),
+            ));
+        }
+        if explain {
+            let mut stmt = self.conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+            let mut depths: HashMap<i64, usize> = HashMap::new();
+            let mut lines = Vec::new();
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let (id, parent, detail): (i64, i64, String) =
+                    (row.get(0)?, row.get(1)?, row.get(3)?);
+                let depth = depths.get(&parent).map_or(0, |depth| depth + 1);
+                depths.insert(id, depth);
+                lines.push(format!("{}{}", "  ".repeat(depth), detail));
+            }
+            return Ok(lines);
+        }
+
+        // ATTACH gets past query_only but creates the file it names, so no
+        // database may be attached while the query runs. Both settings are
+        // put back whatever happens.
+        let attached = self.conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+        let lines = self
+            .conn
+            .pragma_update(None, "query_only", true)
+            .and_then(|_| self.read_rows(sql));
+        let reset = self.conn.pragma_update(None, "query_only", false);
+        self.conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, attached);
+        reset?;
+        match lines {
+            Err(rusqlite::Error::SqliteFailure(error, _))
+                if error.code == rusqlite::ErrorCode::ReadOnly =>
+            {
+                Err(rusqlite::Error::InvalidParameterName(
+                    "query only runs statements that read the database".to_string(),
+                ))
+            }
+            lines => lines,
+        }
+    }
+
+    /// How many statements `sql` holds. Text that fails to compile counts as
+    /// a statement, except at the start, where running it reports the error.
+    fn count_statements(&self, sql: &str) -> usize {
+        let mut batch = rusqlite::Batch::new(&self.conn, sql);
+        let mut statements = 0;
+        loop {
+            match batch.next() {
+                Ok(Some(_)) => statements += 1,
+                Ok(None) => return statements,
+                Err(_) => return statements + usize::from(statements > 0),
+            }
+        }
+    }
+
+    fn read_rows(&self, sql: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let columns = stmt.column_count();
+        let mut lines = vec![stmt.column_names().join("\t")];
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let mut fields = Vec::new();
+            for index in 0..columns {
+                fields.push(match row.get_ref(index)? {
+                    ValueRef::Null => String::new(),
+                    ValueRef::Integer(number) => number.to_string(),
+                    ValueRef::Real(number) => number.to_string(),
+                    ValueRef::Text(text) => {
+                        String::from_utf8_lossy(text).replace(['\t', '\n'], " ")
+                    }
+                    ValueRef::Blob(bytes) => format!("<{} bytes>", bytes.len()),
+                });
+            }
+            lines.push(fields.join("\t"));
+        }
+        Ok(lines)
+    }
+
+    /// Solved problems per category, keyed by the lowercased name
+    fn get_solved_by_category(&self) -> Result<HashMap<String, usize>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT lower(category), COUNT(*) FROM problems
+            WHERE category IS NOT NULL AND time_to_solve_1st IS NOT NULL AND deleted_at IS NULL
+            GROUP BY lower(category)",
+        )?;
+        let row_iter = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        let mut solved = HashMap::new();
+        for row_result in row_iter {
+            let (category, count) = row_result?;
+            solved.insert(category, count);
+        }
+        Ok(solved)
+    }
+
+    /// ID of the newest audit log entry, 0 when there is none
+    fn last_audit_id(&self) -> Result<i64> {
+        self.conn
+            .query_row("SELECT COALESCE(MAX(id), 0) FROM audit_log", [], |row| {
+                row.get(0)
+            })
+    }
+
+    /// The problem of each audit log entry after `audit_id`, oldest first;
+    /// `None` for changes not tied to one problem
+    fn get_changes_since(&self, audit_id: i64) -> Result<Vec<Option<i64>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT problem_id FROM audit_log WHERE id > ? ORDER BY id")?;
+        let row_iter = stmt.query_map(params![audit_id], |row| row.get(0))?;
+
+        let mut changes = Vec::new();
+        for row_result in row_iter {
+            changes.push(row_result?);
+        }
+        Ok(changes)
+    }
+
+    /// Most recent audit log entries first
+    fn get_audit_log(&self, limit: usize) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT logged_at, command, problem_id, description FROM audit_log
+            ORDER BY id DESC LIMIT ?",
+        )?;
+        let entry_iter = stmt.query_map(params![limit as i64], |row| {
+            Ok(AuditEntry {
+                logged_at: row.get(0)?,
+                command: row.get(1)?,
+                problem_id: row.get(2)?,
+                description: row.get(3)?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for entry_result in entry_iter {
+            entries.push(entry_result?);
+        }
+
+        Ok(entries)
+    }
+
+    fn add_problem(&self, problem: Problem) -> Result<i64> {
+        let tx = self.begin_write()?;
+
+        let id = self.insert_problem(&problem)?;
+        let times = [
+            problem.time_to_solve_1st,
+            problem.time_to_solve_2nd,
+            problem.time_to_solve_3rd,
+        ];
+        // Problems from an export bring their own history; otherwise the
+        // given times become attempts logged now
+        for attempt in &problem.attempts {
+            self.insert_attempt(id, attempt)?;
+        }
+        for (index, minutes) in times.into_iter().enumerate() {
+            if let Some(minutes) = minutes
+                && problem.attempts.is_empty()
+            {
+                self.record_attempt(id, index + 1, minutes, Utc::now())?;
+            }
+        }
+        self.log_change("add", Some(id), &format!("Added '{}'", problem.description))?;
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Looks up the canonical category matching `name` case-insensitively,
+    /// registering `name` as a new category when there is none
+    fn resolve_category(&self, name: Option<&str>) -> Result<(Option<i64>, Option<String>)> {
+        let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) else {
+            return Ok((None, None));
+        };
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO categories (name) VALUES (?)",
+            params![name],
+        )?;
+        self.conn.query_row(
+            "SELECT id, name FROM categories WHERE name = ?",
+            params![name],
+            |row| Ok((Some(row.get(0)?), Some(row.get(1)?))),
+        )
+    }
+
+    /// Inserts the problem row as-is, without logging any attempts
+    fn insert_problem(&self, problem: &Problem) -> Result<i64> {
+        let (category_id, category) = self.resolve_category(problem.category.as_deref())?;
+        let slug = self.unclaimed_slug(problem)?;
+        self.conn.execute(
+            "INSERT INTO problems (
+                description, link, category, pattern, difficulty,
+                time_to_solve_1st, time_to_solve_2nd, time_to_solve_3rd,
+                comments, should_solve_again, company, frequency, personal_difficulty,
+                locked, category_id, source, focus, slug, solution, solution_language
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                problem.description,
+                problem.problem_url(),
+                category,
+                problem.pattern,
+                problem.difficulty,
+                problem.time_to_solve_1st,
+                problem.time_to_solve_2nd,
+                problem.time_to_solve_3rd,
+                problem.comments,
+                problem.should_solve_again as i64,
+                problem.company,
+                problem.frequency,
+                problem.personal_difficulty,
+                problem.locked as i64,
+                category_id,
+                problem.source,
+                problem.focused as i64,
+                slug,
+                problem.solution,
+                problem.solution_language
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.write_links(id, problem)?;
+        self.write_tags(id, problem)?;
+
+        Ok(id)
+    }
+
+    /// The problem's `stored_slug`, failing when another problem from the
+    /// same source, trashed ones included, already has it
+    fn unclaimed_slug(&self, problem: &Problem) -> Result<Option<String>> {
+        let Some(slug) = stored_slug(problem) else {
+            return Ok(None);
+        };
+        let owner: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT id FROM problems WHERE source = ? AND slug = ? AND id IS NOT ?",
+                params![problem.source, slug, problem.id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match owner {
+            Some(owner) => Err(rusqlite::Error::InvalidParameterName(format!(
+                "Problem #{} already has the {} slug '{}'",
+                owner, problem.source, slug
+            ))),
+            None => Ok(Some(slug)),
+        }
+    }
+
+    /// Replaces the problem's stored links with its `all_links`
+    fn write_links(&self, id: i64, problem: &Problem) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM links WHERE problem_id = ?", params![id])?;
+        for link in problem.all_links() {
+            self.conn.execute(
+                "INSERT INTO links (problem_id, label, url) VALUES (?, ?, ?)",
+                params![id, link.label, link.url],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the problem's stored tags with its `tags`
+    fn write_tags(&self, id: i64, problem: &Problem) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM tags WHERE problem_id = ?", params![id])?;
+        for tag in &problem.tags {
+            self.conn.execute(
+                "INSERT INTO tags (problem_id, tag) VALUES (?, ?)",
+                params![id, tag],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn get_tags(&self, id: i64) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM tags WHERE problem_id = ? ORDER BY tag")?;
+        let tag_iter = stmt.query_map(params![id], |row| row.get(0))?;
+
+        let mut tags = Vec::new();
+        for tag_result in tag_iter {
+            tags.push(tag_result?);
+        }
+
+        Ok(tags)
+    }
+
+    fn get_links(&self, id: i64) -> Result<Vec<Link>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT label, url FROM links WHERE problem_id = ? ORDER BY id")?;
+        let link_iter = stmt.query_map(params![id], |row| {
+            Ok(Link {
+                label: row.get(0)?,
+                url: row.get(1)?,
+            })
+        })?;
+
+        let mut links = Vec::new();
+        for link_result in link_iter {
+            links.push(link_result?);
+        }
+
+        Ok(links)
+    }
+
+    /// Fills in each problem's labelled links and tags
+    fn with_links_and_tags(&self, mut problems: Vec<Problem>) -> Result<Vec<Problem>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT problem_id, label, url FROM links ORDER BY id")?;
+        let link_iter = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Link {
+                    label: row.get(1)?,
+                    url: row.get(2)?,
+                },
+            ))
+        })?;
+
+        let mut links: HashMap<i64, Vec<Link>> = HashMap::new();
+        for link_result in link_iter {
+            let (id, link) = link_result?;
+            links.entry(id).or_default().push(link);
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT problem_id, tag FROM tags ORDER BY tag")?;
+        let tag_iter = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for tag_result in tag_iter {
+            let (id, tag) = tag_result?;
+            tags.entry(id).or_default().push(tag);
+        }
+
+        for problem in &mut problems {
+            if let Some(id) = problem.id {
+                problem.links = links.remove(&id).unwrap_or_default();
+                problem.tags = tags.remove(&id).unwrap_or_default();
+            }
+        }
+
+        Ok(problems)
+    }
+
+    fn record_attempt(
+        &self,
+        id: i64,
+        attempt: usize,
+        minutes: f64,
+        attempted_at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.insert_attempt(
+            id,
+            &Attempt {
+                attempt,
+                minutes,
+                attempted_at,
+                solved: true,
+                note: None,
+            },
+        )
+    }
+
+    fn insert_attempt(&self, id: i64, attempt: &Attempt) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO attempts (problem_id, attempt, minutes, attempted_at, solved, note)
+            VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                id,
+                attempt.attempt as i64,
+                attempt.minutes,
+                attempt.attempted_at,
+                attempt.solved as i64,
+                attempt.note
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Fills in each problem's `attempts`, for exports that keep them
+    fn with_attempts(&self, mut problems: Vec<Problem>) -> Result<Vec<Problem>> {
+        for problem in &mut problems {
+            if let Some(id) = problem.id {
+                problem.attempts = self.get_attempts(id)?;
+            }
+        }
+
+        Ok(problems)
+    }
+
+    fn get_attempts(&self, id: i64) -> Result<Vec<Attempt>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT attempt, minutes, attempted_at, solved, note FROM attempts
+            WHERE problem_id = ? ORDER BY attempted_at",
+        )?;
+        let attempt_iter = stmt.query_map(params![id], |row| {
+            Ok(Attempt {
+                attempt: row.get::<_, i64>(0)? as usize,
+                minutes: row.get(1)?,
+                attempted_at: row.get(2)?,
+                solved: row.get::<_, i64>(3)? != 0,
+                note: row.get(4)?,
+            })
+        })?;
+
+        let mut attempts = Vec::new();
+        for attempt_result in attempt_iter {
+            attempts.push(attempt_result?);
+        }
+
+        Ok(attempts)
+    }
+
+    /// Every attempt at a current problem, oldest first. Solve times
+    /// recorded in the attempt columns without a logged attempt, from
+    /// before attempts were logged, come first with no date.
+    fn get_attempt_history(&self) -> Result<Vec<export::AttemptRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problem_id, attempt, minutes, attempted_at, solved FROM attempts
+            WHERE problem_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)
+            ORDER BY attempted_at, id",
+        )?;
+        let row_iter = stmt.query_map([], |row| {
+            Ok(export::AttemptRow {
+                problem_id: row.get(0)?,
+                attempt_number: row.get::<_, i64>(1)? as usize,
+                minutes: row.get(2)?,
+                solved_at: Some(row.get(3)?),
+                solved: row.get::<_, i64>(4)? != 0,
+            })
+        })?;
+        let mut logged = Vec::new();
+        for row_result in row_iter {
+            logged.push(row_result?);
+        }
+
+        let mut rows = Vec::new();
+        for problem in self.get_all_problems()? {
+            let Some(id) = problem.id else { continue };
+            let columns = [
+                problem.time_to_solve_1st,
+                problem.time_to_solve_2nd,
+                problem.time_to_solve_3rd,
+            ];
+            for (attempt_number, minutes) in (1..).zip(columns) {
+                let Some(minutes) = minutes else { continue };
+                let is_logged = logged
+                    .iter()
+                    .any(|row| row.problem_id == id && row.attempt_number == attempt_number);
+                if !is_logged {
+                    rows.push(export::AttemptRow {
+                        problem_id: id,
+                        attempt_number,
+                        minutes,
+                        solved_at: None,
+                        solved: true,
+                    });
+                }
+            }
+        }
+        rows.extend(logged);
+
+        Ok(rows)
+    }
+
+    /// Logs a solve; the browser's shortcut for `log_attempt`
+    #[cfg(any(test, feature = "tui"))]
+    fn update_solve_time(
+        &self,
+        id: i64,
+        attempt: usize,
+        minutes: f64,
+        attempted_at: DateTime<Utc>,
+        force: bool,
+    ) -> Result<()> {
+        self.log_attempt(
+            id,
+            &Attempt {
+                attempt,
+                minutes,
+                attempted_at,
+                solved: true,
+                note: None,
+            },
+            force,
+        )
+    }
+
+    /// Logs an attempt; a solve also becomes the attempt's solve time,
+    /// while a give-up leaves the time columns alone. Unless `force`, every
+    /// earlier attempt number must already be logged, so a mistyped number
+    /// can't leave a gap, and a locked problem is refused.
+    fn log_attempt(&self, id: i64, attempt: &Attempt, force: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+        self.write_attempt(id, attempt, force)?;
+        tx.commit()
+    }
+
+    /// Logs solves from `import-times` in one transaction, returning a
+    /// message per row that failed. Any failure rolls every row back, so a
+    /// corrected file can simply be imported again.
+    fn log_solve_times(
+        &self,
+        rows: &[import::SolveTime],
+        attempted_at: DateTime<Utc>,
+        force: bool,
+    ) -> Result<Vec<String>> {
+        let tx = self.begin_write()?;
+
+        let mut failures = Vec::new();
+        for row in rows {
+            let attempt = Attempt {
+                attempt: row.attempt,
+                minutes: row.minutes,
+                attempted_at,
+                solved: true,
+                note: None,
+            };
+            match self.write_attempt(row.id, &attempt, force) {
+                Ok(()) => {}
+                Err(rusqlite::Error::InvalidParameterName(message)) => {
+                    failures.push(format!("line {}: {}", row.line, message))
+                }
+                Err(rusqlite::Error::QueryReturnedNoRows) => failures.push(format!(
+                    "line {}: Problem with ID {} not found",
+                    row.line, row.id
+                )),
+                Err(error) => return Err(error),
+            }
+        }
+
+        if failures.is_empty() {
+            tx.commit()?;
+        }
+        Ok(failures)
+    }
+
+    /// `log_attempt` without its transaction
+    fn write_attempt(&self, id: i64, attempt: &Attempt, force: bool) -> Result<()> {
+        self.ensure_unlocked(id, force)?;
+        if attempt.solved {
+            self.set_solve_time_column(id, attempt.attempt, attempt.minutes)?;
+        } else {
+            // Fails for a missing problem, as setting the column would
+            self.get_problem(id)?;
+        }
+        let earlier: usize = self.conn.query_row(
+            "SELECT COUNT(DISTINCT attempt) FROM attempts WHERE problem_id = ? AND attempt < ?",
+            params![id, attempt.attempt as i64],
+            |row| row.get(0),
+        )?;
+        if !force && earlier + 1 < attempt.attempt {
+            return Err(rusqlite::Error::InvalidParameterName(format!(
+                "Attempt {} needs attempts 1 to {} logged first; pass --force to record it anyway",
+                attempt.attempt,
+                attempt.attempt - 1
+            )));
+        }
+        self.insert_attempt(id, attempt)?;
+        self.log_change(
+            "update-time",
+            Some(id),
+            &format!(
+                "Attempt {}: {} {} minutes",
+                attempt.attempt,
+                if attempt.solved {
+                    "solved in"
+                } else {
+                    "gave up after"
+                },
+                attempt.minutes
+            ),
+        )
+    }
+
+    /// Writes the attempt's time column without logging an attempt
+    fn set_solve_time_column(&self, id: i64, attempt: usize, minutes: f64) -> Result<()> {
+        let column = match attempt {
+            1 => "time_to_solve_1st",
+            2 => "time_to_solve_2nd",
+            3 => "time_to_solve_3rd",
+            _ => {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Attempt must be 1, 2, or 3".to_string(),
+                ))
+            }
+        };
+
+        let query = format!(
+            "UPDATE problems SET {} = ? WHERE id = ? AND deleted_at IS NULL",
+            column
+        );
+        let updated = self.conn.execute(&query, params![minutes, id])?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+
+        Ok(())
+    }
+
+    fn update_problem(&self, problem: &Problem, force: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        if let Some(id) = problem.id {
+            self.ensure_unlocked(id, force)?;
+        }
+        self.write_problem(problem)?;
+        self.log_change("edit", problem.id, "Updated fields")?;
+
+        tx.commit()
+    }
+
+    /// `update_problem` for an import that replaced or merged into the
+    /// problem. Each solve time it sets or changes is logged as an attempt
+    /// in the same transaction, dated from the incoming history when that
+    /// has the attempt and now otherwise.
+    fn update_imported(&self, problem: &Problem) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let id = problem.id.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_problem(id)?;
+        self.write_problem(problem)?;
+        let times = |problem: &Problem| {
+            [
+                problem.time_to_solve_1st,
+                problem.time_to_solve_2nd,
+                problem.time_to_solve_3rd,
+            ]
+        };
+        for (index, (before, after)) in times(&current).into_iter().zip(times(problem)).enumerate()
+        {
+            let Some(minutes) = after.filter(|_| after != before) else {
+                continue;
+            };
+            let logged = problem.attempts.iter().find(|logged| {
+                logged.attempt == index + 1 && logged.solved && logged.minutes == minutes
+            });
+            match logged {
+                Some(logged) => self.insert_attempt(id, logged)?,
+                None => self.record_attempt(id, index + 1, minutes, Utc::now())?,
+            }
+        }
+        self.log_change("import", Some(id), "Updated fields from an import")?;
+
+        tx.commit()
+    }
+
+    /// `update_problem` along with prerequisite changes, in one transaction
+    /// so a refused prerequisite leaves the problem as it was. Returns the
+    /// IDs in `removed_prereqs` that weren't prerequisites.
+    fn edit_problem(
+        &self,
+        problem: &Problem,
+        force: bool,
+        added_prereqs: &[i64],
+        removed_prereqs: &[i64],
+    ) -> Result<Vec<i64>> {
+        let tx = self.begin_write()?;
+
+        let mut missing = Vec::new();
+        if let Some(id) = problem.id {
+            self.ensure_unlocked(id, force)?;
+            for &prereq_id in removed_prereqs {
+                if !self.remove_prerequisite(id, prereq_id)? {
+                    missing.push(prereq_id);
+                }
+            }
+            for &prereq_id in added_prereqs {
+                self.add_prerequisite(id, prereq_id)?;
+            }
+        }
+        self.write_problem(problem)?;
+        self.log_change("edit", problem.id, "Updated fields")?;
+
+        tx.commit()?;
+        Ok(missing)
+    }
+
+    /// Writes back problems repaired by `lint --fix`, logging which checks
+    /// each one failed
+    fn save_lint_fixes(&self, fixes: &[(Problem, Vec<&lint::Check>)]) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        for (problem, checks) in fixes {
+            self.write_problem(problem)?;
+            let names: Vec<&str> = checks.iter().map(|check| check.name).collect();
+            self.log_change("lint", problem.id, &format!("Fixed {}", names.join(", ")))?;
+        }
+
+        tx.commit()
+    }
+
+    /// Rewrites difficulties that only differ from Easy, Medium or Hard in
+    /// case or surrounding spaces, skipping locked problems unless `force`.
+    /// Returns how many were rewritten and how many locked ones were skipped.
+    fn normalize_difficulties(&self, force: bool) -> Result<(usize, usize)> {
+        let tx = self.begin_write()?;
+
+        let miscased = "lower(trim(difficulty)) IN ('easy', 'medium', 'hard')
+            AND difficulty NOT IN ('Easy', 'Medium', 'Hard') AND deleted_at IS NULL";
+        let locked: i64 = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM problems WHERE {} AND locked != 0 AND NOT ?",
+                miscased
+            ),
+            params![force],
+            |row| row.get(0),
+        )?;
+        let changed = self.conn.execute(
+            &format!(
+                "UPDATE problems SET difficulty = CASE lower(trim(difficulty))
+                    WHEN 'easy' THEN 'Easy' WHEN 'medium' THEN 'Medium' ELSE 'Hard' END
+                WHERE {} AND (locked = 0 OR ?)",
+                miscased
+            ),
+            params![force],
+        )?;
+        if changed > 0 {
+            self.log_change(
+                "normalize-difficulty",
+                None,
+                &format!("Normalized {} difficulties", changed),
+            )?;
+        }
+
+        tx.commit()?;
+        Ok((changed, locked as usize))
+    }
+
+    /// Problems whose difficulty isn't any case of Easy, Medium or Hard
+    fn get_unknown_difficulties(&self) -> Result<Vec<(i64, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, difficulty FROM problems
+            WHERE lower(trim(difficulty)) NOT IN ('easy', 'medium', 'hard')
+            AND deleted_at IS NULL ORDER BY id",
+        )?;
+        let row_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut unknown = Vec::new();
+        for row_result in row_iter {
+            unknown.push(row_result?);
+        }
+        Ok(unknown)
+    }
+
+    /// Writes every stored field of the problem back to its row
+    fn write_problem(&self, problem: &Problem) -> Result<()> {
+        let (category_id, category) = self.resolve_category(problem.category.as_deref())?;
+        let slug = self.unclaimed_slug(problem)?;
+        let updated = self.conn.execute(
+            "UPDATE problems SET
+                description = ?, link = ?, category = ?, pattern = ?, difficulty = ?,
+                time_to_solve_1st = ?, time_to_solve_2nd = ?, time_to_solve_3rd = ?,
+                comments = ?, should_solve_again = ?, company = ?, frequency = ?,
+                personal_difficulty = ?, locked = ?, category_id = ?, source = ?,
+                focus = ?, slug = ?, solution = ?, solution_language = ?
+            WHERE id = ? AND deleted_at IS NULL",
+            params![
+                problem.description,
+                problem.problem_url(),
+                category,
+                problem.pattern,
+                problem.difficulty,
+                problem.time_to_solve_1st,
+                problem.time_to_solve_2nd,
+                problem.time_to_solve_3rd,
+                problem.comments,
+                problem.should_solve_again as i64,
+                problem.company,
+                problem.frequency,
+                problem.personal_difficulty,
+                problem.locked as i64,
+                category_id,
+                problem.source,
+                problem.focused as i64,
+                slug,
+                problem.solution,
+                problem.solution_language,
+                problem.id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        if let Some(id) = problem.id {
+            self.write_links(id, problem)?;
+            self.write_tags(id, problem)?;
+        }
+
+        Ok(())
+    }
+
+    /// The problem's solution code, if it has any; fails for a missing
+    /// problem
+    fn get_solution(&self, id: i64) -> Result<Option<String>> {
+        self.conn.query_row(
+            "SELECT solution FROM problems WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Replaces the problem's solution code, and its language when one is
+    /// given, or removes both for `None`
+    fn set_solution(
+        &self,
+        id: i64,
+        solution: Option<&str>,
+        language: Option<&str>,
+        force: bool,
+    ) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        self.ensure_unlocked(id, force)?;
+        let language = language.map(|language| language.trim().to_lowercase());
+        let updated = self.conn.execute(
+            "UPDATE problems SET solution = ?1,
+                solution_language = CASE WHEN ?1 IS NULL THEN NULL
+                    ELSE COALESCE(?2, solution_language) END
+            WHERE id = ?3 AND deleted_at IS NULL",
+            params![solution, language, id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        let description = match solution {
+            Some(code) => format!("Saved a {}-line solution", code.lines().count()),
+            None => "Removed the solution".to_string(),
+        };
+        self.log_change("set-solution", Some(id), &description)?;
+
+        tx.commit()
+    }
+
+    /// How many problems have a solution in each language
+    fn get_language_counts(&self) -> Result<BTreeMap<String, usize>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT solution_language, COUNT(*) FROM problems
+            WHERE solution_language IS NOT NULL AND deleted_at IS NULL
+            GROUP BY solution_language",
+        )?;
+        let count_iter = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        let mut counts = BTreeMap::new();
+        for count_result in count_iter {
+            let (language, count) = count_result?;
+            counts.insert(language, count);
+        }
+
+        Ok(counts)
+    }
+
+    /// Adds a dated line to the end of the problem's comments
+    fn append_comment(&self, id: i64, text: &str) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let mut problem = self.get_problem(id)?;
+        let line = format!("[{}] {}", clock::today().format("%Y-%m-%d"), text);
+        problem.comments = Some(match problem.comments {
+            Some(comments) if !comments.is_empty() => format!("{}\n{}", comments, line),
+            _ => line,
+        });
+        self.write_problem(&problem)?;
+        self.log_change("append-note", Some(id), text)?;
+
+        tx.commit()
+    }
+
+    /// Records `attempt` as solved and clears the review flag; a locked
+    /// problem is refused unless `force`
+    fn mark_solved(
+        &self,
+        id: i64,
+        attempt: usize,
+        minutes: f64,
+        attempted_at: DateTime<Utc>,
+        force: bool,
+    ) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        self.ensure_unlocked(id, force)?;
+        self.set_solve_time_column(id, attempt, minutes)?;
+        self.record_attempt(id, attempt, minutes, attempted_at)?;
+        self.conn.execute(
+            "UPDATE problems SET should_solve_again = 0 WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+        )?;
+        self.log_change(
+            "solved",
+            Some(id),
+            &format!("Attempt {}: {} minutes", attempt, minutes),
+        )?;
+
+        tx.commit()
+    }
+
+    /// Logs a solve as the attempt after the latest one solved, counting
+    /// solve times recorded before attempts were logged, and returns its
+    /// number
+    fn log_next_attempt(
+        &self,
+        id: i64,
+        minutes: f64,
+        attempted_at: DateTime<Utc>,
+    ) -> Result<usize> {
+        let tx = self.begin_write()?;
+
+        let problem = self.get_problem(id)?;
+        let logged: Option<usize> = self.conn.query_row(
+            "SELECT MAX(attempt) FROM attempts WHERE problem_id = ? AND solved = 1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        let columns = [
+            problem.time_to_solve_1st,
+            problem.time_to_solve_2nd,
+            problem.time_to_solve_3rd,
+        ];
+        let recorded = columns
+            .iter()
+            .rposition(Option::is_some)
+            .map(|index| index + 1);
+        let attempt = logged.max(recorded).unwrap_or(0) + 1;
+        if attempt > 3 {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "All three attempts are already recorded".to_string(),
+            ));
+        }
+
+        self.write_attempt(
+            id,
+            &Attempt {
+                attempt,
+                minutes,
+                attempted_at,
+                solved: true,
+                note: None,
+            },
+            false,
+        )?;
+
+        tx.commit()?;
+        Ok(attempt)
+    }
+
+    fn set_review_flag(&self, id: i64, review: bool) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE problems SET should_solve_again = ? WHERE id = ? AND deleted_at IS NULL",
+            params![review as i64, id],
+        )?;
+        if updated == 0 {
+            return Ok(());
+        }
+
+        let description = if review {
+            "Flagged for review"
+        } else {
+            "Cleared review flag"
+        };
+        self.log_change("review", Some(id), description)
+    }
+
+    /// Flags the problem for review when a solve took longer than
+    /// `threshold` minutes; returns whether it did
+    fn flag_slow_solve(&self, id: i64, minutes: f64, threshold: i64) -> Result<bool> {
+        if minutes <= threshold as f64 {
+            return Ok(false);
+        }
+
+        let tx = self.begin_write()?;
+        self.set_review_flag(id, true)?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn toggle_review_flag(&self, id: i64) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE problems SET should_solve_again = NOT should_solve_again
+            WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+        )?;
+        if updated == 0 {
+            return Ok(());
+        }
+
+        self.log_change("toggle-review", Some(id), "Toggled review flag")
+    }
+
+    /// Fails for a locked problem unless `force`; missing problems pass, so
+    /// callers still report them as not found
+    fn ensure_unlocked(&self, id: i64, force: bool) -> Result<()> {
+        let locked = self
+            .conn
+            .query_row(
+                "SELECT locked FROM problems WHERE id = ?",
+                params![id],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?;
+
+        if locked == Some(true) && !force {
+            return Err(rusqlite::Error::InvalidParameterName(locked_message(id)));
+        }
+
+        Ok(())
+    }
+
+    fn set_locked(&self, id: i64, locked: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let updated = self.conn.execute(
+            "UPDATE problems SET locked = ? WHERE id = ? AND deleted_at IS NULL",
+            params![locked as i64, id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        let command = if locked { "lock" } else { "unlock" };
+        self.log_change(
+            command,
+            Some(id),
+            if locked { "Locked" } else { "Unlocked" },
+        )?;
+
+        tx.commit()
+    }
+
+    /// Adds the problem to, or takes it off, the focus list
+    fn set_focus(&self, id: i64, focused: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let updated = self.conn.execute(
+            "UPDATE problems SET focus = ? WHERE id = ? AND deleted_at IS NULL",
+            params![focused as i64, id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        let command = if focused { "focus" } else { "unfocus" };
+        self.log_change(
+            command,
+            Some(id),
+            if focused {
+                "Added to the focus list"
+            } else {
+                "Removed from the focus list"
+            },
+        )?;
+
+        tx.commit()
+    }
+
+    fn set_personal_difficulty(&self, id: i64, rating: i64) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let updated = self.conn.execute(
+            "UPDATE problems SET personal_difficulty = ? WHERE id = ? AND deleted_at IS NULL",
+            params![rating, id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        self.log_change("rate", Some(id), &format!("Rated {}/5", rating))?;
+
+        tx.commit()
+    }
+
+    fn get_problem(&self, id: i64) -> Result<Problem> {
+        let mut problem = self.conn.query_row(
+            "SELECT * FROM problems WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+            from_row,
+        )?;
+        problem.links = self.get_links(id)?;
+        problem.tags = self.get_tags(id)?;
+
+        Ok(problem)
+    }
+
+    fn count_problems(&self) -> Result<usize> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM problems WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    fn get_all_problems(&self) -> Result<Vec<Problem>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM problems WHERE deleted_at IS NULL ORDER BY id")?;
+        let problem_iter = stmt.query_map([], from_row)?;
+
+        let mut problems = Vec::new();
+        for problem_result in problem_iter {
+            problems.push(problem_result?);
+        }
+
+        self.with_links_and_tags(problems)
+    }
+
+    fn get_problems_to_review(&self) -> Result<Vec<Problem>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM problems WHERE should_solve_again = 1 AND deleted_at IS NULL ORDER BY id")?;
+        let problem_iter = stmt.query_map([], from_row)?;
+
+        let mut problems = Vec::new();
+        for problem_result in problem_iter {
+            problems.push(problem_result?);
+        }
+
+        self.with_links_and_tags(problems)
+    }
+
+    /// Every prerequisite pair; those touching trashed problems only with
+    /// `with_trashed`, since a restore would bring them back
+    fn get_prerequisite_edges(&self, with_trashed: bool) -> Result<Vec<prereq::Edge>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problem_id, prereq_id FROM prerequisites
+            WHERE ?1 OR (
+                problem_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)
+                AND prereq_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)
+            )
+            ORDER BY problem_id, prereq_id",
+        )?;
+        let edge_iter =
+            stmt.query_map(params![with_trashed], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut edges = Vec::new();
+        for edge_result in edge_iter {
+            edges.push(edge_result?);
+        }
+
+        Ok(edges)
+    }
+
+    /// Makes `prereq_id` a prerequisite of problem `id`, refusing one that
+    /// would leave a problem depending on itself. Returns whether it is new.
+    /// Takes no transaction of its own; see `edit_problem`.
+    fn add_prerequisite(&self, id: i64, prereq_id: i64) -> Result<bool> {
+        if self.get_problem(prereq_id).is_err() {
+            return Err(rusqlite::Error::InvalidParameterName(format!(
+                "Problem with ID {} not found",
+                prereq_id
+            )));
+        }
+        if prereq::creates_cycle(&self.get_prerequisite_edges(true)?, id, prereq_id) {
+            return Err(rusqlite::Error::InvalidParameterName(format!(
+                "#{} can't be a prerequisite of #{}: it would end up depending on itself",
+                prereq_id, id
+            )));
+        }
+        let added = self.conn.execute(
+            "INSERT OR IGNORE INTO prerequisites (problem_id, prereq_id) VALUES (?, ?)",
+            params![id, prereq_id],
+        )? > 0;
+        if added {
+            self.log_change(
+                "edit",
+                Some(id),
+                &format!("Added prerequisite #{}", prereq_id),
+            )?;
+        }
+
+        Ok(added)
+    }
+
+    /// Returns whether `prereq_id` was a prerequisite of problem `id`. Like
+    /// `add_prerequisite`, takes no transaction of its own.
+    fn remove_prerequisite(&self, id: i64, prereq_id: i64) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM prerequisites WHERE problem_id = ? AND prereq_id = ?",
+            params![id, prereq_id],
+        )? > 0;
+        if removed {
+            self.log_change(
+                "edit",
+                Some(id),
+                &format!("Removed prerequisite #{}", prereq_id),
+            )?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Puts the problems on the named list, creating it if needed. Returns
+    /// how many weren't on it already.
+    fn add_to_list(&self, name: &str, ids: &[i64]) -> Result<usize> {
+        let tx = self.begin_write()?;
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO lists (name) VALUES (?)",
+            params![name],
+        )?;
+        let list_id: i64 = self.conn.query_row(
+            "SELECT id FROM lists WHERE name = ?",
+            params![name],
+            |row| row.get(0),
+        )?;
+        let mut added = 0;
+        for id in ids {
+            added += self.conn.execute(
+                "INSERT OR IGNORE INTO problem_lists (list_id, problem_id) VALUES (?, ?)",
+                params![list_id, id],
+            )?;
+        }
+        if added > 0 {
+            self.log_change(
+                "list",
+                None,
+                &format!("Added {} problems to list '{}'", added, name),
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// The named list's problems by ID, or `None` if there is no such list
+    fn get_list(&self, name: &str) -> Result<Option<Vec<Problem>>> {
+        let list_id: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT id FROM lists WHERE name = ?",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(list_id) = list_id else {
+            return Ok(None);
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT p.* FROM problems p
+            JOIN problem_lists l ON l.problem_id = p.id
+            WHERE l.list_id = ? AND p.deleted_at IS NULL ORDER BY p.id",
+        )?;
+        let problem_iter = stmt.query_map(params![list_id], from_row)?;
+
+        let mut problems = Vec::new();
+        for problem_result in problem_iter {
+            problems.push(problem_result?);
+        }
+
+        self.with_links_and_tags(problems).map(Some)
+    }
+
+    /// Problems with a recorded time, slowest or fastest first, with the
+    /// time they were ranked by
+    fn get_extremes(
+        &self,
+        basis: TimeBasis,
+        slowest: bool,
+        limit: usize,
+    ) -> Result<Vec<(f64, Problem)>> {
+        let minutes = match basis {
+            TimeBasis::First => "time_to_solve_1st",
+            // MIN skips nulls, unlike the scalar min(a, b, c)
+            TimeBasis::Best => {
+                "(SELECT MIN(value) FROM (SELECT time_to_solve_1st AS value
+                UNION ALL SELECT time_to_solve_2nd UNION ALL SELECT time_to_solve_3rd))"
+            }
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT *, {minutes} AS ranked_minutes FROM problems
+            WHERE ranked_minutes IS NOT NULL AND deleted_at IS NULL
+            ORDER BY ranked_minutes {}, id LIMIT ?",
+            if slowest { "DESC" } else { "ASC" }
+        ))?;
+        let row_iter = stmt.query_map(params![limit as i64], |row| {
+            Ok((row.get::<_, f64>("ranked_minutes")?, from_row(row)?))
+        })?;
+
+        let mut ranked = Vec::new();
+        for row_result in row_iter {
+            ranked.push(row_result?);
+        }
+
+        let (minutes, problems): (Vec<f64>, Vec<Problem>) = ranked.into_iter().unzip();
+        Ok(minutes
+            .into_iter()
+            .zip(self.with_links_and_tags(problems)?)
+            .collect())
+    }
+
+    /// Problems matching every given filter
+    fn get_matching(&self, filters: &FilterArgs) -> Result<Vec<Problem>> {
+        self.get_filtered("1", filters)
+    }
+
+    /// Problems matching every given filter with an ID above `since_id`.
+    /// New problems always get a higher ID than any still stored, so a
+    /// consumer can remember the largest ID it has seen and ask for the
+    /// rest. That breaks if the newest problem is purged (its ID is reused)
+    /// or after `renumber`.
+    fn get_matching_since(&self, since_id: i64, filters: &FilterArgs) -> Result<Vec<Problem>> {
+        self.get_filtered(&format!("id > {}", since_id), filters)
+    }
+
+    /// Problems meeting the SQL `condition` and every given filter; see
+    /// `filtered_query`
+    fn get_filtered(&self, condition: &str, filters: &FilterArgs) -> Result<Vec<Problem>> {
+        self.query_problems(filtered_query(condition, filters))
+    }
+
+    fn query_problems(&self, (query, values): ProblemQuery) -> Result<Vec<Problem>> {
+        self.fetch_problems(&query, params_from_iter(values))
+    }
+
+    /// Runs a `SELECT` of whole problem rows and fills in their links and tags
+    fn fetch_problems(&self, query: &str, params: impl rusqlite::Params) -> Result<Vec<Problem>> {
+        let mut stmt = self.conn.prepare(query)?;
+        let problem_iter = stmt.query_map(params, from_row)?;
+
+        let mut problems = Vec::new();
+        for problem_result in problem_iter {
+            problems.push(problem_result?);
+        }
+
+        self.with_links_and_tags(problems)
+    }
+
+    /// One page of the problems `query` finds, after `listing`'s filter and
+    /// sort. Everything happens in SQL, as `apply_listing_args` does in
+    /// memory, so only the page is loaded. Returns the page annotated, with
+    /// how many problems there were before paging.
+    fn get_listing(
+        &self,
+        (query, values): ProblemQuery,
+        listing: &ListingArgs,
+    ) -> Result<(Vec<Problem>, usize)> {
+        let cutoff = listing.last_days.map(days_ago_cutoff);
+        let condition = match cutoff {
+            Some(_) => "id IN (SELECT problem_id FROM attempts WHERE attempted_at >= ?)",
+            None => "1",
+        };
+        let mut params: Vec<&dyn ToSql> = values.iter().map(|value| value as &dyn ToSql).collect();
+        if let Some(cutoff) = &cutoff {
+            params.push(cutoff);
+        }
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({}) WHERE {}", query, condition),
+            params.as_slice(),
+            |row| row.get(0),
+        )?;
+
+        // Each key is ascending unless false; ties fall back to ID order
+        let keys: &[(&str, bool)] = match listing.sort_by {
+            None | Some(SortField::Id) => &[],
+            // Easiest first, other and missing difficulties last
+            Some(SortField::Difficulty) => &[(
+                "CASE lower(trim(difficulty))
+                    WHEN 'easy' THEN 0 WHEN 'medium' THEN 1 WHEN 'hard' THEN 2 ELSE 3 END",
+                true,
+            )],
+            // Most frequently asked first, problems without a frequency last
+            Some(SortField::Frequency) => &[("frequency IS NULL", true), ("frequency", false)],
+            // Hardest for me first, unrated problems last
+            Some(SortField::PersonalDifficulty) => &[
+                ("personal_difficulty IS NULL", true),
+                ("personal_difficulty", false),
+            ],
+            // Most attempts first
+            Some(SortField::Attempts) => &[(
+                "(SELECT COUNT(*) FROM attempts WHERE problem_id = listed.id)",
+                false,
+            )],
+        };
+        // --desc reverses every key, ties included
+        let order: Vec<String> = keys
+            .iter()
+            .chain(&[("id", true)])
+            .map(|(key, ascending)| {
+                let ascending = *ascending != listing.desc;
+                format!("{} {}", key, if ascending { "ASC" } else { "DESC" })
+            })
+            .collect();
+        // SQLite reads a negative LIMIT as none
+        let limit = listing
+            .limit
+            .map_or(-1, |limit| limit.min(i64::MAX as usize) as i64);
+        let offset = listing.offset.min(i64::MAX as usize) as i64;
+        params.extend([&limit as &dyn ToSql, &offset]);
+
+        let mut problems = self.fetch_problems(
+            &format!(
+                "SELECT * FROM ({}) AS listed WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+                query,
+                condition,
+                order.join(", ")
+            ),
+            params.as_slice(),
+        )?;
+        annotate(self, &mut problems)?;
+        Ok((problems, total as usize))
+    }
+
+    /// Problems where each keyword matches the description, category,
+    /// pattern or comments; `mode` decides whether all or any must match
+    fn search_problems(&self, keywords: &[String], mode: SearchMode) -> Result<Vec<Problem>> {
+        self.query_problems(search_query(keywords, mode))
+    }
+
+    /// How many problems `search_problems` would return, without fetching them
+    fn count_search_matches(&self, keywords: &[String], mode: SearchMode) -> Result<usize> {
+        let (condition, search_patterns) = search_condition(keywords, mode);
+        self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM problems WHERE ({}) AND deleted_at IS NULL",
+                condition
+            ),
+            params_from_iter(search_patterns),
+            |row| Ok(row.get::<_, i64>(0)? as usize),
+        )
+    }
+
+    /// Problems not flagged for review whose latest attempt is older than
+    /// `days`, least recently attempted first
+    fn get_stale_problems(&self, days: u32) -> Result<Vec<Problem>> {
+        let cutoff = Utc::now() - Duration::days(days as i64);
+        let mut stmt = self.conn.prepare(
+            "SELECT p.* FROM problems p
+            JOIN (
+                SELECT problem_id, MAX(attempted_at) AS last_attempted_at
+                FROM attempts GROUP BY problem_id
+            ) a ON a.problem_id = p.id
+            WHERE p.should_solve_again = 0 AND p.deleted_at IS NULL AND a.last_attempted_at < ?
+            ORDER BY a.last_attempted_at",
+        )?;
+        let problem_iter = stmt.query_map(params![cutoff], from_row)?;
+
+        let mut problems = Vec::new();
+        for problem_result in problem_iter {
+            problems.push(problem_result?);
+        }
+
+        self.with_links_and_tags(problems)
+    }
+
+    fn get_problem_ids_attempted_since(&self, cutoff: DateTime<Utc>) -> Result<HashSet<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT problem_id FROM attempts WHERE attempted_at >= ?")?;
+        let id_iter = stmt.query_map(params![cutoff], |row| row.get(0))?;
+
+        let mut ids = HashSet::new();
+        for id_result in id_iter {
+            ids.insert(id_result?);
+        }
+
+        Ok(ids)
+    }
+
+    /// Copies the problem and its attempts into `dest`, then removes it here
+    fn move_problem(&self, id: i64, dest: &ProblemTracker) -> Result<i64> {
+        let problem = self.get_problem(id)?;
+        let attempts = self.get_attempts(id)?;
+
+        let dest_tx = dest.begin_write()?;
+        let new_id = dest.insert_problem(&problem)?;
+        for attempt in &attempts {
+            dest.insert_attempt(new_id, attempt)?;
+        }
+        dest.log_change(
+            "move",
+            Some(new_id),
+            &format!("Moved in from another database (was #{})", id),
+        )?;
+        dest_tx.commit()?;
+
+        let tx = self.begin_write()?;
+        self.remove_problem_rows(id)?;
+        self.log_change(
+            "move",
+            Some(id),
+            &format!("Moved to another database as #{}", new_id),
+        )?;
+        tx.commit()?;
+
+        Ok(new_id)
+    }
+
+    /// Copies every problem into `dest` as a template, returning how many
+    fn export_template(&self, dest: &ProblemTracker) -> Result<usize> {
+        let problems = self.get_all_problems()?;
+        let count = problems.len();
+
+        let dest_tx = dest.begin_write()?;
+        for problem in problems {
+            dest.insert_problem(&problem.into_template())?;
+        }
+        dest.log_change(
+            "export-template",
+            None,
+            &format!("Created as a template of {} problems", count),
+        )?;
+        dest_tx.commit()?;
+
+        Ok(count)
+    }
+
+    /// Imports problems missing here from `other` and, for problems present in
+    /// both, any of its attempts newer than the latest local one. Returns the
+    /// number of problems added and updated.
+    fn sync_from(&self, other: &ProblemTracker) -> Result<(usize, usize)> {
+        let tx = self.begin_write()?;
+
+        let local: HashMap<String, i64> = self
+            .get_all_problems()?
+            .iter()
+            .filter_map(|problem| problem.id.map(|id| (problem_key(problem), id)))
+            .collect();
+
+        let mut added = 0;
+        let mut updated = 0;
+        for problem in other.get_all_problems()? {
+            let attempts = other.get_attempts(problem.id.unwrap_or(0))?;
+
+            match local.get(&problem_key(&problem)) {
+                None => {
+                    let id = self.insert_problem(&problem)?;
+                    for attempt in &attempts {
+                        self.insert_attempt(id, attempt)?;
+                    }
+                    added += 1;
+                }
+                Some(&id) => {
+                    let latest = self.get_attempts(id)?.last().map(|a| a.attempted_at);
+                    let newer: Vec<&Attempt> = attempts
+                        .iter()
+                        .filter(|a| latest.is_none_or(|latest| a.attempted_at > latest))
+                        .collect();
+
+                    for attempt in &newer {
+                        self.insert_attempt(id, attempt)?;
+                        self.set_solve_time_column(id, attempt.attempt, attempt.minutes)?;
+                    }
+                    if !newer.is_empty() {
+                        updated += 1;
+                    }
+                }
+            }
+        }
+
+        self.log_change(
+            "sync",
+            None,
+            &format!("Added {} and updated {} problems", added, updated),
+        )?;
+
+        tx.commit()?;
+        Ok((added, updated))
+    }
+
+    /// Per-pattern counts, average first-attempt time and share of problems
+    /// flagged for review, most practiced pattern first
+    fn get_pattern_stats(&self) -> Result<Vec<PatternStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT pattern, COUNT(*), AVG(time_to_solve_1st), AVG(should_solve_again)
+            FROM problems WHERE deleted_at IS NULL
+            GROUP BY pattern ORDER BY COUNT(*) DESC, pattern",
+        )?;
+        let stats_iter = stmt.query_map([], |row| {
+            Ok(PatternStats {
+                pattern: row.get(0)?,
+                count: row.get(1)?,
+                avg_first_time: row.get(2)?,
+                review_ratio: row.get(3)?,
+            })
+        })?;
+
+        let mut stats = Vec::new();
+        for stats_result in stats_iter {
+            stats.push(stats_result?);
+        }
+
+        Ok(stats)
+    }
+
+    /// Problem counts for each pattern and difficulty pairing in use
+    fn get_pattern_difficulty_counts(&self) -> Result<Vec<report::CoverageGroup>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT pattern, difficulty, COUNT(*) FROM problems WHERE deleted_at IS NULL
+            GROUP BY pattern, difficulty",
+        )?;
+        let counts_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+
+        let mut counts = Vec::new();
+        for count_result in counts_iter {
+            counts.push(count_result?);
+        }
+
+        Ok(counts)
+    }
+
+    /// Total attempt minutes, across give-ups as well as solves, for each
+    /// value of `by` (most time first), or as a single total
+    fn get_time_spent(&self, by: Option<GroupField>) -> Result<Vec<(Option<String>, i64)>> {
+        let Some(field) = by else {
+            let total = self.conn.query_row(
+                "SELECT CAST(ROUND(COALESCE(SUM(minutes), 0)) AS INTEGER) FROM attempts
+                WHERE problem_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)",
+                [],
+                |row| row.get(0),
+            )?;
+            return Ok(vec![(None, total)]);
+        };
+
+        let column = match field {
+            GroupField::Category => "category",
+            GroupField::Pattern => "pattern",
+            GroupField::Difficulty => "difficulty",
+        };
+        let query = format!(
+            "SELECT problems.{0}, CAST(ROUND(SUM(attempts.minutes)) AS INTEGER) FROM attempts
+            JOIN problems ON problems.id = attempts.problem_id
+            WHERE problems.deleted_at IS NULL
+            GROUP BY problems.{0} ORDER BY SUM(attempts.minutes) DESC, problems.{0}",
+            column
+        );
+        let mut stmt = self.conn.prepare(&query)?;
+        let spent_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut spent = Vec::new();
+        for spent_result in spent_iter {
+            spent.push(spent_result?);
+        }
+
+        Ok(spent)
+    }
+
+    /// Starts timing a practice session on the problem, replacing any
+    /// session already running. Returns the replaced session's problem.
+    fn start_session(&self, id: i64) -> Result<Option<i64>> {
+        let tx = self.begin_write()?;
+
+        self.get_problem(id)?;
+        let replaced = self
+            .conn
+            .query_row("SELECT problem_id FROM sessions", [], |row| row.get(0))
+            .optional()?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sessions (id, problem_id, started_at) VALUES (1, ?, ?)",
+            params![id, Utc::now()],
+        )?;
+        self.log_change("start", Some(id), "Started a practice session")?;
+
+        tx.commit()?;
+        Ok(replaced)
+    }
+
+    /// The problem being timed and when its session started
+    fn get_session(&self) -> Result<Option<(i64, DateTime<Utc>)>> {
+        self.conn
+            .query_row("SELECT problem_id, started_at FROM sessions", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()
+    }
+
+    /// Ends the session in progress, if any, without recording anything
+    fn clear_session(&self) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let Some((id, _)) = self.get_session()? else {
+            return Ok(());
+        };
+        self.conn.execute("DELETE FROM sessions", [])?;
+        self.log_change("finish", Some(id), "Ended the practice session")?;
+
+        tx.commit()
+    }
+
+    /// Logs that the problem was shown, keeping only the latest views
+    fn record_view(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO recent_views (problem_id, viewed_at) VALUES (?, ?)",
+            params![id, Utc::now()],
+        )?;
+        self.conn.execute(
+            "DELETE FROM recent_views WHERE id <= (SELECT MAX(id) FROM recent_views) - ?",
+            params![RECENT_VIEWS_KEPT],
+        )?;
+
+        Ok(())
+    }
+
+    /// Recently shown problems with when each was last shown, most recent first
+    fn get_recent_views(&self, limit: usize) -> Result<Vec<(Problem, DateTime<Utc>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problem_id, MAX(viewed_at) FROM recent_views
+            WHERE problem_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)
+            GROUP BY problem_id ORDER BY MAX(viewed_at) DESC LIMIT ?",
+        )?;
+        let view_iter = stmt.query_map(params![limit as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get(1)?))
+        })?;
+
+        let mut views = Vec::new();
+        for view_result in view_iter {
+            let (id, viewed_at) = view_result?;
+            views.push((self.get_problem(id)?, viewed_at));
+        }
+
+        Ok(views)
+    }
+
+    fn compute_score(&self, points: &score::Points) -> Result<score::Score> {
+        Ok(score::compute(&self.get_all_problems()?, points))
+    }
+
+    /// Number of logged attempts per problem, for problems with any
+    fn get_attempt_counts(&self) -> Result<HashMap<i64, usize>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT problem_id, COUNT(*) FROM attempts GROUP BY problem_id")?;
+        let row_iter =
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?;
+
+        let mut counts = HashMap::new();
+        for row_result in row_iter {
+            let (id, count) = row_result?;
+            counts.insert(id, count);
+        }
+
+        Ok(counts)
+    }
+
+    /// Minutes of each problem's earliest logged attempt and of its fastest
+    fn get_first_and_best_times(&self) -> Result<HashMap<i64, (f64, f64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problem_id,
+                (SELECT minutes FROM attempts first
+                WHERE first.problem_id = attempts.problem_id AND first.solved
+                ORDER BY attempted_at, id LIMIT 1),
+                MIN(minutes)
+            FROM attempts WHERE solved GROUP BY problem_id",
+        )?;
+        let row_iter = stmt.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?;
+
+        let mut times = HashMap::new();
+        for row_result in row_iter {
+            let (id, first_and_best) = row_result?;
+            times.insert(id, first_and_best);
+        }
+
+        Ok(times)
+    }
+
+    /// Average of every recorded solve time, per difficulty
+    fn get_average_time_by_difficulty(&self) -> Result<HashMap<String, f64>> {
+        let mut stmt = self.conn.prepare(
+            "WITH live AS (SELECT * FROM problems WHERE deleted_at IS NULL)
+            SELECT difficulty, AVG(minutes) FROM (
+                SELECT difficulty, time_to_solve_1st AS minutes FROM live
+                UNION ALL SELECT difficulty, time_to_solve_2nd FROM live
+                UNION ALL SELECT difficulty, time_to_solve_3rd FROM live
+            )
+            WHERE difficulty IS NOT NULL AND minutes IS NOT NULL
+            GROUP BY difficulty",
+        )?;
+        let row_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut averages = HashMap::new();
+        for row_result in row_iter {
+            let (difficulty, average) = row_result?;
+            averages.insert(difficulty, average);
+        }
+
+        Ok(averages)
+    }
+
+    /// Annotated problems and solution language counts, what `stats` needs
+    fn get_stats_inputs(&self) -> Result<(Vec<Problem>, BTreeMap<String, usize>)> {
+        let mut problems = self.get_all_problems()?;
+        annotate(self, &mut problems)?;
+        Ok((problems, self.get_language_counts()?))
+    }
+
+    /// `get_stats_inputs` for another database, opened read-only so that it
+    /// is never migrated or written to
+    fn other_stats_inputs(
+        path: &Path,
+        key: Option<&str>,
+    ) -> Result<(Vec<Problem>, BTreeMap<String, usize>)> {
+        ProblemTracker::open_read_only(&path.to_string_lossy(), key)?.get_stats_inputs()
+    }
+
+    /// Average first-attempt time over the problems of `difficulty`, in
+    /// any case; `None` when none of them has one
+    fn get_average_first_time(&self, difficulty: &str) -> Result<Option<f64>> {
+        self.conn.query_row(
+            "SELECT AVG(time_to_solve_1st) FROM problems
+            WHERE difficulty = ? COLLATE NOCASE AND deleted_at IS NULL",
+            params![difficulty],
+            |row| row.get(0),
+        )
+    }
+
+    /// Every solved attempt at a problem not in the trash, for period
+    /// reports
+    fn get_attempt_records(&self) -> Result<Vec<report::AttemptRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT attempts.problem_id, attempts.minutes, attempts.attempted_at
+            FROM attempts JOIN problems ON problems.id = attempts.problem_id
+            WHERE attempts.solved = 1 AND problems.deleted_at IS NULL",
+        )?;
+        let record_iter = stmt.query_map([], |row| {
+            Ok(report::AttemptRecord {
+                problem_id: row.get(0)?,
+                minutes: row.get(1)?,
+                attempted_at: row.get(2)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for record_result in record_iter {
+            records.push(record_result?);
+        }
+
+        Ok(records)
+    }
+
+    /// Solved attempts at problems with this pattern, in any case
+    fn get_pattern_attempt_records(&self, pattern: &str) -> Result<Vec<report::AttemptRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT attempts.problem_id, attempts.minutes, attempts.attempted_at
+            FROM attempts JOIN problems ON problems.id = attempts.problem_id
+            WHERE problems.pattern = ? COLLATE NOCASE AND attempts.solved = 1
+                AND problems.deleted_at IS NULL",
+        )?;
+        let record_iter = stmt.query_map(params![pattern], |row| {
+            Ok(report::AttemptRecord {
+                problem_id: row.get(0)?,
+                minutes: row.get(1)?,
+                attempted_at: row.get(2)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for record_result in record_iter {
+            records.push(record_result?);
+        }
+
+        Ok(records)
+    }
+
+    /// When each current problem was first seen: its audit log entry for
+    /// being added or moved in, or its first attempt, whichever is earlier.
+    /// Problems with neither, added before the audit log existed and never
+    /// attempted, are left out.
+    fn get_first_seen(&self) -> Result<Vec<DateTime<Utc>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problem_id, attempted_at FROM attempts
+            UNION ALL
+            SELECT problem_id, logged_at FROM audit_log
+            WHERE command IN ('add', 'move') AND problem_id IS NOT NULL",
+        )?;
+        let row_iter = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, DateTime<Utc>>(1)?))
+        })?;
+
+        let ids: HashSet<i64> = self
+            .get_all_problems()?
+            .iter()
+            .filter_map(|p| p.id)
+            .collect();
+        let mut first_seen: HashMap<i64, DateTime<Utc>> = HashMap::new();
+        for row_result in row_iter {
+            let (id, timestamp) = row_result?;
+            if ids.contains(&id) {
+                first_seen
+                    .entry(id)
+                    .and_modify(|seen| *seen = (*seen).min(timestamp))
+                    .or_insert(timestamp);
+            }
+        }
+
+        Ok(first_seen.into_values().collect())
+    }
+
+    /// Problems first seen on each of the last `days` days, oldest first;
+    /// see `get_first_seen` for what counts as being added
+    fn added_per_day(&self, days: usize) -> Result<Vec<(NaiveDate, usize)>> {
+        Ok(report::per_day(
+            &self.get_first_seen()?,
+            days,
+            clock::today(),
+        ))
+    }
+
+    /// Timestamp of each problem's most recent attempt
+    fn get_last_attempted(&self) -> Result<HashMap<i64, DateTime<Utc>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT problem_id, MAX(attempted_at) FROM attempts GROUP BY problem_id")?;
+        let row_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut last_attempted = HashMap::new();
+        for row_result in row_iter {
+            let (id, attempted_at) = row_result?;
+            last_attempted.insert(id, attempted_at);
+        }
+
+        Ok(last_attempted)
+    }
+
+    /// When each problem was last solved, ignoring give-ups
+    fn get_last_solved(&self) -> Result<HashMap<i64, DateTime<Utc>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problem_id, MAX(attempted_at) FROM attempts WHERE solved = 1
+            GROUP BY problem_id",
+        )?;
+        let row_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut last_solved = HashMap::new();
+        for row_result in row_iter {
+            let (id, solved_at) = row_result?;
+            last_solved.insert(id, solved_at);
+        }
+
+        Ok(last_solved)
+    }
+
+    /// Attempts at current problems since `cutoff`, and how many were solves
+    fn get_attempt_outcomes_since(&self, cutoff: DateTime<Utc>) -> Result<(usize, usize)> {
+        self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(solved), 0) FROM attempts
+            WHERE attempted_at >= ?
+            AND problem_id IN (SELECT id FROM problems WHERE deleted_at IS NULL)",
+            params![cutoff],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as usize,
+                ))
+            },
+        )
+    }
+
+    /// Problems whose most recent attempt was a give-up, with when it
+    /// happened, the longest-waiting first
+    fn get_failed_problems(&self) -> Result<Vec<(DateTime<Utc>, Problem)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT problems.*, latest.attempted_at AS gave_up_at FROM problems
+            JOIN attempts latest ON latest.problem_id = problems.id
+            WHERE latest.id = (
+                SELECT id FROM attempts WHERE problem_id = problems.id
+                ORDER BY attempted_at DESC, id DESC LIMIT 1
+            )
+            AND NOT latest.solved AND problems.deleted_at IS NULL
+            ORDER BY latest.attempted_at, problems.id",
+        )?;
+        let row_iter = stmt.query_map([], |row| {
+            Ok((row.get::<_, DateTime<Utc>>("gave_up_at")?, from_row(row)?))
+        })?;
+
+        let mut failed = Vec::new();
+        for row_result in row_iter {
+            failed.push(row_result?);
+        }
+
+        let (gave_up_at, problems): (Vec<DateTime<Utc>>, Vec<Problem>) = failed.into_iter().unzip();
+        Ok(gave_up_at
+            .into_iter()
+            .zip(self.with_links_and_tags(problems)?)
+            .collect())
+    }
+
+    fn set_goal(&self, minutes_per_day: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO goals (id, minutes_per_day) VALUES (1, ?)",
+            params![minutes_per_day],
+        )?;
+        self.log_change(
+            "set-goal",
+            None,
+            &format!("Daily goal set to {} minutes", minutes_per_day),
+        )
+    }
+
+    fn get_goal(&self) -> Result<Option<i64>> {
+        self.conn
+            .query_row(
+                "SELECT minutes_per_day FROM goals WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// Total minutes of attempts recorded since midnight in the configured zone
+    fn today_minutes(&self) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT CAST(ROUND(COALESCE(SUM(minutes), 0)) AS INTEGER) FROM attempts
+            WHERE attempted_at >= ?",
+            params![days_ago_cutoff(0)],
+            |row| row.get(0),
+        )
+    }
+
+    fn delete_problem(&self, id: i64, force: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        self.ensure_unlocked(id, force)?;
+        self.remove_problem_rows(id)?;
+        self.log_change("delete", Some(id), "Deleted")?;
+
+        tx.commit()
+    }
+
+    /// Wipes the problem's practice history: its attempts, solve times,
+    /// review flag and any running session. The problem itself stays.
+    fn reset_problem(&self, id: i64, force: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        self.ensure_unlocked(id, force)?;
+        let updated = self.conn.execute(
+            "UPDATE problems SET time_to_solve_1st = NULL, time_to_solve_2nd = NULL,
+            time_to_solve_3rd = NULL, should_solve_again = 0
+            WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        self.conn
+            .execute("DELETE FROM attempts WHERE problem_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM sessions WHERE problem_id = ?", params![id])?;
+        self.log_change("reset", Some(id), "Cleared attempts and review flag")?;
+
+        tx.commit()
+    }
+
+    /// Moves the problem to the trash, where only `get_trashed` sees it
+    /// until it is restored
+    fn trash_problem(&self, id: i64, force: bool) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        self.ensure_unlocked(id, force)?;
+        let updated = self.conn.execute(
+            "UPDATE problems SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+            params![Utc::now(), id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        self.conn
+            .execute("DELETE FROM sessions WHERE problem_id = ?", params![id])?;
+        self.log_change("delete", Some(id), "Moved to the trash")?;
+
+        tx.commit()
+    }
+
+    /// Takes the problem back out of the trash
+    fn restore_problem(&self, id: i64) -> Result<()> {
+        let tx = self.begin_write()?;
+
+        let updated = self.conn.execute(
+            "UPDATE problems SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
+        if updated == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        self.log_change("restore", Some(id), "Restored from the trash")?;
+
+        tx.commit()
+    }
+
+    fn get_trashed_problem(&self, id: i64) -> Result<Problem> {
+        let problem = self.conn.query_row(
+            "SELECT * FROM problems WHERE id = ? AND deleted_at IS NOT NULL",
+            params![id],
+            from_row,
+        )?;
+
+        Ok(self.with_links_and_tags(vec![problem])?.remove(0))
+    }
+
+    /// Problems in the trash with when each was deleted, most recent first
+    fn get_trashed(&self) -> Result<Vec<(DateTime<Utc>, Problem)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM problems WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id",
+        )?;
+        let row_iter = stmt.query_map([], |row| {
+            Ok((row.get::<_, DateTime<Utc>>("deleted_at")?, from_row(row)?))
+        })?;
+
+        let mut trashed = Vec::new();
+        for row_result in row_iter {
+            trashed.push(row_result?);
+        }
+
+        let (deleted_at, problems): (Vec<DateTime<Utc>>, Vec<Problem>) =
+            trashed.into_iter().unzip();
+        Ok(deleted_at
+            .into_iter()
+            .zip(self.with_links_and_tags(problems)?)
+            .collect())
+    }
+
+    /// Deletes for good every problem in the trash, or only those deleted
+    /// before `cutoff`, with their attempts, links and tags. Returns how
+    /// many were purged.
+    fn purge_trash(&self, cutoff: Option<DateTime<Utc>>) -> Result<usize> {
+        let tx = self.begin_write()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM problems WHERE deleted_at IS NOT NULL
+            AND (?1 IS NULL OR deleted_at < ?1)",
+        )?;
+        let ids = stmt
+            .query_map(params![cutoff], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<i64>>>()?;
+        for &id in &ids {
+            self.remove_problem_rows(id)?;
+        }
+        if !ids.is_empty() {
+            self.log_change(
+                "empty-trash",
+                None,
+                &format!("Purged {} problems from the trash", ids.len()),
+            )?;
+        }
+
+        drop(stmt);
+        tx.commit()?;
+        Ok(ids.len())
+    }
+
+    /// Reassigns IDs 1, 2, 3, ... in the current order, carrying attempts,
+    /// links, tags, views, list memberships, prerequisites and a running
+    /// session along. The audit log is append-only, so its entries keep the
+    /// old IDs. Returns how many problems changed ID.
+    fn renumber(&self) -> Result<usize> {
+        const TABLES: [(&str, &str); 9] = [
+            ("problems", "id"),
+            ("attempts", "problem_id"),
+            ("links", "problem_id"),
+            ("tags", "problem_id"),
+            ("recent_views", "problem_id"),
+            ("sessions", "problem_id"),
+            ("problem_lists", "problem_id"),
+            ("prerequisites", "problem_id"),
+            ("prerequisites", "prereq_id"),
+        ];
+        let tx = self.begin_write()?;
+
+        let mut stmt = self.conn.prepare("SELECT id FROM problems ORDER BY id")?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<i64>>>()?;
+        let moves: Vec<(i64, i64)> = (1..)
+            .zip(&ids)
+            .filter(|&(new, &old)| new != old)
+            .map(|(new, &old)| (old, new))
+            .collect();
+
+        // Parking moved rows at the negated new ID first means no new ID
+        // collides with an old one still waiting to move
+        for (table, column) in TABLES {
+            let mut park = self.conn.prepare(&format!(
+                "UPDATE {table} SET {column} = ? WHERE {column} = ?"
+            ))?;
+            for &(old, new) in &moves {
+                park.execute(params![-new, old])?;
+            }
+            self.conn.execute(
+                &format!("UPDATE {table} SET {column} = -{column} WHERE {column} < 0"),
+                [],
+            )?;
+        }
+        if !moves.is_empty() {
+            self.log_change(
+                "renumber",
+                None,
+                &format!("Renumbered {} problems to IDs 1-{}", moves.len(), ids.len()),
+            )?;
+        }
+
+        drop(stmt);
+        tx.commit()?;
+        Ok(moves.len())
+    }
+
+    /// Deletes the problem and its attempts without logging the change
+    fn remove_problem_rows(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM problems WHERE id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM attempts WHERE problem_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM links WHERE problem_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM tags WHERE problem_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM recent_views WHERE problem_id = ?", params![id])?;
+        self.conn
+            .execute("DELETE FROM sessions WHERE problem_id = ?", params![id])?;
+        self.conn.execute(
+            "DELETE FROM problem_lists WHERE problem_id = ?",
+            params![id],
+        )?;
+        self.conn.execute(
+            "DELETE FROM prerequisites WHERE problem_id = ?1 OR prereq_id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+struct Cli {
+    /// Path to the SQLite database file (":memory:" for a throwaway database)
+    #[arg(short, long, default_value = "problems.db")]
+    database: PathBuf,
+
+    /// Fill a ":memory:" database with sample problems
+    #[arg(long)]
+    seed: bool,
+
+    /// Open the database read-only and refuse commands that modify it
+    #[arg(long)]
+    read_only: bool,
+
+    /// Treat --database as a SQLite URI, e.g. "file:problems.db?immutable=1"
+    #[arg(long)]
+    uri: bool,
+
+    /// Fail instead of creating a database's missing parent directories
+    #[arg(long, global = true)]
+    no_create_dirs: bool,
+
+    /// Show dates as YYYY-MM-DD HH:MM instead of relative times
+    #[arg(long, global = true)]
+    absolute_dates: bool,
+
+    /// Show times as plain minutes even if the config asks for compact ones
+    #[arg(long, global = true)]
+    raw_minutes: bool,
+
+    /// Never color output, even on a terminal ($NO_COLOR does the same)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Leave the [REVIEW NEEDED] marker out of problem details
+    #[arg(long, global = true)]
+    hide_review_marker: bool,
+
+    /// Print JSON instead of text (stats only)
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Indent JSON output instead of writing it on one line
+    #[arg(long, global = true)]
+    pretty: bool,
+
+    /// Automatically confirm all prompts
+    #[arg(short, long, global = true)]
+    yes: bool,
+
+    /// Key for an encrypted database, else $SHIKARI_KEY (needs the `encryption` feature)
+    #[arg(long, global = true)]
+    key: Option<String>,
+
+    /// Columns to fit descriptions into in tables and grouped lists;
+    /// defaults to the terminal's width, or 80 when not writing to one
+    #[arg(long, global = true)]
+    width: Option<usize>,
+
+    /// Re-run a read command whenever the database file changes
+    #[arg(long, global = true)]
+    watch: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchMode {
+    And,
+    Or,
+}
+
+/// Spelt the same in `default_list_sort` as on the command line
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+enum SortField {
+    Id,
+    Difficulty,
+    Frequency,
+    PersonalDifficulty,
+    Attempts,
+}
+
+/// Which solve time `top` ranks by
+#[derive(Clone, Copy, ValueEnum)]
+enum TimeBasis {
+    /// The first attempt's time
+    First,
+    /// The fastest of the recorded times
+    Best,
+}
+
+/// Order of the `categories` listing
+#[derive(Clone, Copy, ValueEnum)]
+enum CategorySort {
+    Name,
+    /// Most recently practiced first
+    Recent,
+    /// Longest unpracticed first, for rotating through topics
+    LeastRecent,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GroupField {
+    Category,
+    Pattern,
+    Difficulty,
+}
+
+/// Header for problems without a value for the `--group-by` field
+const UNGROUPED: &str = "Uncategorized";
+
+/// Splits `problems` into runs sharing the `field` value, in name order with
+/// `UNGROUPED` last, keeping the given order within each group
+fn group_problems(problems: &[Problem], field: GroupField) -> Vec<(&str, Vec<&Problem>)> {
+    let mut groups: BTreeMap<Option<String>, (&str, Vec<&Problem>)> = BTreeMap::new();
+    for problem in problems {
+        let value = match field {
+            GroupField::Category => problem.category.as_deref(),
+            GroupField::Pattern => problem.pattern.as_deref(),
+            GroupField::Difficulty => problem.difficulty.as_deref(),
+        }
+        .filter(|value| !value.trim().is_empty());
+        groups
+            .entry(value.map(str::to_lowercase))
+            .or_insert_with(|| (value.unwrap_or(UNGROUPED), Vec::new()))
+            .1
+            .push(problem);
+    }
+
+    // None sorts first in the map, but belongs at the end
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    if groups.first().is_some_and(|(key, _)| key.is_none()) {
+        groups.rotate_left(1);
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Options shared by every command that prints a list of problems
+#[derive(Args, Default)]
+struct ListingArgs {
+    /// Only include problems attempted in the last N days (0 = today)
+    #[arg(long, value_parser = days_parser(0))]
+    last_days: Option<u32>,
+
+    /// Field to sort the results by
+    #[arg(long, value_enum)]
+    sort_by: Option<SortField>,
+
+    /// Reverse the sort order
+    #[arg(long)]
+    desc: bool,
+
+    /// Print tab-separated rows, one problem per line
+    #[arg(long)]
+    tsv: bool,
+
+    /// Show at most N problems
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Skip the first N problems
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+}
+
+/// Exact-match filters on a problem's labels, and bounds on its first
+/// solve time
+#[derive(Args, Default)]
+struct FilterArgs {
+    /// Only include problems in this category
+    #[arg(short = 'C', long)]
+    category: Option<String>,
+
+    /// Only include problems with this pattern
+    #[arg(short, long)]
+    pattern: Option<String>,
+
+    /// Only include problems with this difficulty
+    #[arg(short, long)]
+    difficulty: Option<String>,
+
+    /// Only include problems from this judge, e.g. codeforces
+    #[arg(long)]
+    source: Option<String>,
+
+    /// Only include problems whose first solve took at least this many minutes
+    #[arg(long, value_parser = parse_minutes)]
+    min_time: Option<f64>,
+
+    /// Only include problems whose first solve took at most this many minutes
+    #[arg(long, value_parser = parse_minutes)]
+    max_time: Option<f64>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Add a new problem
     Add {
+        /// Problem description; defaults to the link's slug with --from-clipboard
+        #[cfg_attr(feature = "clipboard", arg(required_unless_present = "from_clipboard"))]
+        #[cfg_attr(not(feature = "clipboard"), arg(required = true))]
+        description: Option<String>,
+
+        /// Take the problem link from the clipboard
+        #[cfg(feature = "clipboard")]
+        #[arg(long)]
+        from_clipboard: bool,
+
+        /// Problem link; repeat as label=url to add more (e.g. editorial=...)
+        #[arg(short, long, value_parser = parse_link)]
+        link: Vec<Link>,
+
+        /// Problem category
+        #[arg(short = 'C', long)]
+        category: Option<String>,
+
+        /// Problem pattern
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Problem difficulty
+        #[arg(short, long)]
+        difficulty: Option<String>,
+
+        /// Time to solve (first attempt) in minutes, e.g. 12 or 2.5
+        #[arg(short, long, value_parser = parse_minutes)]
+        time: Option<f64>,
+
+        /// Comments about the problem
+        #[arg(short, long)]
+        comments: Option<String>,
+
+        /// Should solve again
+        #[arg(short, long)]
+        review: bool,
+
+        /// Companies that ask this problem (comma-separated)
+        #[arg(long)]
+        company: Option<String>,
+
+        /// How frequently the problem is asked
+        #[arg(long)]
+        frequency: Option<f64>,
+
+        /// Tag the problem; repeatable
+        #[arg(long, value_parser = parse_tag)]
+        tag: Vec<String>,
+
+        /// Take the default category and pattern from this config profile
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Judge the problem is from; defaults to the link's site, else leetcode
+        #[arg(long, value_parser = parse_source)]
+        source: Option<String>,
+
+        /// Don't offer an existing category when the given one looks like a typo
+        #[arg(long)]
+        no_suggest: bool,
+
+        /// Cut a description longer than max_description_length down to it
+        #[arg(long)]
+        truncate: bool,
+    },
+    /// Add a problem by answering a prompt for each field
+    AddInteractive,
+    /// Edit an existing problem
+    Edit {
+        /// Problem ID
+        id: i64,
+
         /// Problem description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Set a link, as a bare URL or label=url; repeatable
+        #[arg(short, long, value_parser = parse_link)]
+        link: Vec<Link>,
+
+        /// Remove the link with this label; repeatable
+        #[arg(long, value_name = "LABEL")]
+        remove_link: Vec<String>,
+
+        /// Problem category
+        #[arg(short = 'C', long)]
+        category: Option<String>,
+
+        /// Problem pattern
+        #[arg(short, long)]
+        pattern: Option<String>,
+
+        /// Problem difficulty
+        #[arg(short, long)]
+        difficulty: Option<String>,
+
+        /// Comments about the problem
+        #[arg(short, long)]
+        comments: Option<String>,
+
+        /// Companies that ask this problem (comma-separated)
+        #[arg(long)]
+        company: Option<String>,
+
+        /// How frequently the problem is asked
+        #[arg(long)]
+        frequency: Option<f64>,
+
+        /// Personal difficulty rating from 1 (easy for me) to 5 (hard for me)
+        #[arg(long, value_parser = clap::value_parser!(i64).range(1..=5))]
+        rate: Option<i64>,
+
+        /// Add a tag; repeatable
+        #[arg(long, value_parser = parse_tag)]
+        tag: Vec<String>,
+
+        /// Remove a tag; repeatable
+        #[arg(long, value_parser = parse_tag)]
+        remove_tag: Vec<String>,
+
+        /// Add a problem to solve before this one, by ID; repeatable
+        #[arg(long, value_name = "ID")]
+        prereq: Vec<i64>,
+
+        /// Drop a prerequisite, by ID; repeatable
+        #[arg(long, value_name = "ID")]
+        remove_prereq: Vec<i64>,
+
+        /// Don't offer an existing category when the given one looks like a typo
+        #[arg(long)]
+        no_suggest: bool,
+
+        /// Cut a description longer than max_description_length down to it
+        #[arg(long)]
+        truncate: bool,
+
+        /// Edit even if the problem is locked
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Show a specific problem by ID
+    Show {
+        /// Problem ID
+        id: i64,
+
+        /// Also list every logged attempt, oldest first
+        #[arg(long)]
+        with_attempts: bool,
+
+        /// Render the comments as Markdown instead of printing them raw
+        #[arg(long)]
+        render: bool,
+
+        /// Print just this line, e.g. "{id} {description} ({difficulty}) {time1}"
+        #[arg(long, value_parser = template::parse, conflicts_with_all = ["with_attempts", "render"])]
+        template: Option<template::Template>,
+    },
+    /// Show two problems side by side, starring the fields that differ
+    Compare {
+        /// First problem ID
+        id1: i64,
+
+        /// Second problem ID
+        id2: i64,
+    },
+    /// Show the problems to solve before a problem, and those it leads to
+    Prereqs {
+        /// Problem ID
+        id: i64,
+    },
+    /// List every problem in an order that respects prerequisites
+    Path {
+        /// Leave out problems already solved
+        #[arg(long)]
+        unsolved: bool,
+    },
+    /// List problems sharing the most tags with a problem
+    Similar {
+        /// Problem ID
+        id: i64,
+
+        /// Maximum number of problems to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// List recently shown problems, most recent first
+    Recent {
+        /// Maximum number of problems to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// List a problem's links
+    Links {
+        /// Problem ID
+        id: i64,
+    },
+    /// Open a problem's link in the default browser
+    Open {
+        /// Problem ID
+        id: i64,
+
+        /// Label of the link to open
+        #[arg(long, default_value = PROBLEM_LINK_LABEL)]
+        label: String,
+    },
+    /// Browse problems in a full-screen interface
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Request every stored link and report those that don't answer with 2xx
+    #[cfg(feature = "network")]
+    CheckLinks {
+        /// Requests in flight at once
+        #[arg(long, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Seconds to wait for each link
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+
+        /// Flag problems with a broken link for review
+        #[arg(long)]
+        flag_broken: bool,
+    },
+    /// List all problems
+    List {
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+
+        /// Comma-separated columns to show, one line per problem
+        #[arg(long, value_delimiter = ',', value_parser = export::parse_column)]
+        columns: Option<Vec<usize>>,
+
+        /// Line the columns up in a table; without --columns, shows the ID,
+        /// description, difficulty, pattern and first time
+        #[arg(long, conflicts_with_all = ["group_by", "template", "tsv"])]
+        table: bool,
+
+        /// Print problems under a header for each value of this field
+        #[arg(long, value_enum, conflicts_with_all = ["columns", "tsv"])]
+        group_by: Option<GroupField>,
+
+        /// Print each problem as this line, e.g. "{id} {description} {time1}"
+        #[arg(long, value_parser = template::parse, conflicts_with_all = ["columns", "group_by", "tsv"])]
+        template: Option<template::Template>,
+    },
+    /// List problems that need review
+    Review {
+        #[command(flatten)]
+        listing: ListingArgs,
+
+        /// Keep only the N most overdue problems, those attempted longest
+        /// ago or never; defaults to the config's review_max
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        max: Option<u64>,
+
+        /// Output format, e.g. markdown for a cram sheet
+        #[arg(long, value_enum, default_value_t = export::ListingFormat::Plain, conflicts_with = "tsv")]
+        format: export::ListingFormat,
+    },
+    /// List problems that have never been attempted
+    Todo {
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems with no problem link, to fill in with edit --link
+    NoLink {
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems at a mastery level
+    ByMastery {
+        /// New (never attempted), learning, or mastered (solved in the last
+        /// 30 days, not slowly, and not flagged for review)
+        #[arg(value_enum)]
+        level: stats::Mastery,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems by category
+    ByCategory {
+        /// Category name
+        category: String,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems by pattern
+    ByPattern {
+        /// Pattern name
+        pattern: String,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems with a solution in a language
+    ByLanguage {
+        /// Language, as given to set-solution --lang
+        lang: String,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems by difficulty
+    ByDifficulty {
+        /// Difficulty level
+        difficulty: String,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems asked by a company
+    ByCompany {
+        /// Company name
+        company: String,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// List problems by tags
+    ByTags {
+        /// Comma-separated tags a problem must have every one of
+        #[arg(long, value_delimiter = ',', value_parser = parse_tag, required_unless_present = "any", conflicts_with = "any")]
+        all: Vec<String>,
+
+        /// Comma-separated tags a problem must have at least one of
+        #[arg(long, value_delimiter = ',', value_parser = parse_tag)]
+        any: Vec<String>,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Search problems by keyword
+    Search {
+        /// Search keywords (quote a phrase to search for it as one keyword)
+        #[arg(required = true)]
+        keywords: Vec<String>,
+
+        /// Whether all keywords or any keyword must match
+        #[arg(long, value_enum, default_value_t = SearchMode::And)]
+        mode: SearchMode,
+
+        /// Print only how many problems match
+        #[arg(long, conflicts_with_all = ["last_days", "sort_by", "desc", "tsv", "limit", "offset"])]
+        count_only: bool,
+
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Add a tag to, or remove one from, every problem a search finds
+    TagSearch {
+        /// Search keywords, as for search
+        #[arg(required = true)]
+        keywords: Vec<String>,
+
+        /// Whether all keywords or any keyword must match
+        #[arg(long, value_enum, default_value_t = SearchMode::And)]
+        mode: SearchMode,
+
+        /// Tag to add to every match
+        #[arg(long, value_parser = parse_tag, required_unless_present = "remove", conflicts_with = "remove")]
+        add: Option<String>,
+
+        /// Tag to remove from every match
+        #[arg(long, value_parser = parse_tag)]
+        remove: Option<String>,
+
+        /// Change locked problems too
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Update a problem's solve time
+    UpdateTime {
+        /// Problem ID
+        id: i64,
+
+        /// Attempt number (1, 2, or 3)
+        attempt: usize,
+
+        /// Time to solve in minutes, e.g. 12 or 2.5
+        #[arg(value_parser = parse_minutes)]
+        minutes: f64,
+
+        /// When the attempt happened (YYYY-MM-DD or YYYY-MM-DD HH:MM), defaults to now
+        // Parsed once the configured timezone is known
+        #[arg(long, value_name = "DATE")]
+        on: Option<String>,
+
+        /// Allow an attempt date in the future or an attempt number with
+        /// earlier ones missing, and update a locked problem
+        #[arg(short, long)]
+        force: bool,
+
+        /// Log the attempt as a give-up, leaving the solve time unchanged
+        #[arg(long)]
+        gave_up: bool,
+
+        /// Note about how the attempt went
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Don't flag the problem for review when the time is above auto_review_minutes
+        #[arg(long)]
+        no_auto_review: bool,
+    },
+    /// Record a solve time and clear the review flag
+    Solved {
+        /// Problem ID
+        id: i64,
+
+        /// Time to solve in minutes, e.g. 12 or 2.5
+        #[arg(value_parser = parse_minutes)]
+        minutes: f64,
+
+        /// When the attempt happened (YYYY-MM-DD or YYYY-MM-DD HH:MM), defaults to now
+        // Parsed once the configured timezone is known
+        #[arg(long, value_name = "DATE")]
+        on: Option<String>,
+
+        /// Allow an attempt date in the future, and update a locked problem
+        #[arg(short, long)]
+        force: bool,
+
+        /// Don't flag the problem for review when the time is above auto_review_minutes
+        #[arg(long)]
+        no_auto_review: bool,
+    },
+    /// Record a solve as the next attempt, without naming its number
+    Log {
+        /// Problem ID
+        id: i64,
+
+        /// Time to solve in minutes, e.g. 12 or 2.5
+        #[arg(value_parser = parse_minutes)]
+        minutes: f64,
+
+        /// Don't flag the problem for review when the time is above auto_review_minutes
+        #[arg(long)]
+        no_auto_review: bool,
+    },
+    /// Change a problem's pattern, picking from those in use or typing a new one
+    Reclassify {
+        /// Problem ID
+        id: i64,
+
+        /// Change a locked problem too
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Toggle a problem's review flag
+    ToggleReview {
+        /// Problem ID
+        id: i64,
+    },
+    /// Rate how hard a problem is for you, from 1 to 5
+    Rate {
+        /// Problem ID
+        id: i64,
+
+        /// Rating from 1 (easy for me) to 5 (hard for me)
+        #[arg(value_parser = clap::value_parser!(i64).range(1..=5))]
+        value: i64,
+    },
+    /// Set the difficulty of every problem matching the filters
+    SetDifficulty {
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        /// New difficulty
+        #[arg(long)]
+        to: String,
+
+        /// Change locked problems too
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Save a problem's solution code, read from stdin or else written in $EDITOR
+    SetSolution {
+        /// Problem ID
+        id: i64,
+
+        /// Language the solution is written in, e.g. rust; kept from the
+        /// previous solution when omitted
+        #[arg(long, conflicts_with = "clear")]
+        lang: Option<String>,
+
+        /// Remove the saved solution, and its language, instead
+        #[arg(long)]
+        clear: bool,
+
+        /// Change the solution even if the problem is locked
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Print a problem's saved solution code
+    Solution {
+        /// Problem ID
+        id: i64,
+    },
+    /// Append a dated note to a problem's comments
+    AppendNote {
+        /// Problem ID
+        id: i64,
+
+        /// Note text
+        text: String,
+    },
+    /// Move a problem into another database
+    Move {
+        /// Problem ID
+        id: i64,
+
+        /// Path to the destination database file
+        dest_db: PathBuf,
+    },
+    /// Create a new database with every problem but none of your times,
+    /// notes or flags, for sharing as a practice pack
+    ExportTemplate {
+        /// Path of the database to create; it must not exist yet
+        dest_db: PathBuf,
+    },
+    /// Compare problems with another database
+    Diff {
+        /// Path to the other database file
+        other_db: PathBuf,
+    },
+    /// Import new problems and attempts from another database
+    Sync {
+        /// Path to the other database file
+        other_db: PathBuf,
+    },
+    /// Export all problems, or only those matching the filters
+    Export {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = export::ExportFormat::Json)]
+        format: export::ExportFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Leave out the CSV or TSV header row
+        #[arg(long)]
+        no_header: bool,
+
+        /// CSV field separator, e.g. ';' for spreadsheets in many European locales
+        #[arg(long, value_parser = export::parse_delimiter)]
+        delimiter: Option<u8>,
+
+        /// Only problems with a higher ID, for picking up where an earlier
+        /// export stopped (IDs are reused after deleting the newest problem
+        /// and change with renumber)
+        #[arg(long, value_name = "ID")]
+        since_id: Option<i64>,
+
+        /// Comma-separated columns to leave out, e.g. comments,time_to_solve_1st
+        #[arg(long, value_delimiter = ',', value_parser = export::parse_column)]
+        exclude: Vec<usize>,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+    },
+    /// Export every logged attempt, one row each, e.g. for plotting
+    ExportAttempts {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = export::AttemptsFormat::Csv)]
+        format: export::AttemptsFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import problems from a JSON export or a CSV file
+    Import {
+        /// Path to the JSON or CSV file
+        file: PathBuf,
+
+        /// Read a field from a differently named CSV column, e.g.
+        /// "description=Name,difficulty=Level" for a Notion export
+        #[arg(long, value_delimiter = ',', value_parser = import::parse_mapping, value_name = "FIELD=COLUMN")]
+        map: Vec<(String, String)>,
+
+        /// Field used to detect problems that already exist; defaults to
+        /// the link, or the description for records without one
+        #[arg(long, value_enum)]
+        dedupe_on: Option<import::DedupeField>,
+
+        /// What to do with problems that already exist
+        #[arg(long, value_enum, default_value_t = import::ConflictStrategy::Skip)]
+        on_conflict: import::ConflictStrategy,
+
+        /// Don't show a progress bar for large imports
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Guess the pattern of problems without one from keywords in their
+        /// description, e.g. "subarray" for sliding window
+        #[arg(long)]
+        infer_pattern: bool,
+
+        /// Also put every problem in the file, new or already stored, on
+        /// this named list, e.g. "Blind 75"
+        #[arg(long, value_name = "NAME")]
+        as_list: Option<String>,
+    },
+    /// Show how many problems of a named list are solved
+    ListProgress {
+        /// List name, as given to import --as-list
+        name: String,
+    },
+    /// Add a problem for each URL in a file, one per line
+    ImportUrls {
+        /// Path to the list of URLs; blank lines and lines starting with # are ignored
+        file: PathBuf,
+    },
+    /// Log solve times from a CSV with id, attempt and minutes columns
+    ImportTimes {
+        /// Path to the CSV; nothing is logged unless every row is valid
+        file: PathBuf,
+
+        /// When the solves happened (YYYY-MM-DD or YYYY-MM-DD HH:MM), defaults to now
+        // Parsed once the configured timezone is known
+        #[arg(long, value_name = "DATE")]
+        on: Option<String>,
+
+        /// Allow a date in the future or attempt numbers with earlier ones
+        /// missing, and update locked problems
+        #[arg(short, long)]
+        force: bool,
+
+        /// Don't flag problems for review when a time is above auto_review_minutes
+        #[arg(long)]
+        no_auto_review: bool,
+    },
+    /// Show overall statistics
+    Stats {
+        /// Average only first-attempt times
+        #[arg(long, conflicts_with = "latest_only")]
+        first_only: bool,
+
+        /// Average only each problem's latest attempt time
+        #[arg(long)]
+        latest_only: bool,
+    },
+    /// Show stats across this database and others, combined and per database
+    AggregateStats {
+        /// Other databases to include
         #[arg(required = true)]
-        description: String,
+        databases: Vec<PathBuf>,
+    },
+    /// Show problems solved, minutes and new problems per week or month
+    Report {
+        /// Length of each period
+        #[arg(long, value_enum, default_value_t = report::Period::Week)]
+        period: report::Period,
+
+        /// Number of periods to show, ending with the current one
+        #[arg(short = 'n', long, default_value_t = 8)]
+        periods: usize,
+    },
+    /// Show problem counts, times and review ratios per pattern
+    PatternStats,
+    /// Show problem counts per pattern and difficulty, marking the gaps
+    Coverage,
+    /// Show the 25th, 50th, 75th and 90th percentile first-attempt times,
+    /// overall and per difficulty
+    Percentiles,
+    /// Total the minutes of every logged attempt
+    TimeSpent {
+        /// Break the total down by this field
+        #[arg(long, value_enum)]
+        by: Option<GroupField>,
+    },
+    /// Chart how many problems were added on each recent day
+    Intake {
+        /// Number of days to show, ending today
+        #[arg(long, default_value_t = 14, value_parser = days_parser(1))]
+        days: u32,
+    },
+    /// Chart a pattern's average solve time month by month
+    Trend {
+        /// Pattern to chart
+        pattern: String,
+    },
+    /// List categories with their problem counts
+    Categories {
+        /// Offer to merge categories that look like the same one, such as
+        /// "DP" and "Dynamic Programming"
+        #[arg(long)]
+        merge_similar: bool,
+
+        /// Order to list categories in
+        #[arg(long, value_enum, default_value_t = CategorySort::Name, conflicts_with = "merge_similar")]
+        sort: CategorySort,
+    },
+    /// Show a points total weighted by difficulty
+    Score,
+    /// Recommend problems from your weakest patterns
+    Weak {
+        /// Skip problems attempted within this many days
+        #[arg(long, default_value_t = 7)]
+        recent_days: i64,
+
+        /// Maximum number of suggestions
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Rank every problem by how urgently it wants practice
+    Queue {
+        /// Number of problems to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Score interview readiness from 0 to 100 from pattern coverage, recent
+    /// solve rate and freshness, and show what's holding it back
+    Readiness,
+    /// List the problems that took longest, or shortest, to solve
+    Top {
+        /// Longest times first (the default)
+        #[arg(long)]
+        slowest: bool,
+
+        /// Shortest times first
+        #[arg(long, conflicts_with = "slowest")]
+        fastest: bool,
+
+        /// Which recorded time to rank by
+        #[arg(long, value_enum, default_value_t = TimeBasis::First)]
+        by: TimeBasis,
+
+        /// Number of problems to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// List problems whose last attempt was a give-up, longest-waiting first
+    Retry {
+        /// Maximum number of problems to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// List attempted problems most likely forgotten by now
+    Forgetting {
+        /// Maximum number of problems to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Set the daily practice goal
+    SetGoal {
+        /// Target practice minutes per day
+        minutes_per_day: i64,
+    },
+    /// Show progress towards today's practice goal
+    Goal,
+    /// Show solved problems against each category's target from the config
+    Targets,
+    /// List problems not attempted recently
+    Stale {
+        /// Days since the last attempt
+        #[arg(long, default_value_t = 30, value_parser = days_parser(0))]
+        days: u32,
+
+        /// Flag the stale problems for review
+        #[arg(long)]
+        flag: bool,
+    },
+    /// Generate a day-by-day study plan
+    Plan {
+        /// Number of days to plan
+        #[arg(long, default_value_t = 7)]
+        days: usize,
+
+        /// Problems per day
+        #[arg(long, default_value_t = 3)]
+        per_day: usize,
+
+        /// Seed for reproducing a previous plan
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Print the plan as a markdown table
+        #[arg(long)]
+        markdown: bool,
+    },
+    /// Start timing a problem; finish records the time as an attempt
+    Start {
+        /// Problem ID
+        id: i64,
+    },
+    /// Record the time since start as a solve of the started problem
+    Finish {
+        /// Stop the session without recording an attempt
+        #[arg(long)]
+        discard: bool,
+
+        /// Record the time even if the problem is locked
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Show the problem being timed and for how long
+    Status,
+    /// Pick a random problem, optionally offering others until one suits
+    Random {
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        /// Only pick from the focus list
+        #[arg(long)]
+        focus_only: bool,
+
+        /// Keep offering problems: n for another, s to start a session on
+        /// it, q to quit
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// Favor problems by how urgently they want practice, weighed as
+        /// queue does, instead of picking every problem equally often
+        #[arg(short, long)]
+        weighted: bool,
+    },
+    /// Show the next problem to do in a pattern, unlocking Medium only once
+    /// every Easy is solved, then Hard
+    NextUp {
+        /// Pattern to work through
+        pattern: String,
+    },
+    /// Pick today's problem, the same one on every run that day
+    Daily {
+        /// Only pick from this category
+        #[arg(short = 'C', long)]
+        category: Option<String>,
+
+        /// Only pick from the focus list
+        #[arg(long)]
+        focus_only: bool,
+    },
+    /// Print every category, pattern, tag and difficulty in use as JSON
+    Meta,
+    /// Show recent changes from the audit log
+    History {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Check the database location, permissions and schema
+    Doctor,
+    /// Print the database's path, size, schema version and problem count,
+    /// and the SQLite version, e.g. for a bug report
+    Info,
+    /// Run a read-only SQL statement and print its rows tab-separated
+    Query {
+        /// The statement, e.g. "SELECT pattern, COUNT(*) FROM problems GROUP BY 1"
+        sql: String,
+
+        /// Print SQLite's plan for the statement instead of running it
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Print the configuration in effect, or check the config file
+    Config {
+        /// Print the settings in effect as JSON: the file's, with defaults
+        /// for missing keys and environment overrides applied
+        #[arg(
+            long,
+            required_unless_present = "validate",
+            conflicts_with = "validate"
+        )]
+        show: bool,
+
+        /// Check the config file for unknown keys and bad values
+        #[arg(long)]
+        validate: bool,
+    },
+    /// Move the database file, along with its -wal and -shm files
+    Rename {
+        /// New path for the database
+        new_path: PathBuf,
+
+        /// Replace a file already at the new path
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// List problems with inconsistent data, such as a 2nd attempt time without a 1st
+    Lint {
+        /// Repair the issues that have only one sensible fix
+        #[arg(long)]
+        fix: bool,
+
+        /// Fix locked problems too
+        #[arg(short, long, requires = "fix")]
+        force: bool,
+    },
+    /// Rewrite difficulties like "easy" or "HARD" as Easy, Medium or Hard
+    NormalizeDifficulty {
+        /// Rewrite locked problems too
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Renumber problems 1, 2, 3, ... in order, closing gaps left by deletions
+    Renumber {
+        /// Confirm that links, notes or scripts using the old IDs will break
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Delete a problem
+    Delete {
+        /// Problem ID
+        id: i64,
+
+        /// Skip confirmation and delete even if the problem is locked
+        #[arg(short, long)]
+        force: bool,
+
+        /// Delete for good instead of moving to the trash; also works on a
+        /// problem already in the trash
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Start a problem over: clear its attempts, solve times and review flag
+    ResetProblem {
+        /// Problem ID
+        id: i64,
+
+        /// Skip confirmation and reset even if the problem is locked
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// List deleted problems, most recently deleted first
+    Trash,
+    /// Take a deleted problem back out of the trash
+    Restore {
+        /// Problem ID
+        id: i64,
+    },
+    /// Delete every problem in the trash for good
+    EmptyTrash {
+        /// Only purge problems deleted more than this many days ago
+        #[arg(long, value_name = "DAYS", value_parser = days_parser(0))]
+        older_than: Option<u32>,
+
+        /// Skip confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Protect a problem from edits, time updates and deletion
+    Lock {
+        /// Problem ID
+        id: i64,
+    },
+    /// Add a problem to the focus list, a shortlist separate from review
+    Focus {
+        /// Problem ID
+        id: i64,
+    },
+    /// Take a problem off the focus list
+    Unfocus {
+        /// Problem ID
+        id: i64,
+    },
+    /// List the problems on the focus list
+    FocusList {
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Allow a locked problem to be changed again
+    Unlock {
+        /// Problem ID
+        id: i64,
+    },
+}
+
+/// Accepts a count of days from `min` up to `clock::MAX_DAYS`
+fn days_parser(min: i64) -> clap::builder::RangedI64ValueParser<u32> {
+    clap::value_parser!(u32).range(min..=clock::MAX_DAYS as i64)
+}
+
+/// Midnight `days` days ago in the configured zone, so 0 covers today only
+fn days_ago_cutoff(days: u32) -> DateTime<Utc> {
+    let date = clock::today() - Duration::days(days as i64);
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    match clock::instant_of(&midnight) {
+        Some(instant) => instant,
+        // Midnight fell into a DST gap; close enough for a day filter
+        None => Utc.from_utc_datetime(&midnight),
+    }
+}
+
+/// Prints a titled list of problems, or TSV rows when `--tsv` is set
+fn print_listing(
+    problems: &[Problem],
+    total: usize,
+    listing: &ListingArgs,
+    title: &str,
+    empty_message: &str,
+) -> Result<(), Box<dyn Error>> {
+    if listing.tsv {
+        export::write_tsv(
+            problems,
+            &export::CsvOptions::default(),
+            &mut std::io::stdout(),
+        )?;
+    } else if problems.is_empty() && total == 0 {
+        println!("{}", empty_message);
+    } else {
+        if problems.len() < total {
+            println!("{} ({} of {})", title, problems.len(), total);
+        } else {
+            println!("{} ({})", title, total);
+        }
+        for problem in problems {
+            println!("\n{}", problem);
+        }
+    }
+    print_truncation_hint(problems.len(), total, listing);
+
+    Ok(())
+}
+
+/// Tells the user on stderr, so TSV output stays clean, when `--limit` or
+/// `--offset` left matching problems out
+fn print_truncation_hint(shown: usize, total: usize, listing: &ListingArgs) {
+    if shown == total {
+        return;
+    }
+
+    let first = listing.offset.min(total);
+    if shown == 0 {
+        eprintln!(
+            "\nNo problems after offset {}; only {} matched",
+            listing.offset, total
+        );
+    } else if first + shown < total {
+        eprintln!(
+            "\nShowing {}-{} of {}; use --offset {} to see more",
+            first + 1,
+            first + shown,
+            total,
+            first + shown
+        );
+    } else {
+        eprintln!("\nShowing {}-{} of {}", first + 1, first + shown, total);
+    }
+}
+
+/// Cuts `value` down to `width` characters, marking the cut with an ellipsis
+fn fit(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        value.to_string()
+    } else {
+        let kept: String = value.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", kept)
+    }
+}
+
+/// Cuts the problem's description so the first line of its listing fits
+/// in `width` columns
+fn fit_description(problem: &mut Problem, width: usize) {
+    let description = std::mem::take(&mut problem.description);
+    let rest = problem
+        .to_string()
+        .lines()
+        .next()
+        .map_or(0, |line| line.chars().count());
+    problem.description = fit(
+        &description,
+        width.saturating_sub(rest).max(MIN_DESCRIPTION_WIDTH),
+    );
+}
+
+/// Columns `list --table` shows when `--columns` doesn't pick them
+const TABLE_COLUMNS: [&str; 5] = [
+    "id",
+    "description",
+    "difficulty",
+    "pattern",
+    "time_to_solve_1st",
+];
+
+/// The problem's values in the chosen `CSV_COLUMNS`, with "-" for empty ones
+fn column_values(problem: &Problem, columns: &[usize]) -> Vec<String> {
+    let fields = export::csv_fields(problem);
+    columns
+        .iter()
+        .map(|&i| match fields[i].as_str() {
+            "" => "-".to_string(),
+            value => value.to_string(),
+        })
+        .collect()
+}
+
+/// Lays out `rows` under `headers` and a rule, each column padded to its
+/// widest value. The `fill` column is cut short so lines fit in `width`,
+/// though never below `MIN_DESCRIPTION_WIDTH`.
+fn format_table(
+    headers: &[&str],
+    rows: &[Vec<String>],
+    fill: Option<usize>,
+    width: usize,
+) -> Vec<String> {
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in rows {
+        for (column, value) in row.iter().enumerate() {
+            widths[column] = widths[column].max(value.chars().count());
+        }
+    }
+    if let Some(fill) = fill {
+        let rest: usize = widths
+            .iter()
+            .enumerate()
+            .filter(|(column, _)| *column != fill)
+            .map(|(_, width)| width + "  ".len())
+            .sum();
+        widths[fill] = widths[fill].min(width.saturating_sub(rest).max(MIN_DESCRIPTION_WIDTH));
+    }
+
+    let line = |values: Vec<String>| {
+        let cells: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, &width)| format!("{:<width$}", fit(value, width)))
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+    let mut lines = vec![
+        line(headers.iter().map(|header| header.to_string()).collect()),
+        line(widths.iter().map(|&width| "-".repeat(width)).collect()),
+    ];
+    lines.extend(rows.iter().map(|row| line(row.clone())));
+    lines
+}
+
+/// Lays out `(field, left, right)` rows in aligned columns under `headers`,
+/// starring rows whose values differ. Empty values show as "-" and values
+/// wider than `width` are cut short.
+fn side_by_side(
+    headers: (&str, &str),
+    rows: &[(&str, String, String)],
+    width: usize,
+) -> Vec<String> {
+    let shown = |value: &str| fit(if value.is_empty() { "-" } else { value }, width);
+    let field_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let left_width = rows
+        .iter()
+        .map(|row| shown(&row.1).chars().count())
+        .chain([headers.0.chars().count()])
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = vec![format!(
+        "  {:<field_width$}  {:<left_width$}  {}",
+        "", headers.0, headers.1
+    )];
+    for (field, left, right) in rows {
+        let marker = if left == right { ' ' } else { '*' };
+        let line = format!(
+            "{} {:<field_width$}  {:<left_width$}  {}",
+            marker,
+            field,
+            shown(left),
+            shown(right)
+        );
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines
+}
+
+/// Parses `YYYY-MM-DD` (taken as noon), `YYYY-MM-DD HH:MM[:SS]` in the
+/// configured timezone, or an RFC 3339 timestamp
+fn parse_attempt_date(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+    })
+    .ok_or_else(|| {
+        format!(
+            "invalid date '{}', expected YYYY-MM-DD or YYYY-MM-DD HH:MM",
+            value
+        )
+    })?;
+
+    clock::instant_of(&naive)
+        .ok_or_else(|| format!("'{}' does not exist in the configured timezone", value))
+}
+
+/// The timestamp to record an attempt at, or `None` (after explaining why)
+/// when it lies in the future and `force` is not set. Exits on a date that
+/// doesn't parse.
+fn attempt_date(on: Option<&str>, force: bool) -> Option<DateTime<Utc>> {
+    let on = on.map(|value| {
+        parse_attempt_date(value).unwrap_or_else(|message| {
+            eprintln!("--on: {}", message);
+            std::process::exit(1);
+        })
+    });
+    let now = Utc::now();
+    match on {
+        Some(date) if date > now && !force => {
+            println!("Attempt date is in the future; pass --force to record it anyway");
+            None
+        }
+        Some(date) => Some(date),
+        None => Some(now),
+    }
+}
+
+fn locked_message(id: i64) -> String {
+    format!("Problem #{} is locked; unlock it or pass --force", id)
+}
+
+/// Whether the problem may be changed: `false` (after explaining why) when
+/// it is locked and `force` is not set
+fn may_change(id: i64, problem: &Problem, force: bool) -> bool {
+    if problem.locked && !force {
+        println!("{}", locked_message(id));
+        return false;
+    }
+    true
+}
+
+/// Fills in the fields derived at display time: each problem's attempt
+/// count, first and best times, how its latest time compares to its
+/// difficulty's average, and its mastery
+fn annotate(tracker: &ProblemTracker, problems: &mut [Problem]) -> Result<()> {
+    let averages = tracker.get_average_time_by_difficulty()?;
+    let attempt_counts = tracker.get_attempt_counts()?;
+    let first_and_best = tracker.get_first_and_best_times()?;
+    let last_solved = tracker.get_last_solved()?;
+    let now = Utc::now();
+    for problem in problems {
+        // Problems from before attempts were logged only have the columns
+        let columns = [
+            problem.time_to_solve_1st,
+            problem.time_to_solve_2nd,
+            problem.time_to_solve_3rd,
+        ];
+        let (first, best) = match problem.id.and_then(|id| first_and_best.get(&id)) {
+            Some(&(first, best)) => (Some(first), Some(best)),
+            None => (
+                columns.into_iter().flatten().next(),
+                columns.into_iter().flatten().min_by(f64::total_cmp),
+            ),
+        };
+        problem.first_time = first;
+        problem.best_time = best;
+
+        problem.comfort = problem
+            .difficulty
+            .as_ref()
+            .and_then(|difficulty| averages.get(difficulty))
+            .and_then(|average| stats::comfort(problem, *average));
+        problem.attempt_count = problem
+            .id
+            .map(|id| attempt_counts.get(&id).copied().unwrap_or(0));
+        let solved_at = problem.id.and_then(|id| last_solved.get(&id)).copied();
+        problem.mastery = Some(stats::mastery(problem, solved_at, now));
+    }
+
+    Ok(())
+}
+
+/// Applies the shared listing filters, ordering and paging to fetched
+/// problems, returning how many matched before paging
+fn apply_listing_args(
+    tracker: &ProblemTracker,
+    problems: &mut Vec<Problem>,
+    listing: &ListingArgs,
+) -> Result<usize> {
+    filter_and_sort_listing(tracker, problems, listing)?;
+    Ok(page_listing(problems, listing))
+}
+
+/// The problems to review after `listing`'s filters and sort, keeping only
+/// the `max` most overdue of those, in the same order. Returns them paged,
+/// with how many there were before paging and before `max`.
+fn review_listing(
+    tracker: &ProblemTracker,
+    listing: &ListingArgs,
+    max: Option<usize>,
+) -> Result<(Vec<Problem>, usize, usize)> {
+    let mut problems = tracker.get_problems_to_review()?;
+    let last_attempted = tracker.get_last_attempted()?;
+    // Never attempted sorts before any date
+    let overdue = |problem: &Problem| problem.id.and_then(|id| last_attempted.get(&id)).copied();
+    if max.is_some() {
+        // Without --sort-by, the most overdue come first
+        problems.sort_by_key(overdue);
+    }
+    filter_and_sort_listing(tracker, &mut problems, listing)?;
+
+    let flagged = problems.len();
+    if let Some(max) = max.filter(|max| *max < flagged) {
+        let mut by_overdue: Vec<&Problem> = problems.iter().collect();
+        by_overdue.sort_by_key(|problem| overdue(problem));
+        let kept: HashSet<Option<i64>> = by_overdue
+            .iter()
+            .take(max)
+            .map(|problem| problem.id)
+            .collect();
+        problems.retain(|problem| kept.contains(&problem.id));
+    }
+    let total = page_listing(&mut problems, listing);
+    Ok((problems, total, flagged))
+}
+
+/// `apply_listing_args` without the paging
+fn filter_and_sort_listing(
+    tracker: &ProblemTracker,
+    problems: &mut Vec<Problem>,
+    listing: &ListingArgs,
+) -> Result<()> {
+    annotate(tracker, problems)?;
+
+    if let Some(days) = listing.last_days {
+        let ids = tracker.get_problem_ids_attempted_since(days_ago_cutoff(days))?;
+        problems.retain(|problem| problem.id.is_some_and(|id| ids.contains(&id)));
+    }
+
+    match listing.sort_by {
+        Some(SortField::Id) => problems.sort_by_key(|problem| problem.id),
+        // Easiest first, other and missing difficulties last
+        Some(SortField::Difficulty) => problems.sort_by_key(|problem| {
+            let difficulty = problem.difficulty.as_deref().unwrap_or("").trim();
+            ["easy", "medium", "hard"]
+                .iter()
+                .position(|known| known.eq_ignore_ascii_case(difficulty))
+                .unwrap_or(3)
+        }),
+        // Most frequently asked first, problems without a frequency last
+        Some(SortField::Frequency) => problems.sort_by(|a, b| {
+            b.frequency
+                .unwrap_or(f64::NEG_INFINITY)
+                .total_cmp(&a.frequency.unwrap_or(f64::NEG_INFINITY))
+        }),
+        // Hardest for me first, unrated problems last
+        Some(SortField::PersonalDifficulty) => {
+            problems.sort_by_key(|problem| std::cmp::Reverse(problem.personal_difficulty))
+        }
+        // Most attempts first
+        Some(SortField::Attempts) => {
+            problems.sort_by_key(|problem| std::cmp::Reverse(problem.attempt_count))
+        }
+        None => {}
+    }
+    if listing.desc {
+        problems.reverse();
+    }
+    Ok(())
+}
+
+/// Drops the problems before `--offset` and after `--limit`, returning how
+/// many there were. Paging comes last so it applies to the filtered, sorted
+/// list.
+fn page_listing(problems: &mut Vec<Problem>, listing: &ListingArgs) -> usize {
+    let total = problems.len();
+    problems.drain(..listing.offset.min(total));
+    if let Some(limit) = listing.limit {
+        problems.truncate(limit);
+    }
+    total
+}
+
+/// Hands the URL to the platform's opener without waiting for the browser
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+
+    command.arg(url).spawn()?;
+    Ok(())
+}
+
+/// Renders `[#####.....] 50%`, capped at full width
+fn progress_bar(current: i64, target: i64, width: usize) -> String {
+    let ratio = if target > 0 {
+        (current as f64 / target as f64).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let filled = (ratio * width as f64).round() as usize;
+
+    format!(
+        "[{}{}] {:.0}%",
+        "#".repeat(filled),
+        ".".repeat(width - filled),
+        ratio * 100.0
+    )
+}
+
+/// Flags a solve slower than `auto_review_minutes` for review and says so
+fn auto_review(tracker: &ProblemTracker, config: &Config, id: i64, minutes: f64) -> Result<()> {
+    if let Some(threshold) = config.auto_review_minutes
+        && tracker.flag_slow_solve(id, minutes, threshold)?
+    {
+        println!(
+            "Flagged problem #{} for review: {} minutes is over auto_review_minutes ({})",
+            id, minutes, threshold
+        );
+    }
+    Ok(())
+}
+
+/// Warns on stderr when `description` runs past `max_length` characters,
+/// cutting it down to that when `truncate` is set. Descriptions are meant
+/// to be titles; long text belongs in the comments.
+fn check_description(description: &str, max_length: usize, truncate: bool) -> String {
+    let length = description.chars().count();
+    if length <= max_length {
+        return description.to_string();
+    }
+
+    if truncate {
+        eprintln!(
+            "Description cut from {} to {} characters; put longer text in --comments",
+            length, max_length
+        );
+        description.chars().take(max_length).collect()
+    } else {
+        eprintln!(
+            "Description is {} characters, over the limit of {}; consider moving the \
+            detail to --comments, or pass --truncate",
+            length, max_length
+        );
+        description.to_string()
+    }
+}
+
+/// Offers the existing category `category` looks like a typo of, returning
+/// the name to store
+fn suggest_category(
+    tracker: &ProblemTracker,
+    category: &str,
+    assume_yes: bool,
+) -> Result<String, Box<dyn Error>> {
+    let categories = tracker.get_categories()?;
+    if let Some(existing) = categories::suggestion(category, &categories) {
+        let prompt = format!(
+            "Category '{}' does not exist yet. Did you mean '{}'?",
+            category.trim(),
+            existing.name
+        );
+        if confirm(&prompt, assume_yes)? {
+            return Ok(existing.name.clone());
+        }
+    }
+
+    Ok(category.to_string())
+}
+
+/// Ask a y/N question on stdin; `assume_yes` answers it without prompting
+fn confirm(prompt: &str, assume_yes: bool) -> std::io::Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+
+    println!("{} [y/N]", prompt);
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+impl Cli {
+    /// Whether the database opens without write access, from --read-only
+    /// or a URI's own options
+    fn opens_read_only(&self) -> bool {
+        self.read_only || self.uri && uri_is_read_only(&self.database.to_string_lossy())
+    }
+
+    /// `--width`, else the terminal's width, else `DEFAULT_WIDTH`
+    fn output_width(&self) -> usize {
+        self.width
+            .or_else(|| {
+                std::io::stdout()
+                    .is_terminal()
+                    .then(termimad::crossterm::terminal::size)?
+                    .ok()
+                    .map(|(columns, _)| columns as usize)
+                    .filter(|&columns| columns > 0)
+            })
+            .unwrap_or(DEFAULT_WIDTH)
+    }
+
+    /// `--key`, or else `$SHIKARI_KEY`
+    fn encryption_key(&self) -> Option<String> {
+        self.key
+            .clone()
+            .or_else(|| std::env::var("SHIKARI_KEY").ok())
+            .filter(|key| !key.is_empty())
+    }
+}
+
+impl Commands {
+    /// Whether the command can print JSON for `--json`
+    fn has_json_output(&self) -> bool {
+        matches!(self, Commands::Stats { .. })
+    }
+
+    /// Whether the command writes to the current database
+    fn is_mutating(&self) -> bool {
+        match self {
+            Commands::Add { .. }
+            | Commands::AddInteractive
+            | Commands::Edit { .. }
+            | Commands::UpdateTime { .. }
+            | Commands::Solved { .. }
+            | Commands::Log { .. }
+            | Commands::Reclassify { .. }
+            | Commands::Start { .. }
+            | Commands::Finish { .. }
+            | Commands::ToggleReview { .. }
+            | Commands::Rate { .. }
+            | Commands::SetDifficulty { .. }
+            | Commands::TagSearch { .. }
+            | Commands::AppendNote { .. }
+            | Commands::SetSolution { .. }
+            | Commands::Move { .. }
+            | Commands::Sync { .. }
+            | Commands::Import { .. }
+            | Commands::ImportUrls { .. }
+            | Commands::ImportTimes { .. }
+            | Commands::SetGoal { .. }
+            | Commands::Delete { .. }
+            | Commands::ResetProblem { .. }
+            | Commands::NormalizeDifficulty { .. }
+            | Commands::Renumber { .. }
+            | Commands::Lock { .. }
+            | Commands::Unlock { .. }
+            | Commands::Focus { .. }
+            | Commands::Unfocus { .. } => true,
+            // Can toggle review flags
+            #[cfg(feature = "tui")]
+            Commands::Tui => true,
+            #[cfg(feature = "network")]
+            Commands::CheckLinks { flag_broken, .. } => *flag_broken,
+            Commands::Stale { flag, .. } => *flag,
+            Commands::Random { interactive, .. } => *interactive,
+            Commands::Categories { merge_similar, .. } => *merge_similar,
+            Commands::Lint { fix, .. } => *fix,
+            Commands::Show { .. }
+            | Commands::Compare { .. }
+            | Commands::Similar { .. }
+            | Commands::Prereqs { .. }
+            | Commands::Path { .. }
+            | Commands::Recent { .. }
+            | Commands::Links { .. }
+            | Commands::Open { .. }
+            | Commands::List { .. }
+            | Commands::Review { .. }
+            | Commands::ListProgress { .. }
+            | Commands::FocusList { .. }
+            | Commands::Todo { .. }
+            | Commands::NoLink { .. }
+            | Commands::ByMastery { .. }
+            | Commands::ByCategory { .. }
+            | Commands::ByPattern { .. }
+            | Commands::ByLanguage { .. }
+            | Commands::ByDifficulty { .. }
+            | Commands::ByCompany { .. }
+            | Commands::ByTags { .. }
+            | Commands::Search { .. }
+            | Commands::Diff { .. }
+            | Commands::ExportTemplate { .. }
+            | Commands::Export { .. }
+            | Commands::ExportAttempts { .. }
+            | Commands::Stats { .. }
+            | Commands::AggregateStats { .. }
+            | Commands::Report { .. }
+            | Commands::PatternStats
+            | Commands::Coverage
+            | Commands::Percentiles
+            | Commands::TimeSpent { .. }
+            | Commands::Trend { .. }
+            | Commands::Intake { .. }
+            | Commands::Score
+            | Commands::Weak { .. }
+            | Commands::Queue { .. }
+            | Commands::Readiness
+            | Commands::Top { .. }
+            | Commands::Retry { .. }
+            | Commands::Forgetting { .. }
+            | Commands::Goal
+            | Commands::Targets
+            | Commands::Status
+            | Commands::Plan { .. }
+            | Commands::Daily { .. }
+            | Commands::NextUp { .. }
+            | Commands::Meta
+            | Commands::History { .. }
+            | Commands::Doctor
+            | Commands::Info
+            | Commands::Query { .. }
+            | Commands::Solution { .. }
+            | Commands::Trash
+            | Commands::Config { .. } => false,
+            Commands::Restore { .. } | Commands::EmptyTrash { .. } => true,
+            Commands::Rename { .. } => true,
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let db_path = cli.database.to_string_lossy();
+    let key = cli.encryption_key();
+    if cli.json && !cli.command.has_json_output() {
+        eprintln!("--json is not supported by this command");
+        std::process::exit(1);
+    }
+
+    // Runs before opening the tracker, which would create and migrate the
+    // database it is meant to inspect
+    if let Commands::Doctor = cli.command {
+        if cli.uri {
+            eprintln!("doctor checks a database file; pass its path without --uri");
+            std::process::exit(1);
+        }
+        if !doctor::run(&cli.database, key.as_deref()) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // Moves the files while no connection has them open
+    if let Commands::Rename { new_path, force } = &cli.command {
+        if cli.uri || db_path == MEMORY_DATABASE || cli.read_only {
+            eprintln!("rename moves a database file; pass its path without --uri or --read-only");
+            std::process::exit(1);
+        }
+        match rename_database(&cli.database, new_path, *force) {
+            Ok(sidecars) if sidecars.is_empty() => {
+                println!("Moved {} to {}", cli.database.display(), new_path.display())
+            }
+            Ok(sidecars) => println!(
+                "Moved {} to {}, with its {} {}",
+                cli.database.display(),
+                new_path.display(),
+                sidecars.join(" and "),
+                if sidecars.len() == 1 { "file" } else { "files" }
+            ),
+            Err(message) => {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Runs before loading the config, which fails on the errors it reports
+    if let Commands::Config { show, .. } = cli.command {
+        if show {
+            let config = config::load()?.effective();
+            export::write_value(&config, cli.pretty, &mut std::io::stdout())?;
+            return Ok(());
+        }
+        match config::path() {
+            Some(path) if path.exists() => match config::load() {
+                Ok(_) => println!("{} is valid", path.display()),
+                Err(error) => {
+                    eprintln!("{}", error);
+                    std::process::exit(1);
+                }
+            },
+            Some(path) => println!("No config file at {}; using the defaults", path.display()),
+            None => println!("No config file location: set $SHIKARI_CONFIG or $HOME"),
+        }
+        return Ok(());
+    }
+
+    let config = config::load()?;
+    humanize::set_time_format(if cli.raw_minutes {
+        humanize::TimeFormat::Minutes
+    } else {
+        config.time_format
+    });
+    clock::set_zone(config.zone()?);
+    let _ = DISPLAY_OPTIONS.set(DisplayOptions {
+        review_marker: !cli.hide_review_marker,
+        color: color::Palette::new(cli.no_color).is_enabled(),
+        ..DisplayOptions::default()
+    });
+    if cli.uri
+        && let Err(message) = check_uri(&db_path, cli.read_only)
+    {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+    let opened = if cli.opens_read_only() {
+        if cli.command.is_mutating() {
+            if cli.read_only {
+                eprintln!("This command modifies the database and cannot run with --read-only");
+            } else {
+                eprintln!("This command modifies the database, which the URI opens read-only");
+            }
+            std::process::exit(1);
+        }
+        let opened = if cli.uri {
+            ProblemTracker::open_uri(&db_path, true, key.as_deref())
+        } else {
+            ProblemTracker::open_read_only(&db_path, key.as_deref())
+        };
+        // Nothing read-only can migrate it, so an old schema stops here
+        opened.and_then(|tracker| schema::check_current(&tracker.conn).map(|_| tracker))
+    } else if cli.uri {
+        ProblemTracker::open_uri(&db_path, false, key.as_deref())
+    } else {
+        ProblemTracker::new(&db_path, !cli.no_create_dirs, key.as_deref())
+    };
+    let tracker = match opened {
+        Ok(tracker) => tracker,
+        Err(rusqlite::Error::InvalidParameterName(message)) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        Err(rusqlite::Error::SqliteFailure(error, _))
+            if error.code == rusqlite::ErrorCode::NotADatabase =>
+        {
+            eprintln!(
+                "{} is not a shikari database, or it is encrypted and needs --key",
+                db_path
+            );
+            std::process::exit(1);
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    if cli.seed {
+        if db_path != MEMORY_DATABASE {
+            eprintln!("--seed only works with --database {}", MEMORY_DATABASE);
+            std::process::exit(1);
+        }
+        tracker.seed_samples()?;
+    }
+
+    // A URI's file path can't be told apart from its options reliably, so
+    // there is nowhere to put backups
+    if db_path != MEMORY_DATABASE && !cli.uri {
+        match backup::run_if_due(&tracker, &cli.database, key.as_deref(), &config) {
+            Ok(Some(path)) => eprintln!("Backed up the database to {}", path.display()),
+            Ok(None) => {}
+            // A failed backup shouldn't stop the command it runs ahead of
+            Err(error) => eprintln!("Automatic backup failed: {}", error),
+        }
+    }
+
+    // Runs after the backup so the purged problems are still in it
+    if let Some(days) = config.trash_retention_days
+        && !cli.opens_read_only()
+    {
+        match tracker.purge_trash(Some(Utc::now() - Duration::days(days as i64))) {
+            Ok(0) => {}
+            Ok(purged) => eprintln!(
+                "Purged {} problems deleted over {} days ago from the trash",
+                purged, days
+            ),
+            Err(error) => eprintln!("Purging the trash failed: {}", error),
+        }
+    }
+
+    if cli.watch {
+        if cli.command.is_mutating() {
+            eprintln!("Only commands that read the database can run with --watch");
+            std::process::exit(1);
+        }
+        if db_path == MEMORY_DATABASE {
+            eprintln!(
+                "--watch needs a database file; nothing else can change {}",
+                MEMORY_DATABASE
+            );
+            std::process::exit(1);
+        }
+        if cli.uri {
+            eprintln!("--watch needs the database's path, not a URI");
+            std::process::exit(1);
+        }
+        return watch(&cli, &tracker, &config);
+    }
+
+    // Only commands that got as far as logging a change run the hook
+    let hook = config
+        .post_write_hook
+        .as_deref()
+        .filter(|_| cli.command.is_mutating());
+    let before = match hook {
+        Some(_) => Some(tracker.last_audit_id()?),
+        None => None,
+    };
+    let result = run_command(&cli, &tracker, &config);
+    if let (Ok(()), Some(hook), Some(before)) = (&result, hook, before) {
+        let changes = tracker.get_changes_since(before)?;
+        if !changes.is_empty() {
+            let mut ids: Vec<i64> = Vec::new();
+            for id in changes.into_iter().flatten() {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            run_post_write_hook(hook, &db_path, &ids);
+        }
+    }
+
+    match result {
+        Err(error) if is_locked_error(error.as_ref()) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+/// Runs the configured hook through the shell. A hook that fails or can't
+/// start is reported but doesn't fail the command, whose change is already
+/// saved.
+fn run_post_write_hook(hook: &str, db_path: &str, ids: &[i64]) {
+    let ids: Vec<String> = ids.iter().map(i64::to_string).collect();
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .arg("shikari")
+        .args(&ids)
+        .env("SHIKARI_PROBLEM_IDS", ids.join(" "))
+        .env("SHIKARI_DATABASE", db_path)
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("post_write_hook failed: {}", status),
+        Err(error) => eprintln!("post_write_hook could not run: {}", error),
+    }
+}
+
+/// Suffixes of the files SQLite keeps next to a database in WAL mode
+const SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
+
+/// The database's path with `suffix` appended, e.g. `problems.db-wal`
+fn sidecar(database: &Path, suffix: &str) -> PathBuf {
+    let mut path = database.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Latest modification time of the database or its write-ahead log
+fn database_modified(database: &Path) -> Option<SystemTime> {
+    [database.to_path_buf(), sidecar(database, "-wal")]
+        .iter()
+        .filter_map(|path| path.metadata().and_then(|meta| meta.modified()).ok())
+        .max()
+}
+
+/// Moves a file, copying it when a plain rename can't cross filesystems
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+/// Moves the database and any `SIDECAR_SUFFIXES` files to `to`, returning
+/// the suffixes of the sidecars moved. A file at `to` is only replaced with
+/// `force`, and then its own stale sidecars are removed so they can't be
+/// replayed into the moved database.
+fn rename_database(
+    from: &Path,
+    to: &Path,
+    force: bool,
+) -> std::result::Result<Vec<&'static str>, String> {
+    if !from.is_file() {
+        return Err(format!("No database at {}", from.display()));
+    }
+    if to.exists() {
+        if std::fs::canonicalize(from).ok() == std::fs::canonicalize(to).ok() {
+            return Err(format!("The database is already at {}", to.display()));
+        }
+        if !force {
+            return Err(format!(
+                "{} already exists; pass --force to replace it",
+                to.display()
+            ));
+        }
+    }
+
+    let failed = |error: std::io::Error| format!("Could not move the database: {}", error);
+    for suffix in SIDECAR_SUFFIXES {
+        let stale = sidecar(to, suffix);
+        if stale.exists() {
+            std::fs::remove_file(stale).map_err(failed)?;
+        }
+    }
+    move_file(from, to).map_err(failed)?;
+    let mut moved = Vec::new();
+    for suffix in SIDECAR_SUFFIXES {
+        let source = sidecar(from, suffix);
+        if source.exists() {
+            move_file(&source, &sidecar(to, suffix)).map_err(failed)?;
+            moved.push(suffix);
+        }
+    }
+
+    Ok(moved)
+}
+
+/// Clears the screen and re-runs the command each time the database file
+/// changes, until interrupted
+fn watch(cli: &Cli, tracker: &ProblemTracker, config: &Config) -> Result<(), Box<dyn Error>> {
+    loop {
+        let seen = run_watched(&cli.database, || {
+            print!("\x1B[2J\x1B[H");
+            println!(
+                "Watching {} for changes, Ctrl-C to quit\n",
+                cli.database.display()
+            );
+            // A failed run, say while another process holds a lock, is shown
+            // and retried on the next change rather than ending the watch
+            if let Err(error) = run_command(cli, tracker, config) {
+                eprintln!("Error: {}", error);
+            }
+        });
+        std::io::stdout().flush()?;
+
+        while database_modified(&cli.database) == seen {
+            std::thread::sleep(WATCH_INTERVAL);
+        }
+    }
+}
+
+/// Runs one pass of a watched command and returns the database's
+/// modification time after it. Taking it afterwards means the command's own
+/// writes, such as `show` recording a view, don't count as a change to rerun
+/// it for.
+fn run_watched(database: &Path, run: impl FnOnce()) -> Option<SystemTime> {
+    run();
+    database_modified(database)
+}
+
+/// Refuses a `--min-time` above `--max-time`, which nothing could match
+fn check_time_range(filters: &FilterArgs) {
+    if let (Some(min), Some(max)) = (filters.min_time, filters.max_time)
+        && min > max
+    {
+        eprintln!("--min-time {} is greater than --max-time {}", min, max);
+        std::process::exit(1);
+    }
+}
+
+/// Starts timing the problem and says so, noting a session it replaced and
+/// how long similar problems took as a target
+fn start_session(
+    tracker: &ProblemTracker,
+    id: i64,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(previous) = tracker.start_session(id)?
+        && previous != id
+    {
+        writeln!(out, "Replaced the session on problem #{}", previous)?;
+    }
+    writeln!(
+        out,
+        "Started problem #{} at {}",
+        id,
+        clock::format(Utc::now(), "%H:%M")
+    )?;
+
+    let problem = tracker.get_problem(id)?;
+    match stats::estimate_solve_time(&problem, &tracker.get_all_problems()?) {
+        Some(estimate) => writeln!(out, "Target: {}", estimate)?,
+        None => writeln!(out, "Target: no estimate")?,
+    }
+    Ok(())
+}
+
+/// Whole minutes from `started` to `now`, rounded up and at least one
+fn elapsed_minutes(started: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let seconds = (now - started).num_seconds().max(0);
+    ((seconds + 59) / 60).max(1)
+}
+
+/// Offers random problems until one is started or the user quits. With
+/// `urgencies`, one per problem, urgent problems come up more often.
+fn pull_random(
+    tracker: &ProblemTracker,
+    problems: &[Problem],
+    urgencies: Option<&[f64]>,
+    rng: &mut impl rand::Rng,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut skipped = HashSet::new();
+    let mut pick = |skipped: &HashSet<i64>| match urgencies {
+        Some(urgencies) => ranking::weighted_pick(problems, urgencies, skipped, rng),
+        None => plan::random_pick(problems, skipped, rng),
+    };
+    while let Some(problem) = pick(&skipped) {
+        let id = problem.id.unwrap_or(0);
+        writeln!(out, "{}\n", problem)?;
+        loop {
+            write!(out, "[n]ext, [s]tart or [q]uit? ")?;
+            out.flush()?;
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                return Ok(());
+            }
+            match answer.trim().to_lowercase().as_str() {
+                "n" => break,
+                "s" => {
+                    start_session(tracker, id, out)?;
+                    return Ok(());
+                }
+                "q" => return Ok(()),
+                _ => {}
+            }
+        }
+        skipped.insert(id);
+        writeln!(out)?;
+    }
+
+    writeln!(out, "No more problems to pick from")?;
+    Ok(())
+}
+
+fn run_command(cli: &Cli, tracker: &ProblemTracker, config: &Config) -> Result<(), Box<dyn Error>> {
+    let create_dirs = !cli.no_create_dirs;
+    let key = cli.encryption_key();
+    match &cli.command {
+        Commands::Add {
+            description,
+            #[cfg(feature = "clipboard")]
+            from_clipboard,
+            link,
+            category,
+            pattern,
+            difficulty,
+            time,
+            comments,
+            review,
+            company,
+            frequency,
+            tag,
+            profile,
+            source,
+            no_suggest,
+            truncate,
+        } => {
+            let defaults = match config.add_defaults(profile.as_deref()) {
+                Ok(defaults) => defaults,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    std::process::exit(1);
+                }
+            };
+            let category = category.clone().or(defaults.default_category);
+            #[cfg(feature = "clipboard")]
+            let link = &match from_clipboard.then(clipboard_link).transpose() {
+                Ok(clipped) => link.iter().cloned().chain(clipped).collect::<Vec<_>>(),
+                Err(message) => {
+                    eprintln!("{}", message);
+                    std::process::exit(1);
+                }
+            };
+            let Some(description) = description.clone().or_else(|| {
+                link.iter()
+                    .rfind(|link| link.label == PROBLEM_LINK_LABEL)
+                    .and_then(|link| link_slug(&link.url))
+            }) else {
+                eprintln!("No description given and no problem slug in the link");
+                std::process::exit(1);
+            };
+            let description =
+                check_description(&description, config.max_description_length, *truncate);
+            let mut problem = Problem::new(&description);
+            for link in link {
+                problem.set_link(link.clone());
+            }
+            problem.category = match category {
+                Some(category) if !no_suggest => {
+                    Some(suggest_category(tracker, &category, cli.yes)?)
+                }
+                _ => category,
+            };
+            problem.pattern = pattern.clone().or(defaults.default_pattern);
+            problem.difficulty = difficulty.clone();
+            problem.time_to_solve_1st = *time;
+            problem.comments = comments.clone();
+            problem.should_solve_again = *review;
+            problem.company = company.as_deref().and_then(normalize_companies);
+            problem.frequency = *frequency;
+            for tag in tag {
+                problem.add_tag(tag);
+            }
+            problem.source = match source {
+                Some(source) => source.clone(),
+                None => problem
+                    .problem_url()
+                    .and_then(|url| link_source(&url))
+                    .map_or_else(default_source, str::to_string),
+            };
+
+            let id = match tracker.add_problem(problem) {
+                Ok(id) => id,
+                // The link's slug is taken
+                Err(rusqlite::Error::InvalidParameterName(message)) => {
+                    eprintln!("{}", message);
+                    std::process::exit(1);
+                }
+                Err(error) => return Err(error.into()),
+            };
+            println!("Added problem with ID: {}", id);
+        }
+        Commands::AddInteractive => {
+            let existing = tracker.get_categories()?;
+            let Some(mut problem) = wizard::ask_problem(
+                &existing,
+                &mut std::io::stdin().lock(),
+                &mut std::io::stdout(),
+            )?
+            else {
+                println!("\nNothing added");
+                return Ok(());
+            };
+            problem.description =
+                check_description(&problem.description, config.max_description_length, false);
+            if let Some(source) = problem.link.as_deref().and_then(link_source) {
+                problem.source = source.to_string();
+            }
+
+            let id = match tracker.add_problem(problem) {
+                Ok(id) => id,
+                // The link's slug is taken
+                Err(rusqlite::Error::InvalidParameterName(message)) => {
+                    eprintln!("{}", message);
+                    std::process::exit(1);
+                }
+                Err(error) => return Err(error.into()),
+            };
+            println!("Added problem with ID: {}", id);
+        }
+        Commands::Edit {
+            id,
+            description,
+            link,
+            remove_link,
+            category,
+            pattern,
+            difficulty,
+            comments,
+            company,
+            frequency,
+            rate,
+            tag,
+            remove_tag,
+            prereq,
+            remove_prereq,
+            no_suggest,
+            truncate,
+            force,
+        } => {
+            let mut problem = match tracker.get_problem(*id) {
+                Ok(problem) => problem,
+                Err(_) => {
+                    println!("Problem with ID {} not found", id);
+                    return Ok(());
+                }
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+
+            if let Some(description) = description {
+                problem.description =
+                    check_description(description, config.max_description_length, *truncate);
+            }
+            for label in remove_link {
+                if !problem.remove_link(label) {
+                    println!("Problem #{} has no '{}' link", id, label);
+                }
+            }
+            for link in link {
+                problem.set_link(link.clone());
+            }
+            for tag in remove_tag {
+                if !problem.remove_tag(tag) {
+                    println!("Problem #{} has no '{}' tag", id, tag);
+                }
+            }
+            for tag in tag {
+                problem.add_tag(tag);
+            }
+            if let Some(category) = category {
+                problem.category = Some(if *no_suggest {
+                    category.clone()
+                } else {
+                    suggest_category(tracker, category, cli.yes)?
+                });
+            }
+            if pattern.is_some() {
+                problem.pattern = pattern.clone();
+            }
+            if difficulty.is_some() {
+                problem.difficulty = difficulty.clone();
+            }
+            if comments.is_some() {
+                problem.comments = comments.clone();
+            }
+            if let Some(company) = company {
+                problem.company = normalize_companies(company);
+            }
+            if frequency.is_some() {
+                problem.frequency = *frequency;
+            }
+            if rate.is_some() {
+                problem.personal_difficulty = *rate;
+            }
+
+            match tracker.edit_problem(&problem, *force, prereq, remove_prereq) {
+                Ok(missing) => {
+                    println!("Updated problem #{}", id);
+                    for prereq_id in missing {
+                        println!("#{} is not a prerequisite of #{}", prereq_id, id);
+                    }
+                }
+                // Locked, or a prerequisite was refused; nothing was saved
+                Err(rusqlite::Error::InvalidParameterName(message)) => {
+                    eprintln!("{}", message);
+                    std::process::exit(1);
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Commands::Show {
+            id,
+            with_attempts,
+            render,
+            template,
+        } => match tracker.get_problem(*id) {
+            Ok(mut problem) => {
+                // Read-only databases just go without view history
+                if !cli.opens_read_only() {
+                    tracker.record_view(*id)?;
+                }
+                annotate(tracker, std::slice::from_mut(&mut problem))?;
+                if let Some(template) = template {
+                    println!("{}", template.render(&problem));
+                    return Ok(());
+                }
+                // Printed after the other fields, where wrapped text and
+                // code blocks have room
+                let rendered = if *render {
+                    problem.comments.take()
+                } else {
+                    None
+                };
+                println!("{}", problem);
+                if let Some(difficulty) = &problem.difficulty
+                    && let Some(average) = tracker.get_average_first_time(difficulty)?
+                {
+                    let comparison = match problem.time_to_solve_1st {
+                        Some(first) if first < average => " (you were faster)",
+                        Some(first) if first > average => " (you were slower)",
+                        Some(_) => " (you matched it)",
+                        None => "",
+                    };
+                    println!(
+                        "  {} average first attempt: {}{}",
+                        difficulty,
+                        humanize::average(average),
+                        comparison
+                    );
+                }
+                if let Some(comments) = rendered {
+                    println!("  Comments:");
+                    print!("{}", termimad::MadSkin::default().term_text(&comments));
+                }
+                let attempts = tracker.get_attempts(*id)?;
+                if let Some(attempt) = attempts.last() {
+                    println!(
+                        "  Last attempted: {}",
+                        humanize::format_timestamp(attempt.attempted_at, cli.absolute_dates)
+                    );
+                }
+                if *with_attempts {
+                    if attempts.is_empty() {
+                        println!("  No attempts logged");
+                    } else {
+                        println!("  Attempt log:");
+                    }
+                    for attempt in &attempts {
+                        println!(
+                            "    {}: {}",
+                            humanize::format_timestamp(attempt.attempted_at, cli.absolute_dates),
+                            attempt
+                        );
+                    }
+                }
+            }
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::Compare { id1, id2 } => {
+            let (first, second) = match (tracker.get_problem(*id1), tracker.get_problem(*id2)) {
+                (Ok(first), Ok(second)) => (first, second),
+                (Err(_), _) => {
+                    println!("Problem with ID {} not found", id1);
+                    return Ok(());
+                }
+                (_, Err(_)) => {
+                    println!("Problem with ID {} not found", id2);
+                    return Ok(());
+                }
+            };
+
+            let rows: Vec<(&str, String, String)> = export::CSV_COLUMNS
+                .iter()
+                .zip(
+                    export::csv_fields(&first)
+                        .into_iter()
+                        .zip(export::csv_fields(&second)),
+                )
+                .skip(1)
+                .map(|(&field, (left, right))| (field, left, right))
+                .collect();
+            let headers = (format!("#{}", id1), format!("#{}", id2));
+            for line in side_by_side((&headers.0, &headers.1), &rows, COMPARE_WIDTH) {
+                println!("{}", line);
+            }
+        }
+        Commands::Prereqs { id } => {
+            let problem = match tracker.get_problem(*id) {
+                Ok(problem) => problem,
+                Err(_) => {
+                    println!("Problem with ID {} not found", id);
+                    return Ok(());
+                }
+            };
+            let edges = tracker.get_prerequisite_edges(false)?;
+            let line = |id: i64| match tracker.get_problem(id) {
+                Ok(problem) => format!(
+                    "#{} {}{}",
+                    id,
+                    problem.description,
+                    if problem.time_to_solve_1st.is_some() {
+                        " (solved)"
+                    } else {
+                        ""
+                    }
+                ),
+                Err(_) => format!("#{}", id),
+            };
+
+            println!("#{} {}", id, problem.description);
+            let chain = prereq::chain(&edges, *id);
+            if chain.is_empty() {
+                println!("  No prerequisites");
+            }
+            for (depth, prereq_id) in chain {
+                println!("{}needs {}", "  ".repeat(depth), line(prereq_id));
+            }
+            let dependents = prereq::dependents(&edges, *id);
+            if !dependents.is_empty() {
+                println!("\nLeads to:");
+                for dependent in dependents {
+                    println!("  {}", line(dependent));
+                }
+            }
+        }
+        Commands::Path { unsolved } => {
+            let problems = tracker.get_all_problems()?;
+            let ids: Vec<i64> = problems.iter().filter_map(|problem| problem.id).collect();
+            let order = match prereq::learning_order(&ids, &tracker.get_prerequisite_edges(false)?)
+            {
+                Ok(order) => order,
+                Err(stuck) => {
+                    let stuck: Vec<String> = stuck.iter().map(|id| format!("#{}", id)).collect();
+                    eprintln!(
+                        "Prerequisites loop, so these can't be ordered: {}",
+                        stuck.join(", ")
+                    );
+                    std::process::exit(1);
+                }
+            };
+
+            let by_id: HashMap<i64, &Problem> = problems
+                .iter()
+                .filter_map(|problem| Some((problem.id?, problem)))
+                .collect();
+            let path: Vec<&Problem> = order
+                .iter()
+                .filter_map(|id| by_id.get(id).copied())
+                .filter(|problem| !*unsolved || problem.time_to_solve_1st.is_none())
+                .collect();
+            if path.is_empty() {
+                println!("No problems found");
+            }
+            for (step, problem) in (1..).zip(path) {
+                println!(
+                    "{:>3}. #{} {} ({}){}",
+                    step,
+                    problem.id.unwrap_or(0),
+                    problem.description,
+                    problem.difficulty.as_deref().unwrap_or("Unknown"),
+                    if problem.time_to_solve_1st.is_some() {
+                        " (solved)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+        Commands::Similar { id, limit } => {
+            let problem = match tracker.get_problem(*id) {
+                Ok(problem) => problem,
+                Err(_) => {
+                    println!("Problem with ID {} not found", id);
+                    return Ok(());
+                }
+            };
+            if problem.tags.is_empty() {
+                println!("Problem #{} has no tags to compare by", id);
+                return Ok(());
+            }
+
+            let similar = tracker.get_similar(*id, *limit)?;
+            if similar.is_empty() {
+                println!("No other problems share a tag with #{}", id);
+                return Ok(());
+            }
+            println!("Problems like #{} {}", id, problem.description);
+            for (other, shared) in similar {
+                let common: Vec<&str> = other
+                    .tags
+                    .iter()
+                    .filter(|tag| problem.tags.contains(tag))
+                    .map(String::as_str)
+                    .collect();
+                println!(
+                    "#{:<4} {}  ({} shared: {})",
+                    other.id.unwrap_or(0),
+                    other.description,
+                    shared,
+                    common.join(", ")
+                );
+            }
+        }
+        Commands::Recent { limit } => {
+            let views = tracker.get_recent_views(*limit)?;
+            if views.is_empty() {
+                println!("No problems viewed yet");
+            }
+            for (problem, viewed_at) in views {
+                println!(
+                    "#{:<4} {}  (viewed {})",
+                    problem.id.unwrap_or(0),
+                    problem.description,
+                    humanize::format_timestamp(viewed_at, cli.absolute_dates)
+                );
+            }
+        }
+        Commands::Links { id } => match tracker.get_problem(*id) {
+            Ok(problem) => {
+                let links = problem.all_links();
+                if links.is_empty() {
+                    println!("Problem #{} has no links", id);
+                }
+                for link in links {
+                    println!("{}: {}", link.label, link.url);
+                }
+            }
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        #[cfg(feature = "tui")]
+        Commands::Tui => tui::run(tracker)?,
+        #[cfg(feature = "network")]
+        Commands::CheckLinks {
+            concurrency,
+            timeout,
+            flag_broken,
+        } => {
+            let targets: Vec<linkcheck::Target> = tracker
+                .get_all_problems()?
+                .iter()
+                .flat_map(|problem| {
+                    problem
+                        .all_links()
+                        .into_iter()
+                        .map(|link| linkcheck::Target {
+                            problem_id: problem.id.unwrap_or(0),
+                            label: link.label,
+                            url: link.url,
+                        })
+                })
+                .collect();
+            let checked = targets.len();
+            let broken = linkcheck::find_broken(
+                targets,
+                *concurrency,
+                std::time::Duration::from_secs(*timeout),
+            )?;
+
+            for link in &broken {
+                println!(
+                    "#{} {}: {} ({})",
+                    link.target.problem_id, link.target.label, link.target.url, link.reason
+                );
+            }
+            println!("Checked {} links, {} broken", checked, broken.len());
+
+            if *flag_broken {
+                let ids: std::collections::BTreeSet<i64> =
+                    broken.iter().map(|link| link.target.problem_id).collect();
+                for id in &ids {
+                    tracker.set_review_flag(*id, true)?;
+                }
+                println!("Flagged {} problems for review", ids.len());
+            }
+        }
+        Commands::Open { id, label } => match tracker.get_problem(*id) {
+            Ok(problem) => {
+                let links = problem.all_links();
+                match links.iter().find(|link| link.label == label.to_lowercase()) {
+                    Some(link) => {
+                        open_in_browser(&link.url)?;
+                        println!("Opening {}", link.url);
+                    }
+                    None if links.is_empty() => println!("Problem #{} has no links", id),
+                    None => println!(
+                        "Problem #{} has no '{}' link; it has: {}",
+                        id,
+                        label,
+                        links
+                            .iter()
+                            .map(|link| link.label.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+            }
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::List {
+            filters,
+            listing,
+            columns,
+            table,
+            group_by,
+            template,
+        } => {
+            check_time_range(filters);
+            // The config's order applies only when --sort-by doesn't pick one
+            let listing = &ListingArgs {
+                sort_by: listing.sort_by.or(config.default_list_sort),
+                desc: listing.desc || listing.sort_by.is_none() && config.default_list_desc,
+                ..*listing
+            };
+            let (mut problems, total) =
+                tracker.get_listing(filtered_query("1", filters), listing)?;
+            let width = cli.output_width();
+            if let Some(template) = template {
+                for problem in &problems {
+                    println!("{}", template.render(problem));
+                }
+                print_truncation_hint(problems.len(), total, listing);
+                return Ok(());
+            }
+            match (group_by, columns) {
+                (Some(field), _) if !problems.is_empty() => {
+                    for problem in &mut problems {
+                        fit_description(problem, width);
+                    }
+                    if problems.len() < total {
+                        println!("All Problems ({} of {})", problems.len(), total);
+                    } else {
+                        println!("All Problems ({})", total);
+                    }
+                    for (name, group) in group_problems(&problems, *field) {
+                        println!("\n== {} ({}) ==", name, group.len());
+                        for problem in group {
+                            println!("\n{}", problem);
+                        }
+                    }
+                    print_truncation_hint(problems.len(), total, listing);
+                }
+                _ if *table && !problems.is_empty() => {
+                    let columns = match columns {
+                        Some(columns) => columns.clone(),
+                        None => TABLE_COLUMNS
+                            .iter()
+                            .map(|name| export::parse_column(name))
+                            .collect::<Result<_, _>>()?,
+                    };
+                    let headers: Vec<&str> =
+                        columns.iter().map(|&i| export::CSV_COLUMNS[i]).collect();
+                    let rows: Vec<Vec<String>> = problems
+                        .iter()
+                        .map(|problem| column_values(problem, &columns))
+                        .collect();
+                    let description = export::parse_column("description")?;
+                    let fill = columns.iter().position(|&i| i == description);
+                    for line in format_table(&headers, &rows, fill, width) {
+                        println!("{}", line);
+                    }
+                    print_truncation_hint(problems.len(), total, listing);
+                }
+                (_, Some(columns)) if !problems.is_empty() && !listing.tsv => {
+                    let header: Vec<&str> =
+                        columns.iter().map(|&i| export::CSV_COLUMNS[i]).collect();
+                    println!("{}", header.join(" | "));
+                    let description = export::parse_column("description")?;
+                    for problem in &problems {
+                        let mut row = column_values(problem, columns);
+                        // The description gets whatever the other columns leave
+                        if let Some(at) = columns.iter().position(|&i| i == description) {
+                            let rest: usize = row
+                                .iter()
+                                .enumerate()
+                                .filter(|(index, _)| *index != at)
+                                .map(|(_, value)| value.chars().count() + " | ".len())
+                                .sum();
+                            row[at] = fit(
+                                &row[at],
+                                width.saturating_sub(rest).max(MIN_DESCRIPTION_WIDTH),
+                            );
+                        }
+                        println!("{}", row.join(" | "));
+                    }
+                    print_truncation_hint(problems.len(), total, listing);
+                }
+                // Only a database with nothing in it, not a filter matching
+                // nothing, gets the getting-started hint
+                _ if total == 0 && !listing.tsv && tracker.count_problems()? == 0 => {
+                    println!("No problems yet. Add your first one with:\n");
+                    println!(
+                        "  shikari add \"Two Sum\" --link https://leetcode.com/problems/two-sum/ -d Easy -p \"hash map\" -t 15\n"
+                    );
+                    println!("Run `shikari add --help` for every option.");
+                }
+                _ => print_listing(
+                    &problems,
+                    total,
+                    listing,
+                    "All Problems",
+                    "No problems found",
+                )?,
+            }
+        }
+        Commands::Review {
+            listing,
+            max,
+            format,
+        } => {
+            let max = max.map(|max| max as usize).or(config.review_max);
+            let (problems, total, flagged) = review_listing(tracker, listing, max)?;
+            if let Some(max) = max.filter(|max| *max < flagged) {
+                eprintln!("The {} most overdue of {} problems to review", max, flagged);
+            }
+            match format {
+                export::ListingFormat::Plain => print_listing(
+                    &problems,
+                    total,
+                    listing,
+                    "Problems to Review",
+                    "No problems to review",
+                )?,
+                export::ListingFormat::Markdown => {
+                    export::write_markdown(
+                        &problems,
+                        "Problems to Review",
+                        &mut std::io::stdout(),
+                    )?;
+                    print_truncation_hint(problems.len(), total, listing);
+                }
+                export::ListingFormat::Json => {
+                    export::write_json(&problems, cli.pretty, &mut std::io::stdout())?;
+                    print_truncation_hint(problems.len(), total, listing);
+                }
+            }
+        }
+        Commands::Todo { filters, listing } => {
+            check_time_range(filters);
+            let (problems, total) = tracker.get_listing(unattempted_query(filters), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                "Problems Not Yet Attempted",
+                "Every matching problem has been attempted",
+            )?;
+        }
+        Commands::NoLink { filters, listing } => {
+            check_time_range(filters);
+            let (problems, total) = tracker.get_listing(without_link_query(filters), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                "Problems Without a Link",
+                "Every matching problem has a link",
+            )?;
+        }
+        Commands::ByMastery {
+            level,
+            filters,
+            listing,
+        } => {
+            check_time_range(filters);
+            let mut problems = tracker.get_matching(filters)?;
+            annotate(tracker, &mut problems)?;
+            problems.retain(|problem| problem.mastery == Some(*level));
+            let total = apply_listing_args(tracker, &mut problems, listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("{} Problems", level),
+                &format!("No {} problems", level.to_string().to_lowercase()),
+            )?;
+        }
+        Commands::ByCategory { category, listing } => {
+            let (problems, total) = tracker.get_listing(category_query(category), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems in Category '{}'", category),
+                &format!("No problems found in category '{}'", category),
+            )?;
+        }
+        Commands::ByPattern { pattern, listing } => {
+            let (problems, total) = tracker.get_listing(pattern_query(pattern), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems with Pattern '{}'", pattern),
+                &format!("No problems found with pattern '{}'", pattern),
+            )?;
+        }
+        Commands::ByLanguage { lang, listing } => {
+            let (problems, total) = tracker.get_listing(language_query(lang), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems Solved in '{}'", lang),
+                &format!("No problems have a solution in '{}'", lang),
+            )?;
+        }
+        Commands::ByDifficulty {
+            difficulty,
+            listing,
+        } => {
+            let (problems, total) = tracker.get_listing(difficulty_query(difficulty), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems with Difficulty '{}'", difficulty),
+                &format!("No problems found with difficulty '{}'", difficulty),
+            )?;
+        }
+        Commands::ByCompany { company, listing } => {
+            let (problems, total) = tracker.get_listing(company_query(company), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems asked by '{}'", company),
+                &format!("No problems found for company '{}'", company),
+            )?;
+        }
+        Commands::ByTags { all, any, listing } => {
+            let (tags, joiner) = if all.is_empty() {
+                (any, " or ")
+            } else {
+                (all, " and ")
+            };
+            let (problems, total) =
+                tracker.get_listing(tags_query(tags, !all.is_empty()), listing)?;
+            let tags = tags.join(joiner);
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems tagged {}", tags),
+                &format!("No problems found tagged {}", tags),
+            )?;
+        }
+        Commands::Search {
+            keywords,
+            mode,
+            count_only: true,
+            ..
+        } => println!("{}", tracker.count_search_matches(keywords, *mode)?),
+        Commands::Search {
+            keywords,
+            mode,
+            listing,
+            ..
+        } => {
+            let (mut problems, total) =
+                tracker.get_listing(search_query(keywords, *mode), listing)?;
+            if !listing.tsv {
+                let palette = color::Palette::new(cli.no_color);
+                for problem in &mut problems {
+                    problem.description = palette.highlight(&problem.description, keywords);
+                    problem.comments = problem
+                        .comments
+                        .as_deref()
+                        .map(|comments| palette.highlight(comments, keywords));
+                }
+            }
+
+            let separator = match mode {
+                SearchMode::And => "' and '",
+                SearchMode::Or => "' or '",
+            };
+            let keywords = keywords.join(separator);
+            print_listing(
+                &problems,
+                total,
+                listing,
+                &format!("Problems matching '{}'", keywords),
+                &format!("No problems found matching '{}'", keywords),
+            )?;
+        }
+        Commands::TagSearch {
+            keywords,
+            mode,
+            add,
+            remove,
+            force,
+        } => {
+            let (tag, removing) = match (add, remove) {
+                (Some(tag), _) => (tag, false),
+                (None, Some(tag)) => (tag, true),
+                (None, None) => unreachable!("clap requires --add or --remove"),
+            };
+            let (changed, skipped) =
+                tracker.tag_search_results(keywords, *mode, tag, removing, *force)?;
+            if removing {
+                println!("Removed tag '{}' from {} problems", tag, changed);
+            } else {
+                println!("Tagged {} problems '{}'", changed, tag);
+            }
+            if skipped > 0 {
+                println!(
+                    "Skipped {} locked problems; pass --force to change them",
+                    skipped
+                );
+            }
+        }
+        Commands::UpdateTime {
+            id,
+            attempt,
+            minutes,
+            on,
+            force,
+            gave_up,
+            note,
+            no_auto_review,
+        } => {
+            if *attempt < 1 || *attempt > 3 {
+                println!("Attempt must be 1, 2, or 3");
+                return Ok(());
+            }
+            let Ok(problem) = tracker.get_problem(*id) else {
+                println!("Problem with ID {} not found", id);
+                return Ok(());
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+
+            let Some(attempted_at) = attempt_date(on.as_deref(), *force) else {
+                return Ok(());
+            };
+
+            let logged = Attempt {
+                attempt: *attempt,
+                minutes: *minutes,
+                attempted_at,
+                solved: !gave_up,
+                note: note.clone(),
+            };
+            match tracker.log_attempt(*id, &logged, *force) {
+                Ok(_) if *gave_up => println!(
+                    "Logged attempt {} on problem #{} as a give-up after {} minutes",
+                    attempt, id, minutes
+                ),
+                Ok(_) => {
+                    println!(
+                        "Updated problem #{} with attempt {} time: {} minutes",
+                        id, attempt, minutes
+                    );
+                    if !no_auto_review {
+                        auto_review(tracker, config, *id, *minutes)?;
+                    }
+                }
+                Err(rusqlite::Error::InvalidParameterName(message)) => println!("{}", message),
+                Err(_) => println!("Problem with ID {} not found", id),
+            }
+        }
+        Commands::Solved {
+            id,
+            minutes,
+            on,
+            force,
+            no_auto_review,
+        } => {
+            let Some(attempted_at) = attempt_date(on.as_deref(), *force) else {
+                return Ok(());
+            };
+
+            let Ok(problem) = tracker.get_problem(*id) else {
+                println!("Problem with ID {} not found", id);
+                return Ok(());
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+            let Some(attempt) = problem.next_attempt() else {
+                println!("All three attempts are already recorded");
+                return Ok(());
+            };
+
+            match tracker.mark_solved(*id, attempt, *minutes, attempted_at, *force) {
+                Ok(_) => {
+                    println!(
+                        "Marked problem #{} as solved (attempt {}: {} minutes)",
+                        id, attempt, minutes
+                    );
+                    if !no_auto_review {
+                        auto_review(tracker, config, *id, *minutes)?;
+                    }
+                }
+                Err(rusqlite::Error::InvalidParameterName(message)) => println!("{}", message),
+                Err(_) => println!("Problem with ID {} not found", id),
+            }
+        }
+        Commands::Log {
+            id,
+            minutes,
+            no_auto_review,
+        } => {
+            if let Ok(problem) = tracker.get_problem(*id)
+                && !may_change(*id, &problem, false)
+            {
+                return Ok(());
+            }
+            match tracker.log_next_attempt(*id, *minutes, Utc::now()) {
+                Ok(attempt) => {
+                    println!(
+                        "Logged attempt {} on problem #{}: {} minutes",
+                        attempt, id, minutes
+                    );
+                    if !no_auto_review {
+                        auto_review(tracker, config, *id, *minutes)?;
+                    }
+                }
+                Err(rusqlite::Error::InvalidParameterName(message)) => println!("{}", message),
+                Err(_) => println!("Problem with ID {} not found", id),
+            }
+        }
+        Commands::Reclassify { id, force } => {
+            let mut problem = match tracker.get_problem(*id) {
+                Ok(problem) => problem,
+                Err(_) => {
+                    println!("Problem with ID {} not found", id);
+                    return Ok(());
+                }
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+            println!("{}\n", problem);
+
+            let Some(pattern) = wizard::ask_pattern(
+                &tracker.get_distinct("problems", "pattern")?,
+                problem.pattern.as_deref(),
+                &mut std::io::stdin().lock(),
+                &mut std::io::stdout(),
+            )?
+            else {
+                println!("Pattern left unchanged");
+                return Ok(());
+            };
+            problem.pattern = Some(pattern.clone());
+
+            tracker.update_problem(&problem, *force)?;
+            println!("Set the pattern of problem #{} to '{}'", id, pattern);
+        }
+        Commands::Start { id } => {
+            if tracker.get_problem(*id).is_err() {
+                println!("Problem with ID {} not found", id);
+                return Ok(());
+            }
+            start_session(tracker, *id, &mut std::io::stdout())?;
+        }
+        Commands::Finish { discard, force } => {
+            let Some((id, started_at)) = tracker.get_session()? else {
+                println!("No session in progress; start one with `start <ID>`");
+                return Ok(());
+            };
+            if *discard {
+                tracker.clear_session()?;
+                println!("Discarded the session on problem #{}", id);
+                return Ok(());
+            }
+
+            let Ok(problem) = tracker.get_problem(id) else {
+                tracker.clear_session()?;
+                println!("Problem with ID {} no longer exists", id);
+                return Ok(());
+            };
+            if !may_change(id, &problem, *force) {
+                return Ok(());
+            }
+            let Some(attempt) = problem.next_attempt() else {
+                println!(
+                    "All three attempts are already recorded; pass --discard to end the session"
+                );
+                return Ok(());
+            };
+
+            let minutes = elapsed_minutes(started_at, Utc::now()) as f64;
+            tracker.mark_solved(id, attempt, minutes, Utc::now(), *force)?;
+            tracker.clear_session()?;
+            println!(
+                "Finished problem #{} (attempt {}: {} minutes)",
+                id, attempt, minutes
+            );
+            auto_review(tracker, config, id, minutes)?;
+        }
+        Commands::Status => match tracker.get_session()? {
+            Some((id, started_at)) => {
+                let problem = tracker.get_problem(id)?;
+                println!("{}\n", problem);
+                println!(
+                    "Working on it for {} (since {})",
+                    humanize::minutes(elapsed_minutes(started_at, Utc::now()) as f64),
+                    clock::format(started_at, "%H:%M")
+                );
+            }
+            None => println!("No session in progress"),
+        },
+        Commands::Rate { id, value } => match tracker.set_personal_difficulty(*id, *value) {
+            Ok(_) => println!("Rated problem #{} {}/5", id, value),
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::ToggleReview { id } => match tracker.toggle_review_flag(*id) {
+            Ok(_) => match tracker.get_problem(*id) {
+                Ok(problem) => println!(
+                    "Problem #{} review flag set to: {}",
+                    id,
+                    if problem.should_solve_again {
+                        "Yes"
+                    } else {
+                        "No"
+                    }
+                ),
+                Err(_) => println!("Problem with ID {} not found", id),
+            },
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::SetSolution {
+            id,
+            lang,
+            clear,
+            force,
+        } => {
+            let (Ok(problem), Ok(current)) = (tracker.get_problem(*id), tracker.get_solution(*id))
+            else {
+                println!("Problem with ID {} not found", id);
+                return Ok(());
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+            let solution = if *clear {
+                None
+            } else if std::io::stdin().is_terminal() {
+                Some(edit_text(current.as_deref().unwrap_or(""))?)
+            } else {
+                Some(std::io::read_to_string(std::io::stdin())?)
+            };
+            let solution = solution.filter(|code| !code.trim().is_empty());
+            if solution.is_none() && !*clear {
+                println!("Empty solution; nothing saved");
+                return Ok(());
+            }
+
+            match tracker.set_solution(*id, solution.as_deref(), lang.as_deref(), *force) {
+                Ok(_) if *clear => println!("Removed the solution of problem #{}", id),
+                Ok(_) => println!("Saved the solution of problem #{}", id),
+                Err(_) => println!("Problem with ID {} not found", id),
+            }
+        }
+        Commands::Solution { id } => match tracker.get_problem(*id) {
+            Ok(problem) => match problem.solution {
+                #[cfg(feature = "highlight")]
+                Some(solution) if color::Palette::new(cli.no_color).is_enabled() => print!(
+                    "{}",
+                    highlight::code(&solution, problem.solution_language.as_deref())
+                ),
+                Some(solution) => print!("{}", solution),
+                None => println!(
+                    "Problem #{} has no solution yet; save one with `set-solution {}`",
+                    id, id
+                ),
+            },
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::AppendNote { id, text } => match tracker.append_comment(*id, text) {
+            Ok(_) => println!("Added note to problem #{}", id),
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::Move { id, dest_db } => {
+            let dest =
+                ProblemTracker::new(&dest_db.to_string_lossy(), create_dirs, key.as_deref())?;
+            match tracker.move_problem(*id, &dest) {
+                Ok(new_id) => println!(
+                    "Moved problem #{} to {} as #{}",
+                    id,
+                    dest_db.display(),
+                    new_id
+                ),
+                Err(rusqlite::Error::QueryReturnedNoRows) => {
+                    println!("Problem with ID {} not found", id)
+                }
+                Err(rusqlite::Error::InvalidParameterName(message)) => {
+                    eprintln!("{}", message);
+                    std::process::exit(1);
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Commands::ExportTemplate { dest_db } => {
+            if dest_db.exists() {
+                eprintln!(
+                    "{} already exists; give a new file for the template",
+                    dest_db.display()
+                );
+                std::process::exit(1);
+            }
+            // Left unencrypted, since it's meant to be handed to others
+            let dest = ProblemTracker::new(&dest_db.to_string_lossy(), create_dirs, None)?;
+            let copied = tracker.export_template(&dest)?;
+            println!(
+                "Wrote a template of {} problems to {}",
+                copied,
+                dest_db.display()
+            );
+        }
+        Commands::Diff { other_db } => {
+            let other = if cli.read_only {
+                ProblemTracker::open_read_only(&other_db.to_string_lossy(), key.as_deref())?
+            } else {
+                ProblemTracker::new(&other_db.to_string_lossy(), create_dirs, key.as_deref())?
+            };
+            let diff = diff_problems(tracker.get_all_problems()?, other.get_all_problems()?);
+
+            if diff.only_here.is_empty() && diff.only_there.is_empty() && diff.changed.is_empty() {
+                println!("No differences found");
+                return Ok(());
+            }
+
+            let palette = color::Palette::new(cli.no_color);
+            println!("Only here ({})", diff.only_here.len());
+            for problem in &diff.only_here {
+                let line = format!("#{}: {}", problem.id.unwrap_or(0), problem.description);
+                println!("  {}", palette.removed(&line));
+            }
+
+            println!("\nOnly there ({})", diff.only_there.len());
+            for problem in &diff.only_there {
+                let line = format!("#{}: {}", problem.id.unwrap_or(0), problem.description);
+                println!("  {}", palette.added(&line));
+            }
+
+            println!("\nChanged ({})", diff.changed.len());
+            for (problem, other, fields) in &diff.changed {
+                let line = format!(
+                    "#{} / #{}: {}",
+                    problem.id.unwrap_or(0),
+                    other.id.unwrap_or(0),
+                    problem.description
+                );
+                println!("  {}", palette.changed(&line));
+                for field in fields {
+                    println!(
+                        "    {}: {} -> {}",
+                        field,
+                        palette.removed(&field_value(problem, field)),
+                        palette.added(&field_value(other, field))
+                    );
+                }
+            }
+        }
+        Commands::Sync { other_db } => {
+            let other =
+                ProblemTracker::new(&other_db.to_string_lossy(), create_dirs, key.as_deref())?;
+            let started = Instant::now();
+            let (added, updated) = tracker.sync_from(&other)?;
+            println!(
+                "Synced from {}: {}",
+                other_db.display(),
+                humanize::bulk_summary(
+                    &[("added", added), ("updated", updated)],
+                    started.elapsed()
+                )
+            );
+        }
+        Commands::Export {
+            format,
+            output,
+            no_header,
+            delimiter,
+            since_id,
+            exclude,
+            filters,
+        } => {
+            if delimiter.is_some() && !matches!(format, export::ExportFormat::Csv) {
+                eprintln!("--delimiter only applies to CSV export");
+                std::process::exit(1);
+            }
+            if *no_header
+                && matches!(
+                    format,
+                    export::ExportFormat::Json
+                        | export::ExportFormat::Jsonl
+                        | export::ExportFormat::Sql
+                        | export::ExportFormat::Html
+                )
+            {
+                eprintln!("--no-header only applies to CSV and TSV export");
+                std::process::exit(1);
+            }
+            if let export::ExportFormat::Sql = format {
+                let FilterArgs {
+                    category: None,
+                    pattern: None,
+                    difficulty: None,
+                    source: None,
+                    min_time: None,
+                    max_time: None,
+                } = filters
+                else {
+                    eprintln!("--format sql dumps the whole database and takes no filters");
+                    std::process::exit(1);
+                };
+                if since_id.is_some() || !exclude.is_empty() {
+                    eprintln!(
+                        "--format sql dumps the whole database; drop --since-id and --exclude"
+                    );
+                    std::process::exit(1);
+                }
+                match output {
+                    Some(path) => {
+                        export::write_sql(&tracker.conn, &mut std::fs::File::create(path)?)?;
+                        println!("Dumped the database to {}", path.display());
+                    }
+                    None => export::write_sql(&tracker.conn, &mut std::io::stdout())?,
+                }
+                return Ok(());
+            }
+            let options = export::CsvOptions {
+                header: !no_header,
+                delimiter: delimiter.unwrap_or(b','),
+                exclude: exclude.clone(),
+            };
+
+            check_time_range(filters);
+            let mut problems = match since_id {
+                Some(since_id) => tracker.get_matching_since(*since_id, filters)?,
+                None => tracker.get_matching(filters)?,
+            };
+            if matches!(
+                format,
+                export::ExportFormat::Json | export::ExportFormat::Jsonl
+            ) {
+                problems = tracker.with_attempts(problems)?;
+            }
+            match output {
+                Some(path) => {
+                    let mut file = std::fs::File::create(path)?;
+                    export::write(*format, &problems, &options, cli.pretty, &mut file)?;
+                    println!("Exported {} problems to {}", problems.len(), path.display());
+                }
+                None => export::write(
+                    *format,
+                    &problems,
+                    &options,
+                    cli.pretty,
+                    &mut std::io::stdout(),
+                )?,
+            }
+        }
+        Commands::ExportAttempts { format, output } => {
+            let rows = tracker.get_attempt_history()?;
+            match output {
+                Some(path) => {
+                    let mut file = std::fs::File::create(path)?;
+                    export::write_attempts(*format, &rows, cli.pretty, &mut file)?;
+                    println!("Exported {} attempts to {}", rows.len(), path.display());
+                }
+                None => export::write_attempts(*format, &rows, cli.pretty, &mut std::io::stdout())?,
+            }
+        }
+        Commands::Import {
+            file,
+            map,
+            dedupe_on,
+            on_conflict,
+            quiet,
+            infer_pattern,
+            as_list,
+        } => {
+            let started = Instant::now();
+            let keywords = infer_pattern.then(|| config.pattern_keywords());
+            let summary = import::import_file(
+                tracker,
+                file,
+                map,
+                *dedupe_on,
+                *on_conflict,
+                keywords.as_ref(),
+                *quiet,
+            )?;
+            for collision in &summary.collisions {
+                println!("{}", collision);
+            }
+            println!(
+                "{}",
+                humanize::bulk_summary(
+                    &[
+                        ("Imported", summary.imported),
+                        ("skipped", summary.skipped),
+                        ("replaced", summary.replaced),
+                        ("merged", summary.merged),
+                    ],
+                    started.elapsed()
+                )
+            );
+            if *infer_pattern {
+                println!("Inferred the pattern of {} problems", summary.inferred);
+            }
+            if let Some(name) = as_list {
+                let added = tracker.add_to_list(name, &summary.ids)?;
+                println!("Added {} problems to list '{}'", added, name);
+            }
+        }
+        Commands::ListProgress { name } => {
+            let Some(mut problems) = tracker.get_list(name)? else {
+                println!("No list named '{}'; create one with import --as-list", name);
+                return Ok(());
+            };
+            annotate(tracker, &mut problems)?;
+            let (solved, unsolved): (Vec<&Problem>, Vec<&Problem>) = problems
+                .iter()
+                .partition(|problem| problem.first_time.is_some());
+
+            println!("{}: {}/{} solved", name, solved.len(), problems.len());
+            println!(
+                "{}",
+                progress_bar(solved.len() as i64, problems.len() as i64, 30)
+            );
+            if !unsolved.is_empty() {
+                println!("\nUnsolved:");
+                for problem in unsolved {
+                    println!(
+                        "  #{}: {} ({})",
+                        problem.id.unwrap_or(0),
+                        problem.description,
+                        problem.difficulty.as_deref().unwrap_or("Unknown")
+                    );
+                }
+            }
+        }
+        Commands::ImportUrls { file } => {
+            let started = Instant::now();
+            let summary = import::import_urls(tracker, file)?;
+            for note in &summary.notes {
+                println!("{}", note);
+            }
+            println!(
+                "{}",
+                humanize::bulk_summary(
+                    &[("Added", summary.added), ("skipped", summary.skipped)],
+                    started.elapsed()
+                )
+            );
+        }
+        Commands::ImportTimes {
+            file,
+            on,
+            force,
+            no_auto_review,
+        } => {
+            let Some(attempted_at) = attempt_date(on.as_deref(), *force) else {
+                return Ok(());
+            };
+
+            let started = Instant::now();
+            let summary = import::import_times(tracker, file, attempted_at, *force)?;
+            for failure in &summary.failures {
+                println!("{}", failure);
+            }
+            println!(
+                "{}",
+                humanize::bulk_summary(
+                    &[
+                        ("Logged", summary.logged.len()),
+                        ("failed", summary.failures.len())
+                    ],
+                    started.elapsed()
+                )
+            );
+            if !summary.failures.is_empty() {
+                println!("Nothing is logged until every row is valid; fix them and import again");
+                return Ok(());
+            }
+            if !no_auto_review {
+                for row in &summary.logged {
+                    auto_review(tracker, config, row.id, row.minutes)?;
+                }
+            }
+        }
+        Commands::Stats {
+            first_only,
+            latest_only,
+        } => {
+            let selection = if *first_only {
+                stats::AttemptSelection::First
+            } else if *latest_only {
+                stats::AttemptSelection::Latest
+            } else {
+                stats::AttemptSelection::All
+            };
+
+            let (problems, by_language) = tracker.get_stats_inputs()?;
+            let mut stats = stats::compute_stats(&problems, selection);
+            stats.by_language = by_language;
+            if cli.json {
+                export::write_value(&stats, cli.pretty, &mut std::io::stdout())?;
+            } else if problems.is_empty() {
+                println!("No problems found");
+            } else {
+                println!("{}", stats);
+            }
+        }
+        Commands::AggregateStats { databases } => {
+            let key = cli.encryption_key();
+            let mut shards = vec![(cli.database.clone(), tracker.get_stats_inputs()?)];
+            for path in databases {
+                if !path.exists() {
+                    eprintln!("Database {} does not exist", path.display());
+                    std::process::exit(1);
+                }
+                shards.push((
+                    path.clone(),
+                    ProblemTracker::other_stats_inputs(path, key.as_deref())?,
+                ));
+            }
+
+            let mut all_problems = Vec::new();
+            let mut by_language = BTreeMap::new();
+            println!("By database:");
+            for (path, (problems, languages)) in shards {
+                let stats = stats::compute_stats(&problems, stats::AttemptSelection::All);
+                print!(
+                    "  {}: {} problems, {} attempted, {} to review",
+                    path.display(),
+                    stats.total,
+                    stats.attempted,
+                    stats.to_review
+                );
+                match stats.average_minutes {
+                    Some(avg) => println!(", avg {}", humanize::average(avg)),
+                    None => println!(),
+                }
+                all_problems.extend(problems);
+                for (language, count) in languages {
+                    *by_language.entry(language).or_insert(0) += count;
+                }
+            }
+
+            let mut combined = stats::compute_stats(&all_problems, stats::AttemptSelection::All);
+            combined.by_language = by_language;
+            println!(
+                "\nCombined ({} databases):\n{}",
+                databases.len() + 1,
+                combined
+            );
+        }
+        Commands::Score => println!("{}", tracker.compute_score(&config.points())?),
+        Commands::Categories {
+            merge_similar,
+            sort,
+        } => {
+            if !merge_similar {
+                let categories = tracker.get_categories_by_practice(*sort)?;
+                if categories.is_empty() {
+                    println!("No categories found");
+                    return Ok(());
+                }
+
+                println!("Categories ({})", categories.len());
+                for (category, last_attempted_at) in &categories {
+                    print!("  {}: {} problems", category.name, category.problems);
+                    match (sort, last_attempted_at) {
+                        (CategorySort::Name, _) => println!(),
+                        (_, Some(timestamp)) => println!(
+                            ", last practiced {}",
+                            humanize::format_timestamp(*timestamp, cli.absolute_dates)
+                        ),
+                        (_, None) => println!(", never practiced"),
+                    }
+                }
+                return Ok(());
+            }
+
+            let categories = tracker.get_categories()?;
+            if categories.is_empty() {
+                println!("No categories found");
+                return Ok(());
+            }
+
+            let pairs = categories::similar_pairs(&categories);
+            if pairs.is_empty() {
+                println!("No similar categories found");
+            }
+            let mut merged = HashSet::new();
+            for (from, into) in pairs {
+                // An earlier merge may already have removed one side
+                if merged.contains(&from.id) || merged.contains(&into.id) {
+                    continue;
+                }
+                let prompt = format!(
+                    "Merge '{}' ({} problems) into '{}' ({} problems)?",
+                    from.name, from.problems, into.name, into.problems
+                );
+                if confirm(&prompt, cli.yes)? {
+                    let moved = tracker.merge_categories(from, into)?;
+                    merged.insert(from.id);
+                    println!("Moved {} problems to '{}'", moved, into.name);
+                }
+            }
+        }
+        Commands::Report { period, periods } => {
+            let rows = report::summarize(
+                *period,
+                &tracker.get_attempt_records()?,
+                &tracker.get_first_seen()?,
+                *periods,
+                clock::today(),
+            );
+
+            println!(
+                "{:<10} {:>7} {:>8} {:>5}",
+                "Period", "Solved", "Time", "New"
+            );
+            for row in rows {
+                println!(
+                    "{:<10} {:>7} {:>8} {:>5}",
+                    row.label,
+                    row.solved,
+                    humanize::minutes(row.minutes),
+                    row.added
+                );
+            }
+        }
+        Commands::Intake { days } => {
+            let counts = tracker.added_per_day(*days as usize)?;
+            let total: usize = counts.iter().map(|(_, count)| count).sum();
+            let most = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+            for (date, count) in &counts {
+                // Scaled so the busiest day fills 40 columns
+                let width = if most > 40 { count * 40 / most } else { *count };
+                println!(
+                    "{}  {:>3} {}",
+                    date.format("%a %m-%d"),
+                    count,
+                    "#".repeat(width)
+                );
+            }
+            println!(
+                "\nAdded {} problems in the last {} days ({:.1} per day)",
+                total,
+                days,
+                total as f64 / (*days).max(1) as f64
+            );
+        }
+        Commands::Trend { pattern } => {
+            let months = report::monthly_averages(&tracker.get_pattern_attempt_records(pattern)?);
+            let (Some((first, _)), Some((last, _))) = (months.first(), months.last()) else {
+                println!("No solved attempts for pattern '{}'", pattern);
+                return Ok(());
+            };
+
+            let averages: Vec<Option<f64>> = months.iter().map(|(_, average)| *average).collect();
+            println!("Average solve time for {}, {} to {}", pattern, first, last);
+            println!("{}\n", report::sparkline(&averages));
+            for (label, average) in &months {
+                println!(
+                    "{:<8} {:>8}",
+                    label,
+                    average.map_or_else(|| "-".to_string(), humanize::average)
+                );
+            }
+        }
+        Commands::PatternStats => {
+            let stats = tracker.get_pattern_stats()?;
+            if stats.is_empty() {
+                println!("No problems found");
+                return Ok(());
+            }
+
+            println!(
+                "{:<24} {:>6} {:>10} {:>7}",
+                "Pattern", "Count", "Avg 1st", "Review"
+            );
+            for row in stats {
+                let avg = match row.avg_first_time {
+                    Some(avg) => humanize::average(avg),
+                    None => "-".to_string(),
+                };
+                println!(
+                    "{:<24} {:>6} {:>10} {:>6.0}%",
+                    row.pattern.as_deref().unwrap_or("(none)"),
+                    row.count,
+                    avg,
+                    row.review_ratio * 100.0
+                );
+            }
+        }
+        Commands::Percentiles => {
+            let (all, by_difficulty) = stats::first_times(&tracker.get_all_problems()?);
+            if all.is_empty() {
+                println!("No first-attempt times recorded");
+                return Ok(());
+            }
+
+            print!("{:<16} {:>6}", "Difficulty", "Count");
+            for p in stats::PERCENTILES {
+                print!(" {:>8}", format!("p{}", p));
+            }
+            println!();
+            for (difficulty, times) in std::iter::once(("All", &all)).chain(
+                by_difficulty
+                    .iter()
+                    .map(|(name, times)| (name.as_str(), times)),
+            ) {
+                print!("{:<16} {:>6}", difficulty, times.len());
+                for p in stats::PERCENTILES {
+                    let value = stats::percentile(times, p)
+                        .map_or_else(|| "-".to_string(), humanize::average);
+                    print!(" {:>8}", value);
+                }
+                println!();
+            }
+        }
+        Commands::Coverage => {
+            let grid = report::coverage(&tracker.get_pattern_difficulty_counts()?);
+            if grid.rows.is_empty() {
+                println!("No problems found");
+                return Ok(());
+            }
+
+            let widths: Vec<usize> = grid
+                .difficulties
+                .iter()
+                .map(|difficulty| difficulty.chars().count().max(6))
+                .collect();
+            print!("{:<24}", "Pattern");
+            for (difficulty, width) in grid.difficulties.iter().zip(&widths) {
+                print!(" {:>width$}", difficulty, width = width);
+            }
+            println!();
+            let mut gaps = 0;
+            for (pattern, counts) in &grid.rows {
+                print!("{:<24}", pattern);
+                for (count, width) in counts.iter().zip(&widths) {
+                    if *count == 0 {
+                        gaps += 1;
+                        print!(" {:>width$}", "--", width = width);
+                    } else {
+                        print!(" {:>width$}", count, width = width);
+                    }
+                }
+                println!();
+            }
+            if gaps > 0 {
+                println!(
+                    "\n{} of {} cells empty (--)",
+                    gaps,
+                    grid.rows.len() * grid.difficulties.len()
+                );
+            }
+        }
+        Commands::TimeSpent { by } => {
+            let spent = tracker.get_time_spent(*by)?;
+            let total: i64 = spent.iter().map(|(_, minutes)| minutes).sum();
+            println!("Total practice: {}", humanize::hours_and_minutes(total));
+            if by.is_some() && total > 0 {
+                println!();
+                for (name, minutes) in spent {
+                    println!(
+                        "{:<24} {:>20} {:>5.1}%",
+                        name.as_deref().unwrap_or(UNGROUPED),
+                        humanize::hours_and_minutes(minutes),
+                        minutes as f64 / total as f64 * 100.0
+                    );
+                }
+            }
+        }
+        Commands::Weak { recent_days, limit } => {
+            let weakness = weak::pattern_weakness(&tracker.get_pattern_stats()?);
+            let ranked = weak::recommend(
+                tracker.get_all_problems()?,
+                &weakness,
+                &tracker.get_last_attempted()?,
+                *recent_days,
+                Utc::now(),
+            );
+
+            if ranked.is_empty() {
+                println!("No recommendations: no weak patterns with unpracticed problems");
+            } else {
+                println!("Suggested next problems ({})", ranked.len().min(*limit));
+                for (score, problem) in ranked.into_iter().take(*limit) {
+                    println!("\n[score {:.2}] {}", score, problem);
+                }
+            }
+        }
+        Commands::Readiness => {
+            let now = Utc::now();
+            let readiness = readiness::assess(
+                &tracker.get_all_problems()?,
+                &tracker.get_last_solved()?,
+                tracker.get_attempt_outcomes_since(now - Duration::days(readiness::RECENT_DAYS))?,
+                now,
+            );
+            println!("{}", readiness);
+        }
+        Commands::Queue { limit } => {
+            let ranked = ranking::rank(
+                tracker.get_all_problems()?,
+                &tracker.get_last_attempted()?,
+                Utc::now(),
+                &config.queue_weights(),
+            );
+
+            if ranked.is_empty() {
+                println!("No problems found");
+            } else {
+                println!("Recommended next problems ({})", ranked.len().min(*limit));
+                for (urgency, problem) in ranked.into_iter().take(*limit) {
+                    println!("\n[urgency {:.2}] {}", urgency, problem);
+                }
+            }
+        }
+        Commands::Top {
+            slowest: _,
+            fastest,
+            by,
+            limit,
+        } => {
+            let ranked = tracker.get_extremes(*by, !fastest, *limit)?;
+            if ranked.is_empty() {
+                println!("No solve times recorded yet");
+            } else {
+                let order = if *fastest { "Fastest" } else { "Slowest" };
+                let basis = match by {
+                    TimeBasis::First => "first",
+                    TimeBasis::Best => "best",
+                };
+                println!("{} by {} solve time ({})", order, basis, ranked.len());
+                for (minutes, problem) in ranked {
+                    println!("\n[{}] {}", humanize::minutes(minutes), problem);
+                }
+            }
+        }
+        Commands::Retry { limit } => {
+            let failed = tracker.get_failed_problems()?;
+            if failed.is_empty() {
+                println!("No problems to retry: every last attempt was a solve");
+            } else {
+                println!("Problems to retry ({})", failed.len().min(*limit));
+                for (gave_up_at, problem) in failed.into_iter().take(*limit) {
+                    println!(
+                        "\n[gave up {}] {}",
+                        humanize::format_timestamp(gave_up_at, cli.absolute_dates),
+                        problem
+                    );
+                }
+            }
+        }
+        Commands::Forgetting { limit } => {
+            let ranked = memory::most_forgotten(
+                tracker.get_all_problems()?,
+                &tracker.get_last_attempted()?,
+                config.memory_half_life_days,
+                Utc::now(),
+            );
+
+            if ranked.is_empty() {
+                println!("No attempted problems yet");
+            } else {
+                println!("Most likely forgotten ({})", ranked.len().min(*limit));
+                for (recall, problem) in ranked.into_iter().take(*limit) {
+                    println!("\n[recall {:.0}%] {}", recall * 100.0, problem);
+                }
+            }
+        }
+        Commands::SetGoal { minutes_per_day } => {
+            if *minutes_per_day <= 0 {
+                println!("Goal must be a positive number of minutes");
+                return Ok(());
+            }
+
+            tracker.set_goal(*minutes_per_day)?;
+            println!("Daily goal set to {} minutes", minutes_per_day);
+        }
+        Commands::Goal => match tracker.get_goal()? {
+            Some(target) => {
+                let minutes = tracker.today_minutes()?;
+                println!("Today: {} / {} minutes", minutes, target);
+                println!("{}", progress_bar(minutes, target, 30));
+                if minutes >= target {
+                    println!("Goal reached!");
+                }
+            }
+            None => println!("No daily goal set. Use set-goal to set one"),
+        },
+        Commands::Targets => {
+            if config.targets.is_empty() {
+                println!(
+                    "No targets set. Add some to the config file, e.g. targets = {{ Graphs = 20 }}"
+                );
+                return Ok(());
+            }
+
+            let solved = tracker.get_solved_by_category()?;
+            let width = config
+                .targets
+                .keys()
+                .map(|name| name.chars().count())
+                .max()
+                .unwrap_or(0);
+            println!("Targets");
+            for (category, target) in &config.targets {
+                let count = solved.get(&category.to_lowercase()).copied().unwrap_or(0);
+                let target = *target as usize;
+                print!(
+                    "  {:<width$}  {:>3}/{:<3} {}",
+                    category,
+                    count,
+                    target,
+                    progress_bar(count as i64, target as i64, 20),
+                    width = width
+                );
+                match target.saturating_sub(count) {
+                    0 => println!("  done"),
+                    remaining => println!("  {} remaining", remaining),
+                }
+            }
+        }
+        Commands::Stale { days, flag } => {
+            let problems = tracker.get_stale_problems(*days)?;
+            if problems.is_empty() {
+                println!("No problems stale for more than {} days", days);
+            } else {
+                println!(
+                    "Problems not attempted in {} days ({})",
+                    days,
+                    problems.len()
+                );
+                for problem in &problems {
+                    println!("\n{}", problem);
+                }
+
+                if *flag {
+                    for problem in &problems {
+                        tracker.set_review_flag(problem.id.unwrap_or(0), true)?;
+                    }
+                    println!("\nFlagged {} problems for review", problems.len());
+                }
+            }
+        }
+        Commands::Plan {
+            days,
+            per_day,
+            seed,
+            markdown,
+        } => {
+            let seed = seed.unwrap_or_else(|| Utc::now().timestamp() as u64);
+            let schedule = plan::build_plan(tracker.get_all_problems()?, *days, *per_day, seed);
+            if schedule.is_empty() {
+                println!("No problems found");
+                return Ok(());
+            }
+
+            if *markdown {
+                plan::print_markdown(&schedule);
+            } else {
+                plan::print_plain(&schedule);
+                println!("Seed: {} (pass --seed {} to reproduce)", seed, seed);
+            }
+        }
+        Commands::Random {
+            filters,
+            focus_only,
+            interactive,
+            weighted,
+        } => {
+            check_time_range(filters);
+            let mut problems = tracker.get_matching(filters)?;
+            if *focus_only {
+                problems.retain(|problem| problem.focused);
+            }
+            annotate(tracker, &mut problems)?;
+            let urgencies = if *weighted {
+                let last_attempted = tracker.get_last_attempted()?;
+                let (now, weights) = (Utc::now(), config.queue_weights());
+                let urgencies: Vec<f64> = problems
+                    .iter()
+                    .map(|problem| {
+                        let last = problem.id.and_then(|id| last_attempted.get(&id)).copied();
+                        ranking::urgency(problem, last, now, &weights)
+                    })
+                    .collect();
+                Some(urgencies)
+            } else {
+                None
+            };
+
+            let mut rng = rand::thread_rng();
+            if problems.is_empty() {
+                println!("No problems found");
+            } else if *interactive {
+                pull_random(
+                    tracker,
+                    &problems,
+                    urgencies.as_deref(),
+                    &mut rng,
+                    &mut std::io::stdin().lock(),
+                    &mut std::io::stdout(),
+                )?;
+            } else {
+                let pick = match &urgencies {
+                    Some(urgencies) => {
+                        ranking::weighted_pick(&problems, urgencies, &HashSet::new(), &mut rng)
+                    }
+                    None => plan::random_pick(&problems, &HashSet::new(), &mut rng),
+                };
+                if let Some(problem) = pick {
+                    println!("{}", problem);
+                }
+            }
+        }
+        Commands::NextUp { pattern } => {
+            let filters = FilterArgs {
+                category: None,
+                pattern: Some(pattern.clone()),
+                difficulty: None,
+                source: None,
+                min_time: None,
+                max_time: None,
+            };
+            let mut problems = tracker.get_matching(&filters)?;
+            annotate(tracker, &mut problems)?;
+            if problems.is_empty() {
+                println!("No problems with pattern '{}'", pattern);
+                return Ok(());
+            }
+
+            match plan::next_up(&problems) {
+                Some(next) => {
+                    println!(
+                        "Next up in '{}' ({} unsolved at this difficulty)\n",
+                        pattern, next.left_in_tier
+                    );
+                    println!("{}", next.problem);
+                }
+                None => println!("Every problem with pattern '{}' is solved", pattern),
+            }
+        }
+        Commands::Daily {
+            category,
+            focus_only,
+        } => {
+            let filters = FilterArgs {
+                category: category.clone(),
+                pattern: None,
+                difficulty: None,
+                source: None,
+                min_time: None,
+                max_time: None,
+            };
+            let mut problems = tracker.get_matching(&filters)?;
+            if *focus_only {
+                problems.retain(|problem| problem.focused);
+            }
+            annotate(tracker, &mut problems)?;
+            let today = clock::today();
+            match plan::daily_pick(&problems, today) {
+                Some(problem) => {
+                    println!("Problem of the day for {}\n", today.format("%Y-%m-%d"));
+                    println!("{}", problem);
+                }
+                None => println!("No problems found"),
+            }
+        }
+        Commands::Meta => {
+            export::write_value(&tracker.get_meta()?, cli.pretty, &mut std::io::stdout())?;
+        }
+        Commands::History { limit } => {
+            let entries = tracker.get_audit_log(*limit)?;
+            if entries.is_empty() {
+                println!("No changes recorded");
+            }
+            for entry in entries {
+                let problem = entry
+                    .problem_id
+                    .map(|id| format!("#{}", id))
+                    .unwrap_or_default();
+                println!(
+                    "{:<16}  {:<13}  {:<5}  {}",
+                    humanize::format_timestamp(entry.logged_at, cli.absolute_dates),
+                    entry.command,
+                    problem,
+                    entry.description
+                );
+            }
+        }
+        Commands::Query { sql, explain } => match tracker.run_query(sql, *explain) {
+            Ok(lines) => {
+                for line in lines {
+                    println!("{}", line);
+                }
+            }
+            Err(rusqlite::Error::InvalidParameterName(message)) => {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+            Err(rusqlite::Error::SqliteFailure(_, Some(message))) => {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+            Err(error) => return Err(error.into()),
+        },
+        Commands::Info => {
+            let db_path = cli.database.to_string_lossy();
+            if cli.uri || db_path == MEMORY_DATABASE {
+                println!("Database: {}", db_path);
+            } else {
+                println!("Database: {}", doctor::resolve(&cli.database).display());
+                let size = std::fs::metadata(&cli.database)?.len();
+                let wal = std::fs::metadata(sidecar(&cli.database, "-wal"))
+                    .map_or(0, |metadata| metadata.len());
+                if wal > 0 {
+                    println!(
+                        "Size: {} (plus {} in the -wal file)",
+                        humanize::file_size(size),
+                        humanize::file_size(wal)
+                    );
+                } else {
+                    println!("Size: {}", humanize::file_size(size));
+                }
+            }
+            println!(
+                "Schema version: {} (this build supports {})",
+                schema::user_version(&tracker.conn)?,
+                schema::SCHEMA_VERSION
+            );
+            println!("Problems: {}", tracker.count_problems()?);
+            println!("SQLite: {}", rusqlite::version());
+            println!("shikari: {}", env!("CARGO_PKG_VERSION"));
+        }
+        Commands::Doctor => unreachable!("doctor runs before the database is opened"),
+        Commands::Config { .. } => unreachable!("config runs before the database is opened"),
+        Commands::Rename { .. } => unreachable!("rename runs before the database is opened"),
+        Commands::Delete { id, force, purge } => {
+            let problem = tracker.get_problem(*id).or_else(|error| match purge {
+                true => tracker.get_trashed_problem(*id),
+                false => Err(error),
+            });
+            let Ok(problem) = problem else {
+                println!("Problem with ID {} not found", id);
+                return Ok(());
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+            if !*purge {
+                match tracker.trash_problem(*id, *force) {
+                    Ok(_) => println!(
+                        "Moved problem #{} to the trash; `restore {}` brings it back",
+                        id, id
+                    ),
+                    Err(_) => println!("Problem with ID {} not found", id),
+                }
+                return Ok(());
+            }
+            if !*force && !cli.yes && !std::io::stdin().is_terminal() {
+                eprintln!(
+                    "Refusing to delete problem #{} without confirmation: stdin is not a terminal. Pass --force to delete non-interactively.",
+                    id
+                );
+                std::process::exit(1);
+            }
+
+            if !*force && !cli.yes {
+                println!("{}\n", problem);
+            }
+            let prompt = format!("Are you sure you want to delete problem #{} for good?", id);
+            if !*force && !confirm(&prompt, cli.yes)? {
+                println!("Deletion cancelled");
+                return Ok(());
+            }
+
+            match tracker.delete_problem(*id, *force) {
+                Ok(_) => println!("Deleted problem #{} for good", id),
+                Err(_) => println!("Problem with ID {} not found", id),
+            }
+        }
+        Commands::ResetProblem { id, force } => {
+            let Ok(problem) = tracker.get_problem(*id) else {
+                println!("Problem with ID {} not found", id);
+                return Ok(());
+            };
+            if !may_change(*id, &problem, *force) {
+                return Ok(());
+            }
+            if !*force && !cli.yes && !std::io::stdin().is_terminal() {
+                eprintln!(
+                    "Refusing to reset problem #{} without confirmation: stdin is not a terminal. Pass --force to reset non-interactively.",
+                    id
+                );
+                std::process::exit(1);
+            }
+
+            if !*force && !cli.yes {
+                println!("{}\n", problem);
+            }
+            let prompt = format!("Clear all attempts and the review flag of problem #{}?", id);
+            if !*force && !confirm(&prompt, cli.yes)? {
+                println!("Reset cancelled");
+                return Ok(());
+            }
+
+            match tracker.reset_problem(*id, *force) {
+                Ok(_) => println!("Reset problem #{}", id),
+                Err(_) => println!("Problem with ID {} not found", id),
+            }
+        }
+        Commands::Trash => {
+            let trashed = tracker.get_trashed()?;
+            if trashed.is_empty() {
+                println!("The trash is empty");
+                return Ok(());
+            }
+
+            println!("Trash ({})", trashed.len());
+            for (deleted_at, problem) in trashed {
+                println!(
+                    "\n[deleted {}] {}",
+                    humanize::format_timestamp(deleted_at, cli.absolute_dates),
+                    problem
+                );
+            }
+        }
+        Commands::EmptyTrash { older_than, force } => {
+            let cutoff = older_than.map(|days| Utc::now() - Duration::days(days as i64));
+            let trashed = tracker
+                .get_trashed()?
+                .into_iter()
+                .filter(|(deleted_at, _)| cutoff.is_none_or(|cutoff| *deleted_at < cutoff))
+                .count();
+            if trashed == 0 {
+                println!("Nothing in the trash to purge");
+                return Ok(());
+            }
+            if !*force && !cli.yes && !std::io::stdin().is_terminal() {
+                eprintln!(
+                    "Refusing to empty the trash without confirmation: stdin is not a terminal. Pass --force to empty it non-interactively."
+                );
+                std::process::exit(1);
+            }
+            let prompt = format!("Delete {} problems in the trash for good?", trashed);
+            if !*force && !confirm(&prompt, cli.yes)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            let purged = tracker.purge_trash(cutoff)?;
+            println!("Purged {} problems from the trash", purged);
+        }
+        Commands::Restore { id } => match tracker.restore_problem(*id) {
+            Ok(_) => println!("Restored problem #{}", id),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                println!("Problem with ID {} is not in the trash", id)
+            }
+            Err(error) => return Err(error.into()),
+        },
+        Commands::Lint { fix, force } => {
+            let mut found = 0;
+            let mut fixes = Vec::new();
+            let mut locked = 0;
+            for mut problem in tracker.get_all_problems()? {
+                let issues = lint::issues(&problem);
+                if issues.is_empty() {
+                    continue;
+                }
+                let id = problem.id.unwrap_or_default();
+                for check in &issues {
+                    println!(
+                        "#{} {}: {} [{}{}]",
+                        id,
+                        problem.description,
+                        check.issue,
+                        check.name,
+                        if check.is_fixable() { ", fixable" } else { "" }
+                    );
+                }
+                found += issues.len();
+
+                if *fix {
+                    if problem.locked && !force {
+                        locked += 1;
+                        continue;
+                    }
+                    let fixed = lint::fix(&mut problem);
+                    if !fixed.is_empty() {
+                        fixes.push((problem, fixed));
+                    }
+                }
+            }
+
+            if found == 0 {
+                println!("No issues found");
+                return Ok(());
+            }
+            println!("\n{} issues found", found);
+            if *fix {
+                tracker.save_lint_fixes(&fixes)?;
+                let fixed: usize = fixes.iter().map(|(_, checks)| checks.len()).sum();
+                println!("Fixed {} of them in {} problems", fixed, fixes.len());
+                if locked > 0 {
+                    println!(
+                        "Skipped {} locked problems; pass --force to fix them too",
+                        locked
+                    );
+                }
+            }
+        }
+        Commands::NormalizeDifficulty { force } => {
+            let started = Instant::now();
+            let (changed, locked) = tracker.normalize_difficulties(*force)?;
+            println!(
+                "{}",
+                humanize::bulk_summary(
+                    &[("Normalized", changed), ("skipped", locked)],
+                    started.elapsed()
+                )
+            );
+            if locked > 0 {
+                println!(
+                    "Skipped {} locked problems; pass --force to rewrite them too",
+                    locked
+                );
+            }
+
+            let unknown = tracker.get_unknown_difficulties()?;
+            if !unknown.is_empty() {
+                println!("\nUnrecognized difficulties to fix by hand:");
+                for (id, difficulty) in unknown {
+                    println!("  #{}: {:?}", id, difficulty);
+                }
+            }
+        }
+        Commands::Renumber { force } => {
+            if !force {
+                eprintln!(
+                    "Renumbering changes problem IDs, so anything that refers to the old ones \
+                    (bookmarks, notes, scripts) will point at the wrong problem. \
+                    Pass --force to renumber anyway."
+                );
+                std::process::exit(1);
+            }
+
+            match tracker.renumber()? {
+                0 => println!("IDs are already sequential"),
+                moved => println!("Renumbered {} problems", moved),
+            }
+        }
+        Commands::SetDifficulty { filters, to, force } => {
+            // Without a filter every problem would be relabelled
+            if filters.category.is_none()
+                && filters.pattern.is_none()
+                && filters.difficulty.is_none()
+                && filters.source.is_none()
+                && filters.min_time.is_none()
+                && filters.max_time.is_none()
+            {
+                eprintln!("Give at least one filter, such as --pattern or --category");
+                std::process::exit(1);
+            }
+            check_time_range(filters);
+
+            let started = Instant::now();
+            let (changed, skipped) = tracker.bulk_set_difficulty(filters, to, *force)?;
+            println!(
+                "Difficulty set to '{}': {}",
+                to,
+                humanize::bulk_summary(
+                    &[("changed", changed), ("skipped", skipped)],
+                    started.elapsed()
+                )
+            );
+            if skipped > 0 {
+                println!(
+                    "Skipped {} locked problems; pass --force to change them",
+                    skipped
+                );
+            }
+        }
+        Commands::Lock { id } => match tracker.set_locked(*id, true) {
+            Ok(_) => println!("Locked problem #{}", id),
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::Focus { id } => match tracker.set_focus(*id, true) {
+            Ok(_) => println!("Added problem #{} to the focus list", id),
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::Unfocus { id } => match tracker.set_focus(*id, false) {
+            Ok(_) => println!("Removed problem #{} from the focus list", id),
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+        Commands::FocusList { listing } => {
+            let (problems, total) = tracker.get_listing(focus_query(), listing)?;
+            print_listing(
+                &problems,
+                total,
+                listing,
+                "Focus List",
+                "The focus list is empty; add problems with focus",
+            )?;
+        }
+        Commands::Unlock { id } => match tracker.set_locked(*id, false) {
+            Ok(_) => println!("Unlocked problem #{}", id),
+            Err(_) => println!("Problem with ID {} not found", id),
+        },
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testdir::TempDir;
+
+    fn tracker() -> ProblemTracker {
+        ProblemTracker::from_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn add(tracker: &ProblemTracker, description: &str) -> i64 {
+        tracker.add_problem(Problem::new(description)).unwrap()
+    }
+
+    fn keywords(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn add_and_get_round_trip() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.link = Some("https://leetcode.com/problems/two-sum/".to_string());
+        problem.category = Some("Arrays".to_string());
+        problem.pattern = Some("hash map".to_string());
+        problem.difficulty = Some("Easy".to_string());
+        problem.time_to_solve_1st = Some(12.0);
+        problem.comments = Some("store complements".to_string());
+        problem.should_solve_again = true;
+
+        let id = tracker.add_problem(problem).unwrap();
+        let stored = tracker.get_problem(id).unwrap();
+
+        assert_eq!(stored.id, Some(id));
+        assert_eq!(stored.description, "Two Sum");
+        assert_eq!(stored.category.as_deref(), Some("Arrays"));
+        assert_eq!(stored.pattern.as_deref(), Some("hash map"));
+        assert_eq!(stored.difficulty.as_deref(), Some("Easy"));
+        assert_eq!(stored.time_to_solve_1st, Some(12.0));
+        assert_eq!(stored.time_to_solve_2nd, None);
+        assert_eq!(stored.comments.as_deref(), Some("store complements"));
+        assert!(stored.should_solve_again);
+        assert_eq!(tracker.get_attempts(id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn empty_database_returns_no_problems() {
+        let tracker = tracker();
+        assert!(tracker.get_all_problems().unwrap().is_empty());
+        assert!(tracker.get_problems_to_review().unwrap().is_empty());
+        assert!(tracker
+            .search_problems(&keywords(&["sum"]), SearchMode::And)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn get_missing_problem_errors() {
+        let tracker = tracker();
+        assert!(matches!(
+            tracker.get_problem(42),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+    }
+
+    #[test]
+    fn update_solve_time_sets_column_and_logs_attempt() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+
+        // Attempt 1 was never logged
+        assert!(tracker
+            .update_solve_time(id, 2, 15.0, Utc::now(), false)
+            .is_err());
+        tracker
+            .update_solve_time(id, 2, 15.0, Utc::now(), true)
+            .unwrap();
+
+        let problem = tracker.get_problem(id).unwrap();
+        assert_eq!(problem.time_to_solve_1st, None);
+        assert_eq!(problem.time_to_solve_2nd, Some(15.0));
+        let attempts = tracker.get_attempts(id).unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].attempt, 2);
+        assert_eq!(attempts[0].minutes, 15.0);
+    }
+
+    #[test]
+    fn update_solve_time_rejects_invalid_attempt() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+
+        for attempt in [0, 4] {
+            assert!(tracker
+                .update_solve_time(id, attempt, 10.0, Utc::now(), false)
+                .is_err());
+        }
+        assert!(tracker.get_attempts(id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn update_solve_time_missing_problem_errors() {
+        let tracker = tracker();
+        assert!(tracker
+            .update_solve_time(42, 1, 10.0, Utc::now(), false)
+            .is_err());
+    }
+
+    #[test]
+    fn review_max_keeps_the_most_overdue_after_filtering() {
+        let tracker = tracker();
+        let mut ids = Vec::new();
+        for (description, days_ago) in [("Old", 30), ("Recent", 5), ("Newest", 2)] {
+            let id = add(&tracker, description);
+            let attempted_at = Utc::now() - Duration::days(days_ago);
+            tracker.record_attempt(id, 1, 10.0, attempted_at).unwrap();
+            tracker.set_review_flag(id, true).unwrap();
+            ids.push(id);
+        }
+
+        let listing = ListingArgs {
+            last_days: Some(10),
+            sort_by: Some(SortField::Id),
+            desc: true,
+            ..ListingArgs::default()
+        };
+        let (problems, total, flagged) = review_listing(&tracker, &listing, Some(1)).unwrap();
+        assert_eq!((total, flagged), (1, 2));
+        assert_eq!(problems[0].id, Some(ids[1]));
+
+        let (problems, _, flagged) =
+            review_listing(&tracker, &ListingArgs::default(), Some(2)).unwrap();
+        assert_eq!(flagged, 3);
+        let shown: Vec<_> = problems.iter().map(|problem| problem.id).collect();
+        assert_eq!(shown, [Some(ids[0]), Some(ids[1])]);
+    }
+
+    #[test]
+    fn toggle_review_flag_flips() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+
+        tracker.toggle_review_flag(id).unwrap();
+        assert!(tracker.get_problem(id).unwrap().should_solve_again);
+        assert_eq!(tracker.get_problems_to_review().unwrap().len(), 1);
+
+        tracker.toggle_review_flag(id).unwrap();
+        assert!(!tracker.get_problem(id).unwrap().should_solve_again);
+        assert!(tracker.get_problems_to_review().unwrap().is_empty());
+    }
+
+    #[test]
+    fn mark_solved_fills_next_attempt_and_clears_review() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.time_to_solve_1st = Some(30.0);
+        problem.should_solve_again = true;
+        let id = tracker.add_problem(problem).unwrap();
+
+        let attempt = tracker.get_problem(id).unwrap().next_attempt();
+        assert_eq!(attempt, Some(2));
+        tracker.mark_solved(id, 2, 12.0, Utc::now(), false).unwrap();
+
+        let mut problem = tracker.get_problem(id).unwrap();
+        assert_eq!(problem.time_to_solve_2nd, Some(12.0));
+        assert!(!problem.should_solve_again);
+        problem.time_to_solve_3rd = Some(9.0);
+        assert_eq!(problem.next_attempt(), None);
+    }
+
+    #[test]
+    fn log_next_attempt_counts_columns_and_solved_attempts() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.time_to_solve_1st = Some(30.0);
+        let id = tracker.add_problem(problem).unwrap();
+        let gave_up = Attempt {
+            attempt: 2,
+            minutes: 40.0,
+            attempted_at: Utc::now(),
+            solved: false,
+            note: None,
+        };
+        tracker.log_attempt(id, &gave_up, true).unwrap();
+
+        assert_eq!(tracker.log_next_attempt(id, 15.0, Utc::now()).unwrap(), 2);
+        assert_eq!(tracker.log_next_attempt(id, 10.0, Utc::now()).unwrap(), 3);
+        assert!(tracker.log_next_attempt(id, 5.0, Utc::now()).is_err());
+        let problem = tracker.get_problem(id).unwrap();
+        assert_eq!(
+            (problem.time_to_solve_2nd, problem.time_to_solve_3rd),
+            (Some(15.0), Some(10.0))
+        );
+    }
+
+    #[test]
+    fn problems_by_tags_match_all_or_any() {
+        let tracker = tracker();
+        for (description, tags) in [
+            ("Course Schedule", ["graph", "hard-for-me"].as_slice()),
+            ("Number of Islands", ["graph"].as_slice()),
+            ("Edit Distance", ["dp", "hard-for-me"].as_slice()),
+        ] {
+            let mut problem = Problem::new(description);
+            for tag in tags {
+                problem.add_tag(tag);
+            }
+            tracker.add_problem(problem).unwrap();
+        }
+        let descriptions = |tags: &[&str], all| -> Vec<String> {
+            let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+            tracker
+                .query_problems(tags_query(&tags, all))
+                .unwrap()
+                .into_iter()
+                .map(|problem| problem.description)
+                .collect()
+        };
+
+        assert_eq!(
+            descriptions(&["graph", "hard-for-me", "graph"], true),
+            ["Course Schedule"]
+        );
+        assert_eq!(
+            descriptions(&["graph", "dp"], false),
+            ["Course Schedule", "Number of Islands", "Edit Distance"]
+        );
+    }
+
+    #[test]
+    fn every_differing_field_has_a_value() {
+        let before = Problem::new("Two Sum");
+        let mut after = Problem::new("2Sum");
+        after.set_link(parse_link("https://leetcode.com/problems/two-sum/").unwrap());
+        after.add_tag("arrays");
+        after.category = Some("Arrays".to_string());
+        after.pattern = Some("hashing".to_string());
+        after.difficulty = Some("Easy".to_string());
+        after.time_to_solve_2nd = Some(12.0);
+        after.comments = Some("one pass".to_string());
+        after.should_solve_again = true;
+        after.company = Some("Google".to_string());
+        after.frequency = Some(0.5);
+        after.personal_difficulty = Some(2);
+        after.locked = true;
+        after.source = "neetcode".to_string();
+        after.focused = true;
+
+        let fields = differing_fields(&before, &after);
+        assert_eq!(fields.len(), 15);
+        assert_eq!(field_value(&before, "solve times"), "-, -, -");
+        for field in fields {
+            assert_ne!(field_value(&before, field), field_value(&after, field));
+        }
+    }
+
+    #[test]
+    fn time_spent_sums_every_attempt() {
+        let tracker = tracker();
+        for (pattern, minutes) in [(Some("dp"), 50.0), (Some("dp"), 25.0), (None, 10.0)] {
+            let mut problem = Problem::new("Problem");
+            problem.pattern = pattern.map(str::to_string);
+            let id = tracker.add_problem(problem).unwrap();
+            let gave_up = Attempt {
+                attempt: 1,
+                minutes,
+                attempted_at: Utc::now(),
+                solved: false,
+                note: None,
+            };
+            tracker.log_attempt(id, &gave_up, false).unwrap();
+        }
+
+        assert_eq!(tracker.get_time_spent(None).unwrap(), [(None, 85)]);
+        assert_eq!(
+            tracker.get_time_spent(Some(GroupField::Pattern)).unwrap(),
+            [(Some("dp".to_string()), 75), (None, 10)]
+        );
+    }
+
+    #[test]
+    fn report_records_skip_give_ups_and_trashed_problems() {
+        let tracker = tracker();
+        let solved = add(&tracker, "Two Sum");
+        let trashed = add(&tracker, "Three Sum");
+        for id in [solved, trashed] {
+            tracker
+                .update_solve_time(id, 1, 10.0, Utc::now(), false)
+                .unwrap();
+        }
+        let gave_up = Attempt {
+            attempt: 2,
+            minutes: 40.0,
+            attempted_at: Utc::now(),
+            solved: false,
+            note: None,
+        };
+        tracker.log_attempt(solved, &gave_up, false).unwrap();
+        tracker.trash_problem(trashed, false).unwrap();
+
+        let records = tracker.get_attempt_records().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].problem_id, records[0].minutes), (solved, 10.0));
+    }
+
+    #[test]
+    fn only_slow_solves_are_flagged_for_review() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+
+        assert!(!tracker.flag_slow_solve(id, 30.0, 30).unwrap());
+        assert!(!tracker.get_problem(id).unwrap().should_solve_again);
+        assert!(tracker.flag_slow_solve(id, 31.0, 30).unwrap());
+        assert!(tracker.get_problem(id).unwrap().should_solve_again);
+    }
+
+    #[test]
+    fn average_first_time_covers_one_difficulty() {
+        let tracker = tracker();
+        for (difficulty, first) in [
+            ("Easy", Some(10.0)),
+            ("easy", Some(20.0)),
+            ("Easy", None),
+            ("Hard", Some(60.0)),
+        ] {
+            let mut problem = Problem::new("Problem");
+            problem.difficulty = Some(difficulty.to_string());
+            problem.time_to_solve_1st = first;
+            tracker.add_problem(problem).unwrap();
+        }
+
+        assert_eq!(tracker.get_average_first_time("Easy").unwrap(), Some(15.0));
+        assert_eq!(tracker.get_average_first_time("Medium").unwrap(), None);
+    }
+
+    #[test]
+    fn uri_options_are_checked() {
+        assert!(check_uri("problems.db", false).is_err());
+        assert!(check_uri("file:problems.db?mode=rwc", true).is_err());
+        assert!(check_uri("file:problems.db?mode=rwx", false).is_err());
+        assert!(check_uri("file:problems.db?mode=rwc&cache=shared", false).is_ok());
+
+        assert!(uri_is_read_only(
+            "file:problems.db?cache=shared&immutable=1"
+        ));
+        assert!(uri_is_read_only("file:problems.db?mode=ro"));
+        assert!(!uri_is_read_only("file:problems.db?mode=rw"));
+    }
+
+    #[test]
+    fn descriptions_fit_the_first_line_to_the_width() {
+        let mut problem = Problem::new("Best Time to Buy and Sell Stock with Cooldown");
+        problem.id = Some(7);
+        problem.difficulty = Some("Medium".to_string());
+
+        fit_description(&mut problem, 40);
+        let first_line = problem.to_string().lines().next().unwrap().to_string();
+        assert_eq!(first_line, "Problem #7: Best Time to Buy a… (Medium)");
+
+        fit_description(&mut problem, 10);
+        assert_eq!(problem.description.chars().count(), MIN_DESCRIPTION_WIDTH);
+    }
+
+    #[test]
+    fn display_options_toggle_markers_and_sections() {
+        let mut problem = Problem::new("Two Sum");
+        problem.comments = Some("hash map".to_string());
+        problem.should_solve_again = true;
+        problem.locked = true;
+
+        let full = problem.to_string();
+        assert!(full.contains("[REVIEW NEEDED]") && full.contains("Comments: hash map"));
+
+        let shared = problem
+            .format_with(DisplayOptions {
+                review_marker: false,
+                comments: false,
+                ..DisplayOptions::default()
+            })
+            .to_string();
+        assert!(!shared.contains("[REVIEW NEEDED]") && !shared.contains("Comments"));
+        assert!(shared.contains("[LOCKED]"));
+    }
+
+    #[test]
+    fn delete_removes_problem_and_attempts() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker
+            .update_solve_time(id, 1, 10.0, Utc::now(), false)
+            .unwrap();
+
+        tracker.delete_problem(id, false).unwrap();
+
+        assert!(tracker.get_problem(id).is_err());
+        assert!(tracker.get_attempts(id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn filters_match_exact_values() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.category = Some("Arrays".to_string());
+        problem.pattern = Some("hash map".to_string());
+        problem.difficulty = Some("Easy".to_string());
+        tracker.add_problem(problem).unwrap();
+        add(&tracker, "Unlabelled");
+
+        assert_eq!(
+            tracker
+                .query_problems(category_query("Arrays"))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            tracker
+                .query_problems(pattern_query("hash map"))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            tracker
+                .query_problems(difficulty_query("Easy"))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(tracker
+            .query_problems(category_query("Graphs"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn label_lookups_list_problems_by_id() {
+        let tracker = tracker();
+        for description in ["Two Sum", "3Sum", "4Sum"] {
+            let mut problem = Problem::new(description);
+            problem.category = Some("Arrays".to_string());
+            problem.pattern = Some("hash map".to_string());
+            problem.difficulty = Some("Medium".to_string());
+            tracker.add_problem(problem).unwrap();
+        }
+        tracker
+            .conn
+            .execute("UPDATE problems SET id = 10 WHERE id = 1", [])
+            .unwrap();
+
+        let ids = |problems: Vec<Problem>| -> Vec<Option<i64>> {
+            problems.iter().map(|problem| problem.id).collect()
+        };
+        let expected = [Some(2), Some(3), Some(10)];
+        assert_eq!(
+            ids(tracker.query_problems(category_query("Arrays")).unwrap()),
+            expected
+        );
+        assert_eq!(
+            ids(tracker.query_problems(pattern_query("hash map")).unwrap()),
+            expected
+        );
+        assert_eq!(
+            ids(tracker.query_problems(difficulty_query("Medium")).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn search_and_mode_requires_every_keyword() {
+        let tracker = tracker();
+        add(&tracker, "Two Sum");
+        add(&tracker, "Three Sum");
+
+        let problems = tracker
+            .search_problems(&keywords(&["sum", "three"]), SearchMode::And)
+            .unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].description, "Three Sum");
+    }
+
+    #[test]
+    fn search_or_mode_matches_any_keyword() {
+        let tracker = tracker();
+        add(&tracker, "Two Sum");
+        add(&tracker, "Three Sum");
+        add(&tracker, "Word Ladder");
+
+        let problems = tracker
+            .search_problems(&keywords(&["two", "ladder"]), SearchMode::Or)
+            .unwrap();
+
+        assert_eq!(problems.len(), 2);
+    }
+
+    #[test]
+    fn search_keywords_may_match_different_fields() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.pattern = Some("hash map".to_string());
+        problem.comments = Some("Check the complement first".to_string());
+        tracker.add_problem(problem).unwrap();
+
+        let count = |words: &[&str], mode| tracker.count_search_matches(&keywords(words), mode);
+        assert_eq!(
+            count(&["sum", "hash", "complement"], SearchMode::And).unwrap(),
+            1
+        );
+        assert_eq!(count(&["sum", "graph"], SearchMode::And).unwrap(), 0);
+        assert_eq!(count(&["graph", "HASH"], SearchMode::Or).unwrap(), 1);
+        assert_eq!(count(&["graph", "tree"], SearchMode::Or).unwrap(), 0);
+    }
+
+    #[test]
+    fn search_treats_a_phrase_as_one_keyword() {
+        let tracker = tracker();
+        add(&tracker, "Two Sum");
+        add(&tracker, "Sum of Two Integers");
+
+        let problems = tracker
+            .search_problems(&keywords(&["two sum"]), SearchMode::And)
+            .unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].description, "Two Sum");
+    }
+
+    #[test]
+    fn like_wildcards_match_only_themselves() {
+        let tracker = tracker();
+        let discount = add(&tracker, "100% Discount");
+        let snake = add(&tracker, "snake_case");
+        add(&tracker, "100 Points");
+        add(&tracker, "snake case");
+        let mut problem = tracker.get_problem(snake).unwrap();
+        problem.company = Some("A_B,Google".to_string());
+        tracker.update_problem(&problem, false).unwrap();
+
+        let ids = |problems: Vec<Problem>| problems.iter().filter_map(|p| p.id).collect::<Vec<_>>();
+        let search = |keyword: &str| {
+            ids(tracker
+                .search_problems(&keywords(&[keyword]), SearchMode::And)
+                .unwrap())
+        };
+        assert_eq!(search("100%"), [discount]);
+        assert_eq!(search("snake_"), [snake]);
+        assert_eq!(search("%"), [discount]);
+        let company = |name: &str| ids(tracker.query_problems(company_query(name)).unwrap());
+        assert_eq!(company("A_B"), [snake]);
+        assert!(company("A%").is_empty());
+        assert!(company("AxB").is_empty());
+    }
+
+    #[test]
+    fn mutations_are_recorded_in_audit_log() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker
+            .update_solve_time(id, 1, 10.0, Utc::now(), false)
+            .unwrap();
+        tracker.toggle_review_flag(id).unwrap();
+        tracker.delete_problem(id, false).unwrap();
+
+        let commands: Vec<String> = tracker
+            .get_audit_log(10)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.command)
+            .collect();
+        assert_eq!(commands, ["delete", "toggle-review", "update-time", "add"]);
+        assert!(tracker.conn.execute("DELETE FROM audit_log", []).is_err());
+    }
+
+    #[test]
+    fn personal_difficulty_round_trips() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+
+        tracker.set_personal_difficulty(id, 4).unwrap();
+
+        assert_eq!(
+            tracker.get_problem(id).unwrap().personal_difficulty,
+            Some(4)
+        );
+        assert!(tracker.set_personal_difficulty(id, 6).is_err());
+        assert!(tracker.set_personal_difficulty(42, 3).is_err());
+    }
+
+    #[test]
+    fn problems_without_link_ignore_other_labels() {
+        let tracker = tracker();
+        let mut linked = Problem::new("Two Sum");
+        linked.set_link(parse_link("https://leetcode.com/problems/two-sum/").unwrap());
+        tracker.add_problem(linked).unwrap();
+        let mut editorial_only = Problem::new("Word Ladder");
+        editorial_only.set_link(parse_link("editorial=https://example.com/word-ladder").unwrap());
+        tracker.add_problem(editorial_only).unwrap();
+
+        let no_filters = FilterArgs {
+            category: None,
+            pattern: None,
+            difficulty: None,
+            source: None,
+            min_time: None,
+            max_time: None,
+        };
+        let problems = tracker
+            .query_problems(without_link_query(&no_filters))
+            .unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].description, "Word Ladder");
+    }
+
+    #[test]
+    fn unattempted_excludes_timed_problems_and_applies_filters() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.category = Some("Arrays".to_string());
+        tracker.add_problem(problem).unwrap();
+        let mut problem = Problem::new("Word Ladder");
+        problem.category = Some("Graphs".to_string());
+        tracker.add_problem(problem).unwrap();
+        let solved = add(&tracker, "Three Sum");
+        tracker
+            .update_solve_time(solved, 1, 20.0, Utc::now(), false)
+            .unwrap();
+
+        let no_filters = FilterArgs {
+            category: None,
+            pattern: None,
+            difficulty: None,
+            source: None,
+            min_time: None,
+            max_time: None,
+        };
+        assert_eq!(
+            tracker
+                .query_problems(unattempted_query(&no_filters))
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(tracker.get_matching(&no_filters).unwrap().len(), 3);
+
+        let graphs = FilterArgs {
+            category: Some("Graphs".to_string()),
+            ..no_filters
+        };
+        let problems = tracker.query_problems(unattempted_query(&graphs)).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].description, "Word Ladder");
+
+        let arrays = FilterArgs {
+            category: Some("arrays".to_string()),
+            pattern: None,
+            difficulty: None,
+            source: None,
+            min_time: None,
+            max_time: None,
+        };
+        assert_eq!(tracker.get_matching(&arrays).unwrap().len(), 1);
+
+        let slow = |min_time, max_time| FilterArgs {
+            category: None,
+            pattern: None,
+            difficulty: None,
+            source: None,
+            min_time,
+            max_time,
+        };
+        assert_eq!(
+            tracker.get_matching(&slow(Some(20.0), None)).unwrap().len(),
+            1
+        );
+        assert_eq!(
+            tracker.get_matching(&slow(Some(20.5), None)).unwrap().len(),
+            0
+        );
+        assert_eq!(
+            tracker.get_matching(&slow(None, Some(20.0))).unwrap().len(),
+            1
+        );
+        assert_eq!(
+            tracker
+                .get_matching(&slow(Some(10.0), Some(30.0)))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn link_slugs_name_the_problem() {
+        assert_eq!(
+            link_slug("https://leetcode.com/problems/two-sum/description/?tab=1").as_deref(),
+            Some("two-sum")
+        );
+        assert_eq!(
+            link_slug("https://codeforces.com/problemset/problem/4/A").as_deref(),
+            Some("4A")
+        );
+        assert_eq!(
+            link_slug("https://codeforces.com/contest/4/problem/A").as_deref(),
+            Some("4A")
+        );
+        assert_eq!(
+            link_slug("https://atcoder.jp/contests/abc300/tasks/abc300_a").as_deref(),
+            Some("abc300_a")
+        );
+    }
+
+    #[test]
+    fn slugs_are_unique_within_a_source() {
+        let tracker = tracker();
+        let with_link = |link: &str, source: &str| {
+            let mut problem = Problem::new("Two Sum");
+            problem.link = Some(link.to_string());
+            problem.source = source.to_string();
+            problem
+        };
+        let id = tracker
+            .add_problem(with_link(
+                "https://leetcode.com/problems/two-sum/",
+                "leetcode",
+            ))
+            .unwrap();
+
+        let error = tracker
+            .add_problem(with_link(
+                "https://leetcode.com/problems/two-sum/description/",
+                "leetcode",
+            ))
+            .unwrap_err();
+        assert!(error.to_string().contains(&format!("#{}", id)));
+        tracker
+            .add_problem(with_link(
+                "https://leetcode.com/problems/two-sum/",
+                "mirror",
+            ))
+            .unwrap();
+        tracker
+            .add_problem(with_link(
+                "https://codeforces.com/contest/4/problem/A",
+                "codeforces",
+            ))
+            .unwrap();
+        tracker
+            .add_problem(with_link(
+                "https://codeforces.com/contest/5/problem/A",
+                "codeforces",
+            ))
+            .unwrap();
+
+        // Rewriting a problem keeps its own slug
+        let problem = tracker.get_problem(id).unwrap();
+        tracker.update_problem(&problem, false).unwrap();
+    }
+
+    #[test]
+    fn link_source_recognizes_known_judges() {
+        assert_eq!(
+            link_source("https://codeforces.com/problemset/problem/4/A"),
+            Some("codeforces")
+        );
+        assert_eq!(
+            link_source("https://www.leetcode.com/problems/two-sum/"),
+            Some("leetcode")
+        );
+        assert_eq!(
+            link_source("atcoder.jp/contests/abc300/tasks/abc300_a"),
+            Some("atcoder")
+        );
+        assert_eq!(
+            link_source("https://notleetcode.com/problems/two-sum/"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_link_accepts_labels_and_bare_urls() {
+        let link = parse_link("editorial=https://example.com/e").unwrap();
+        assert_eq!(link.label, "editorial");
+        assert_eq!(link.url, "https://example.com/e");
+
+        let link = parse_link("https://youtube.com/watch?v=abc").unwrap();
+        assert_eq!(link.label, PROBLEM_LINK_LABEL);
+        assert_eq!(link.url, "https://youtube.com/watch?v=abc");
+
+        assert!(parse_link("editorial=").is_err());
+    }
+
+    #[test]
+    fn links_round_trip_and_mirror_problem_link() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.set_link(parse_link("https://leetcode.com/problems/two-sum/").unwrap());
+        problem.set_link(parse_link("video=https://youtube.com/watch?v=abc").unwrap());
+        let id = tracker.add_problem(problem).unwrap();
+
+        let mut stored = tracker.get_problem(id).unwrap();
+        assert_eq!(
+            stored.link.as_deref(),
+            Some("https://leetcode.com/problems/two-sum/")
+        );
+        assert_eq!(stored.all_links().len(), 2);
+
+        assert!(stored.remove_link(PROBLEM_LINK_LABEL));
+        tracker.update_problem(&stored, false).unwrap();
+
+        let stored = tracker.get_problem(id).unwrap();
+        assert_eq!(stored.link, None);
+        assert_eq!(stored.all_links()[0].label, "video");
+    }
+
+    #[test]
+    fn locked_problems_refuse_changes_unless_forced() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker.set_locked(id, true).unwrap();
+
+        let problem = tracker.get_problem(id).unwrap();
+        assert!(!may_change(id, &problem, false));
+        assert!(may_change(id, &problem, true));
+        tracker.set_locked(id, false).unwrap();
+        assert!(may_change(id, &tracker.get_problem(id).unwrap(), false));
+
+        tracker.set_locked(id, true).unwrap();
+        let rows = [import::SolveTime {
+            line: 2,
+            id,
+            attempt: 1,
+            minutes: 10.0,
+        }];
+        assert_eq!(
+            tracker.log_solve_times(&rows, Utc::now(), false).unwrap(),
+            [format!("line 2: {}", locked_message(id))]
+        );
+        assert_eq!(tracker.get_problem(id).unwrap().time_to_solve_1st, None);
+        assert!(tracker
+            .log_solve_times(&rows, Utc::now(), true)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            tracker.get_problem(id).unwrap().time_to_solve_1st,
+            Some(10.0)
+        );
+
+        // `solved` and `finish` both record through `mark_solved`
+        assert!(matches!(
+            tracker.mark_solved(id, 2, 8.0, Utc::now(), false),
+            Err(rusqlite::Error::InvalidParameterName(message)) if message == locked_message(id)
+        ));
+        assert!(tracker.log_next_attempt(id, 8.0, Utc::now()).is_err());
+        let mut problem = tracker.get_problem(id).unwrap();
+        problem.comments = Some("Edited".to_string());
+        assert!(tracker.update_problem(&problem, false).is_err());
+        assert!(tracker.edit_problem(&problem, false, &[], &[]).is_err());
+        assert!(tracker.set_solution(id, Some("x"), None, false).is_err());
+        assert!(tracker.reset_problem(id, false).is_err());
+        assert!(tracker.trash_problem(id, false).is_err());
+        assert!(tracker.delete_problem(id, false).is_err());
+        let stored = tracker.get_problem(id).unwrap();
+        assert_eq!(stored.comments, None);
+        assert_eq!(stored.time_to_solve_1st, Some(10.0));
+        assert_eq!(tracker.get_solution(id).unwrap(), None);
+
+        tracker.mark_solved(id, 2, 8.0, Utc::now(), true).unwrap();
+        assert_eq!(
+            tracker.get_problem(id).unwrap().time_to_solve_2nd,
+            Some(8.0)
+        );
+    }
+
+    #[test]
+    fn temp_files_get_fresh_names() {
+        let (first, _) = create_temp_file("txt").unwrap();
+        let (second, _) = create_temp_file("txt").unwrap();
+        assert_ne!(first, second);
+        assert!(first.exists() && second.exists());
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn solutions_are_saved_and_cleared() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        assert_eq!(tracker.get_solution(id).unwrap(), None);
+
+        tracker
+            .set_solution(id, Some("fn main() {}\n"), Some("Rust"), false)
+            .unwrap();
+        tracker
+            .set_solution(id, Some("fn main() {}\n"), None, false)
+            .unwrap();
+        assert_eq!(
+            tracker.get_language_counts().unwrap(),
+            BTreeMap::from([("rust".to_string(), 1)])
+        );
+        assert_eq!(
+            tracker
+                .query_problems(language_query("RUST"))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            tracker.get_solution(id).unwrap().as_deref(),
+            Some("fn main() {}\n")
+        );
+        assert!(tracker.get_problem(id).unwrap().comments.is_none());
+
+        tracker.set_solution(id, None, None, false).unwrap();
+        assert_eq!(tracker.get_solution(id).unwrap(), None);
+        assert!(tracker.get_language_counts().unwrap().is_empty());
+        assert!(tracker.set_solution(99, Some("x"), None, false).is_err());
+    }
+
+    #[test]
+    fn moving_a_problem_keeps_its_solution() {
+        let dest = tracker();
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker
+            .set_solution(id, Some("fn main() {}\n"), Some("rust"), false)
+            .unwrap();
+
+        let new_id = tracker.move_problem(id, &dest).unwrap();
+        assert!(tracker.get_problem(id).is_err());
+        assert_eq!(
+            dest.get_solution(new_id).unwrap().as_deref(),
+            Some("fn main() {}\n")
+        );
+        assert_eq!(dest.get_language_counts().unwrap()["rust"], 1);
+    }
+
+    #[test]
+    fn trashed_problems_are_hidden_until_restored() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        add(&tracker, "Valid Anagram");
+
+        tracker.trash_problem(id, false).unwrap();
+        assert!(tracker.get_problem(id).is_err());
+        assert_eq!(tracker.get_all_problems().unwrap().len(), 1);
+        assert!(tracker.set_review_flag(id, true).is_ok());
+        assert_eq!(tracker.get_trashed().unwrap()[0].1.description, "Two Sum");
+        assert!(tracker.trash_problem(id, false).is_err());
+
+        tracker.restore_problem(id).unwrap();
+        assert!(!tracker.get_problem(id).unwrap().should_solve_again);
+        assert!(tracker.get_trashed().unwrap().is_empty());
+        assert!(tracker.restore_problem(id).is_err());
+
+        tracker
+            .update_solve_time(id, 1, 20.0, Utc::now(), false)
+            .unwrap();
+        tracker.trash_problem(id, false).unwrap();
+        assert_eq!(
+            tracker
+                .purge_trash(Some(Utc::now() - Duration::days(1)))
+                .unwrap(),
+            0
+        );
+        assert_eq!(tracker.purge_trash(None).unwrap(), 1);
+        assert!(tracker.get_trashed().unwrap().is_empty());
+        assert!(tracker.get_attempts(id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn recent_views_list_each_problem_once_most_recent_first() {
+        let tracker = tracker();
+        let first = add(&tracker, "Two Sum");
+        let second = add(&tracker, "Three Sum");
+        tracker.record_view(first).unwrap();
+        tracker.record_view(second).unwrap();
+        tracker.record_view(first).unwrap();
+
+        let ids: Vec<Option<i64>> = tracker
+            .get_recent_views(10)
+            .unwrap()
+            .into_iter()
+            .map(|(problem, _)| problem.id)
+            .collect();
+        assert_eq!(ids, [Some(first), Some(second)]);
+
+        tracker.delete_problem(first, false).unwrap();
+        assert_eq!(tracker.get_recent_views(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn attempt_counts_cover_logged_attempts() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        add(&tracker, "Three Sum");
+        tracker
+            .update_solve_time(id, 1, 10.0, Utc::now(), false)
+            .unwrap();
+        tracker
+            .update_solve_time(id, 2, 8.0, Utc::now(), false)
+            .unwrap();
+
+        let mut problems = tracker.get_all_problems().unwrap();
+        annotate(&tracker, &mut problems).unwrap();
+
+        assert_eq!(problems[0].attempt_count, Some(2));
+        assert_eq!(problems[1].attempt_count, Some(0));
+        assert_eq!(problems[0].first_time, Some(10.0));
+        assert_eq!(problems[0].best_time, Some(8.0));
+        assert_eq!(problems[1].first_time, None);
+    }
+
+    #[test]
+    fn attempt_history_fills_in_times_without_logged_attempts() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker
+            .update_solve_time(id, 1, 8.0, Utc::now(), false)
+            .unwrap();
+        // A first time from before attempts were logged
+        let old_id = add(&tracker, "Old");
+        tracker
+            .conn
+            .execute(
+                "UPDATE problems SET time_to_solve_1st = 30 WHERE id = ?1",
+                [old_id],
+            )
+            .unwrap();
+
+        let rows = tracker.get_attempt_history().unwrap();
+        let summary: Vec<(i64, usize, f64, bool)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.problem_id,
+                    row.attempt_number,
+                    row.minutes,
+                    row.solved_at.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(summary, [(old_id, 1, 30.0, false), (id, 1, 8.0, true)]);
+
+        let mut out = Vec::new();
+        export::write_attempts(export::AttemptsFormat::Csv, &rows, false, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with("problem_id,attempt_number,minutes,solved_at,solved\n"));
+        assert!(csv.contains(&format!("{},1,30,,1\n", old_id)));
+    }
+
+    #[test]
+    fn reset_clears_history_but_keeps_the_problem() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker
+            .update_solve_time(id, 1, 10.0, Utc::now(), false)
+            .unwrap();
+        tracker.set_review_flag(id, true).unwrap();
+
+        tracker.reset_problem(id, false).unwrap();
+
+        let problem = tracker.get_problem(id).unwrap();
+        assert_eq!(problem.time_to_solve_1st, None);
+        assert!(!problem.should_solve_again);
+        assert!(tracker.get_attempt_history().unwrap().is_empty());
+        assert!(matches!(
+            tracker.reset_problem(99, false),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+    }
+
+    #[test]
+    fn difficulties_are_normalized_except_unknown_and_locked() {
+        let tracker = tracker();
+        let mut ids = Vec::new();
+        for difficulty in ["easy", " HARD ", "Medium", "Insane", "mEDIUM"] {
+            let mut problem = Problem::new(difficulty);
+            problem.difficulty = Some(difficulty.to_string());
+            ids.push(tracker.add_problem(problem).unwrap());
+        }
+        tracker.set_locked(ids[4], true).unwrap();
+
+        assert_eq!(tracker.normalize_difficulties(false).unwrap(), (2, 1));
+        let difficulty = |id| tracker.get_problem(id).unwrap().difficulty.unwrap();
+        assert_eq!(difficulty(ids[0]), "Easy");
+        assert_eq!(difficulty(ids[1]), "Hard");
+        assert_eq!(difficulty(ids[4]), "mEDIUM");
+        assert_eq!(
+            tracker.get_unknown_difficulties().unwrap(),
+            [(ids[3], "Insane".to_string())]
+        );
+
+        assert_eq!(tracker.normalize_difficulties(true).unwrap(), (1, 0));
+        assert_eq!(difficulty(ids[4]), "Medium");
+    }
+
+    #[test]
+    fn aggregate_stats_leaves_other_databases_unmigrated() {
+        let dir = TempDir::new("aggregate-read-only");
+        let other_path = dir.join("other.db");
+        let other = ProblemTracker::new(&other_path.to_string_lossy(), false, None).unwrap();
+        add(&other, "Two Sum");
+        other.conn.pragma_update(None, "user_version", 17).unwrap();
+        drop(other);
+
+        let (problems, _) = ProblemTracker::other_stats_inputs(&other_path, None).unwrap();
+        assert_eq!(problems.len(), 1);
+        let conn = Connection::open(&other_path).unwrap();
+        assert_eq!(schema::user_version(&conn).unwrap(), 17);
+    }
+
+    #[test]
+    fn query_reads_and_explains_but_never_writes() {
+        let tracker = tracker();
+        add(&tracker, "Two Sum");
+
+        assert_eq!(
+            tracker
+                .run_query("SELECT id, description, link FROM problems", false)
+                .unwrap(),
+            ["id\tdescription\tlink", "1\tTwo Sum\t"]
+        );
+        let plan = tracker
+            .run_query("SELECT * FROM problems WHERE id = 1", true)
+            .unwrap();
+        assert!(plan[0].starts_with("SEARCH problems"));
+        assert!(matches!(
+            tracker.run_query("DELETE FROM problems", false),
+            Err(rusqlite::Error::InvalidParameterName(_))
+        ));
+        assert!(tracker.run_query("DELETE FROM problems", true).is_ok());
+        assert_eq!(tracker.count_problems().unwrap(), 1);
+
+        for sql in [
+            "SELECT 1; DELETE FROM problems",
+            "SELECT 1; garbage",
+            "PRAGMA user_version = 99",
+        ] {
+            assert!(matches!(
+                tracker.run_query(sql, false),
+                Err(rusqlite::Error::InvalidParameterName(_))
+            ));
+        }
+        assert!(tracker.run_query("SELECT 1; -- done", false).is_ok());
+        assert_eq!(tracker.count_problems().unwrap(), 1);
+
+        let dir = TempDir::new("query-attach");
+        let attached = dir.join("other.db");
+        let sql = format!("ATTACH '{}' AS other", attached.display());
+        assert!(tracker.run_query(&sql, false).is_err());
+        assert!(!attached.exists());
+
+        // Refused and failing queries alike leave writes and ATTACH allowed
+        assert!(tracker.conn.limit(Limit::SQLITE_LIMIT_ATTACHED) > 0);
+        let query_only: bool = tracker
+            .conn
+            .query_row("PRAGMA query_only", [], |row| row.get(0))
+            .unwrap();
+        assert!(!query_only);
+    }
+
+    #[test]
+    fn solved_counts_group_categories_ignoring_case() {
+        let tracker = tracker();
+        for (category, minutes) in [
+            ("Graphs", Some(10.0)),
+            ("graphs", Some(20.0)),
+            ("Graphs", None),
+        ] {
+            let mut problem = Problem::new("Islands");
+            problem.category = Some(category.to_string());
+            problem.time_to_solve_1st = minutes;
+            tracker.add_problem(problem).unwrap();
+        }
+
+        let solved = tracker.get_solved_by_category().unwrap();
+        assert_eq!(solved, HashMap::from([("graphs".to_string(), 2)]));
+    }
+
+    #[test]
+    fn retry_lists_problems_whose_last_attempt_was_a_give_up() {
+        let tracker = tracker();
+        let give_up = |id, days_ago| {
+            let attempt = Attempt {
+                attempt: 1,
+                minutes: 30.0,
+                attempted_at: Utc::now() - Duration::days(days_ago),
+                solved: false,
+                note: None,
+            };
+            tracker.log_attempt(id, &attempt, false).unwrap();
+        };
+        let recent = add(&tracker, "Word Ladder");
+        let solved_since = add(&tracker, "Two Sum");
+        let waiting = add(&tracker, "Median of Two Sorted Arrays");
+        give_up(recent, 1);
+        give_up(solved_since, 5);
+        tracker
+            .update_solve_time(solved_since, 2, 20.0, Utc::now(), false)
+            .unwrap();
+        give_up(waiting, 9);
+
+        let ids: Vec<Option<i64>> = tracker
+            .get_failed_problems()
+            .unwrap()
+            .into_iter()
+            .map(|(_, problem)| problem.id)
+            .collect();
+        assert_eq!(ids, [Some(waiting), Some(recent)]);
+    }
+
+    #[test]
+    fn give_ups_are_logged_without_a_solve_time() {
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        let gave_up = Attempt {
+            attempt: 1,
+            minutes: 45.0,
+            attempted_at: Utc::now() - Duration::days(1),
+            solved: false,
+            note: Some("stuck on the hash map".to_string()),
+        };
+        tracker.log_attempt(id, &gave_up, false).unwrap();
+        tracker
+            .update_solve_time(id, 2, 20.0, Utc::now(), false)
+            .unwrap();
+
+        let attempts = tracker.get_attempts(id).unwrap();
+        assert_eq!(
+            attempts[0].to_string(),
+            "attempt 1, 45min, gave up - stuck on the hash map"
+        );
+        assert_eq!(attempts[1].to_string(), "attempt 2, 20min, solved");
+
+        let mut problems = tracker.get_all_problems().unwrap();
+        annotate(&tracker, &mut problems).unwrap();
+        assert_eq!(problems[0].time_to_solve_1st, None);
+        assert_eq!(problems[0].attempt_count, Some(2));
+        assert_eq!(problems[0].first_time, Some(20.0));
+        assert!(tracker.log_attempt(42, &gave_up, false).is_err());
+    }
+
+    #[test]
+    fn new_creates_missing_parent_directories() {
+        let root = TempDir::new("test");
+        let path = root.join("study").join("new").join("problems.db");
+        let path = path.to_string_lossy();
+
+        assert!(ProblemTracker::new(&path, false, None).is_err());
+        assert!(!root.join("study").exists());
+
+        let tracker = ProblemTracker::new(&path, true, None).unwrap();
+        add(&tracker, "Two Sum");
+        drop(tracker);
+        assert!(std::path::Path::new(path.as_ref()).exists());
+    }
+
+    #[test]
+    fn watched_runs_do_not_trigger_themselves() {
+        let root = TempDir::new("watch");
+        let path = root.join("problems.db");
+        let tracker = ProblemTracker::new(&path.to_string_lossy(), false, None).unwrap();
+        let id = add(&tracker, "Two Sum");
+        let before = database_modified(&path);
+
+        // What `show` does on every run
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let seen = run_watched(&path, || tracker.record_view(id).unwrap());
+        assert_ne!(seen, before);
+        assert_eq!(database_modified(&path), seen);
+    }
+
+    #[test]
+    fn rename_moves_sidecars_and_needs_force_to_replace() {
+        let root = TempDir::new("rename");
+        let from = root.join("problems.db");
+        let to = root.join("renamed.db");
+        for path in [&from, &sidecar(&from, "-wal"), &to, &sidecar(&to, "-shm")] {
+            std::fs::write(path, path.to_string_lossy().as_bytes()).unwrap();
+        }
+
+        assert!(rename_database(&from, &to, false).is_err());
+        assert!(rename_database(&from, &from, true).is_err());
+        assert_eq!(rename_database(&from, &to, true).unwrap(), ["-wal"]);
+        assert!(!from.exists() && !sidecar(&from, "-wal").exists());
+        assert_eq!(
+            std::fs::read_to_string(sidecar(&to, "-wal")).unwrap(),
+            sidecar(&from, "-wal").to_string_lossy()
+        );
+        // The replaced database's shared memory file would be stale
+        assert!(!sidecar(&to, "-shm").exists());
+    }
+
+    #[test]
+    fn writes_wait_for_another_connection_to_release_its_lock() {
+        let root = TempDir::new("lock");
+        let path = root.join("problems.db");
+        let tracker = ProblemTracker::new(&path.to_string_lossy(), true, None).unwrap();
+
+        let other = Connection::open(&path).unwrap();
+        other.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            other.execute_batch("COMMIT").unwrap();
+        });
+
+        add(&tracker, "Two Sum");
+        holder.join().unwrap();
+        assert_eq!(tracker.get_all_problems().unwrap().len(), 1);
+    }
 
-        /// Problem link
-        #[arg(short, long)]
-        link: Option<String>,
+    #[test]
+    fn retry_gives_up_with_a_clear_message() {
+        let mut attempts = 0;
+        let result: Result<()> = retry_when_busy(|| {
+            attempts += 1;
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            ))
+        });
 
-        /// Problem category
-        #[arg(short = 'C', long)]
-        category: Option<String>,
+        assert_eq!(attempts, BUSY_ATTEMPTS);
+        let error = result.unwrap_err();
+        assert!(is_locked_error(&error));
+        assert_eq!(error.to_string(), DATABASE_LOCKED);
+    }
 
-        /// Problem pattern
-        #[arg(short, long)]
-        pattern: Option<String>,
+    #[test]
+    fn renumber_closes_gaps_and_moves_related_rows() {
+        let tracker = tracker();
+        for description in ["A", "B", "C"] {
+            add(&tracker, description);
+        }
+        let mut problem = Problem::new("D");
+        problem.add_tag("graphs");
+        tracker.add_problem(problem).unwrap();
+        tracker
+            .update_solve_time(4, 1, 25.0, Utc::now(), false)
+            .unwrap();
+        tracker.delete_problem(1, false).unwrap();
+        tracker.delete_problem(3, false).unwrap();
 
-        /// Problem difficulty
-        #[arg(short, long)]
-        difficulty: Option<String>,
+        assert_eq!(tracker.renumber().unwrap(), 2);
+        let problems = tracker.get_all_problems().unwrap();
+        let ids: Vec<(Option<i64>, &str)> = problems
+            .iter()
+            .map(|problem| (problem.id, problem.description.as_str()))
+            .collect();
+        assert_eq!(ids, [(Some(1), "B"), (Some(2), "D")]);
+        assert_eq!(problems[1].tags, ["graphs"]);
+        assert_eq!(tracker.get_attempts(2).unwrap().len(), 1);
+        assert_eq!(tracker.renumber().unwrap(), 0);
+    }
 
-        /// Time to solve (first attempt) in minutes
-        #[arg(short, long)]
-        time: Option<i64>,
+    #[test]
+    fn prerequisites_refuse_cycles_and_follow_renumbering() {
+        let tracker = tracker();
+        let gap = add(&tracker, "Gap");
+        let two_sum = add(&tracker, "Two Sum");
+        let three_sum = add(&tracker, "Three Sum");
+        assert!(tracker.add_prerequisite(three_sum, two_sum).unwrap());
+        assert!(!tracker.add_prerequisite(three_sum, two_sum).unwrap());
+        assert!(tracker.add_prerequisite(two_sum, three_sum).is_err());
+        assert!(tracker.add_prerequisite(two_sum, 99).is_err());
 
-        /// Comments about the problem
-        #[arg(short, long)]
-        comments: Option<String>,
+        tracker.delete_problem(gap, false).unwrap();
+        tracker.renumber().unwrap();
+        assert_eq!(tracker.get_prerequisite_edges(false).unwrap(), [(2, 1)]);
+        assert!(tracker.remove_prerequisite(2, 1).unwrap());
+        assert!(tracker.get_prerequisite_edges(true).unwrap().is_empty());
+    }
 
-        /// Should solve again
-        #[arg(short, long)]
-        review: bool,
-    },
-    /// Show a specific problem by ID
-    Show {
-        /// Problem ID
-        id: i64,
-    },
-    /// List all problems
-    List,
-    /// List problems that need review
-    Review,
-    /// List problems by category
-    ByCategory {
-        /// Category name
-        category: String,
-    },
-    /// List problems by pattern
-    ByPattern {
-        /// Pattern name
-        pattern: String,
-    },
-    /// List problems by difficulty
-    ByDifficulty {
-        /// Difficulty level
-        difficulty: String,
-    },
-    /// Search problems by keyword
-    Search {
-        /// Search keyword
-        keyword: String,
-    },
-    /// Update a problem's solve time
-    UpdateTime {
-        /// Problem ID
-        id: i64,
+    #[test]
+    fn edits_with_a_refused_prerequisite_save_nothing() {
+        let tracker = tracker();
+        let two_sum = add(&tracker, "Two Sum");
+        let mut problem = tracker.get_problem(two_sum).unwrap();
+        problem.description = "Renamed".to_string();
 
-        /// Attempt number (1, 2, or 3)
-        attempt: usize,
+        assert!(tracker.edit_problem(&problem, false, &[99], &[]).is_err());
+        assert!(tracker
+            .edit_problem(&problem, false, &[two_sum], &[])
+            .is_err());
+        assert_eq!(tracker.get_problem(two_sum).unwrap().description, "Two Sum");
 
-        /// Time to solve in minutes
-        minutes: i64,
-    },
-    /// Toggle a problem's review flag
-    ToggleReview {
-        /// Problem ID
-        id: i64,
-    },
-    /// Delete a problem
-    Delete {
-        /// Problem ID
-        id: i64,
+        let three_sum = add(&tracker, "Three Sum");
+        let missing = tracker
+            .edit_problem(&problem, false, &[three_sum], &[7])
+            .unwrap();
+        assert_eq!(missing, [7]);
+        assert_eq!(tracker.get_problem(two_sum).unwrap().description, "Renamed");
+        assert_eq!(
+            tracker.get_prerequisite_edges(true).unwrap(),
+            [(two_sum, three_sum)]
+        );
+    }
 
-        /// Skip confirmation
-        #[arg(short, long)]
-        force: bool,
-    },
-}
+    #[test]
+    fn listing_pages_after_sorting() {
+        let tracker = tracker();
+        for description in ["A", "B", "C", "D", "E"] {
+            add(&tracker, description);
+        }
+        let listing = ListingArgs {
+            last_days: None,
+            sort_by: Some(SortField::Id),
+            desc: false,
+            tsv: false,
+            limit: Some(2),
+            offset: 3,
+        };
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let cli = Cli::parse();
-    let db_path = cli.database.to_string_lossy();
-    let tracker = ProblemTracker::new(&db_path)?;
+        let mut problems = tracker.get_all_problems().unwrap();
+        let total = apply_listing_args(&tracker, &mut problems, &listing).unwrap();
 
-    match &cli.command {
-        Commands::Add {
-            description,
-            link,
-            category,
-            pattern,
-            difficulty,
-            time,
-            comments,
-            review,
-        } => {
-            let mut problem = Problem::new(description);
-            problem.link = link.clone();
-            problem.category = category.clone();
-            problem.pattern = pattern.clone();
-            problem.difficulty = difficulty.clone();
-            problem.time_to_solve_1st = *time;
-            problem.comments = comments.clone();
-            problem.should_solve_again = *review;
+        assert_eq!(total, 5);
+        let descriptions: Vec<&str> = problems.iter().map(|p| p.description.as_str()).collect();
+        assert_eq!(descriptions, ["D", "E"]);
+    }
 
-            let id = tracker.add_problem(problem)?;
-            println!("Added problem with ID: {}", id);
+    #[test]
+    fn listing_sorts_by_difficulty_and_reverses() {
+        let tracker = tracker();
+        for (description, difficulty) in [
+            ("A", "Hard"),
+            ("B", "easy"),
+            ("C", "Tricky"),
+            ("D", "Medium"),
+        ] {
+            let mut problem = Problem::new(description);
+            problem.difficulty = Some(difficulty.to_string());
+            tracker.add_problem(problem).unwrap();
         }
-        Commands::Show { id } => match tracker.get_problem(*id) {
-            Ok(problem) => println!("{}", problem),
-            Err(_) => println!("Problem with ID {} not found", id),
-        },
-        Commands::List => {
-            let problems = tracker.get_all_problems()?;
-            if problems.is_empty() {
-                println!("No problems found");
-            } else {
-                println!("All Problems ({})", problems.len());
-                for problem in problems {
-                    println!("\n{}", problem);
-                }
+        let mut listing = ListingArgs {
+            last_days: None,
+            sort_by: Some(SortField::Difficulty),
+            desc: false,
+            tsv: false,
+            limit: None,
+            offset: 0,
+        };
+        let sorted = |listing: &ListingArgs| -> String {
+            let mut problems = tracker.get_all_problems().unwrap();
+            apply_listing_args(&tracker, &mut problems, listing).unwrap();
+            problems.iter().map(|p| p.description.as_str()).collect()
+        };
+
+        assert_eq!(sorted(&listing), "BDAC");
+        listing.desc = true;
+        assert_eq!(sorted(&listing), "CADB");
+    }
+
+    #[test]
+    fn sql_listings_match_the_in_memory_ones() {
+        let tracker = tracker();
+        for (description, difficulty, frequency, rating, attempts) in [
+            ("A", "Hard", Some(2.0), None, 1),
+            ("B", "easy", None, Some(3), 0),
+            ("C", "Tricky", Some(5.0), Some(1), 2),
+            ("D", "Medium", Some(2.0), Some(3), 1),
+            ("E", "Easy", None, None, 0),
+        ] {
+            let mut problem = Problem::new(description);
+            problem.difficulty = Some(difficulty.to_string());
+            problem.frequency = frequency;
+            problem.personal_difficulty = rating;
+            let id = tracker.add_problem(problem).unwrap();
+            for attempt in 1..=attempts {
+                let attempted_at = Utc::now() - Duration::days(attempt as i64 * 10);
+                tracker
+                    .record_attempt(id, attempt, 10.0, attempted_at)
+                    .unwrap();
             }
         }
-        Commands::Review => {
-            let problems = tracker.get_problems_to_review()?;
-            if problems.is_empty() {
-                println!("No problems to review");
-            } else {
-                println!("Problems to Review ({})", problems.len());
-                for problem in problems {
-                    println!("\n{}", problem);
+        let descriptions = |problems: &[Problem]| -> String {
+            problems.iter().map(|p| p.description.as_str()).collect()
+        };
+
+        let sorts = [
+            None,
+            Some(SortField::Id),
+            Some(SortField::Difficulty),
+            Some(SortField::Frequency),
+            Some(SortField::PersonalDifficulty),
+            Some(SortField::Attempts),
+        ];
+        for sort_by in sorts {
+            for desc in [false, true] {
+                for (last_days, limit, offset) in
+                    [(None, None, 0), (Some(15), None, 0), (None, Some(2), 1)]
+                {
+                    let listing = ListingArgs {
+                        last_days,
+                        sort_by,
+                        desc,
+                        limit,
+                        offset,
+                        ..ListingArgs::default()
+                    };
+                    let mut expected = tracker.get_all_problems().unwrap();
+                    let expected_total =
+                        apply_listing_args(&tracker, &mut expected, &listing).unwrap();
+                    let (problems, total) = tracker
+                        .get_listing(filtered_query("1", &FilterArgs::default()), &listing)
+                        .unwrap();
+                    assert_eq!(
+                        (descriptions(&problems), total),
+                        (descriptions(&expected), expected_total),
+                        "{:?} desc={} last_days={:?}",
+                        sort_by,
+                        desc,
+                        last_days
+                    );
+                    assert!(problems
+                        .iter()
+                        .all(|problem| problem.attempt_count.is_some()));
                 }
             }
         }
-        Commands::ByCategory { category } => {
-            let problems = tracker.get_problems_by_category(category)?;
-            if problems.is_empty() {
-                println!("No problems found in category '{}'", category);
-            } else {
-                println!("Problems in Category '{}' ({})", category, problems.len());
-                for problem in problems {
-                    println!("\n{}", problem);
-                }
+    }
+
+    #[test]
+    fn categories_are_matched_case_insensitively_and_merged() {
+        let tracker = tracker();
+        let add_in = |category: &str| {
+            let mut problem = Problem::new("Climbing Stairs");
+            problem.category = Some(category.to_string());
+            tracker.add_problem(problem).unwrap()
+        };
+        add_in("DP");
+        let id = add_in(" dp");
+        add_in("Dynamic Programming");
+
+        assert_eq!(
+            tracker.get_problem(id).unwrap().category.as_deref(),
+            Some("DP")
+        );
+        assert_eq!(
+            tracker.query_problems(category_query("dp")).unwrap().len(),
+            2
+        );
+
+        let categories = tracker.get_categories().unwrap();
+        assert_eq!(categories.len(), 2);
+        let moved = tracker
+            .merge_categories(&categories[1], &categories[0])
+            .unwrap();
+        assert_eq!(moved, 1);
+        assert_eq!(tracker.get_categories().unwrap().len(), 1);
+        assert_eq!(
+            tracker.query_problems(category_query("DP")).unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn categories_sort_by_last_practice() {
+        let tracker = tracker();
+        let now = Utc::now();
+        for (category, days_ago) in [("Arrays", Some(3)), ("Graphs", Some(1)), ("Trees", None)] {
+            let mut problem = Problem::new("Problem");
+            problem.category = Some(category.to_string());
+            let id = tracker.add_problem(problem).unwrap();
+            if let Some(days) = days_ago {
+                tracker
+                    .record_attempt(id, 1, 20.0, now - Duration::days(days))
+                    .unwrap();
             }
         }
-        Commands::ByPattern { pattern } => {
-            let problems = tracker.get_problems_by_pattern(pattern)?;
-            if problems.is_empty() {
-                println!("No problems found with pattern '{}'", pattern);
-            } else {
-                println!("Problems with Pattern '{}' ({})", pattern, problems.len());
-                for problem in problems {
-                    println!("\n{}", problem);
-                }
-            }
+        let names = |sort| -> Vec<String> {
+            tracker
+                .get_categories_by_practice(sort)
+                .unwrap()
+                .into_iter()
+                .map(|(category, _)| category.name)
+                .collect()
+        };
+
+        assert_eq!(names(CategorySort::Recent), ["Graphs", "Arrays", "Trees"]);
+        assert_eq!(
+            names(CategorySort::LeastRecent),
+            ["Trees", "Arrays", "Graphs"]
+        );
+        assert_eq!(names(CategorySort::Name), ["Arrays", "Graphs", "Trees"]);
+    }
+
+    #[test]
+    fn bulk_set_difficulty_skips_locked_problems() {
+        let tracker = tracker();
+        for (description, pattern) in [
+            ("Word Ladder", "bfs"),
+            ("Rotting Oranges", "BFS"),
+            ("Two Sum", "hash map"),
+        ] {
+            let mut problem = Problem::new(description);
+            problem.pattern = Some(pattern.to_string());
+            tracker.add_problem(problem).unwrap();
         }
-        Commands::ByDifficulty { difficulty } => {
-            let problems = tracker.get_problems_by_difficulty(difficulty)?;
-            if problems.is_empty() {
-                println!("No problems found with difficulty '{}'", difficulty);
-            } else {
-                println!(
-                    "Problems with Difficulty '{}' ({})",
-                    difficulty,
-                    problems.len()
-                );
-                for problem in problems {
-                    println!("\n{}", problem);
-                }
-            }
+        tracker.set_locked(2, true).unwrap();
+        let bfs = FilterArgs {
+            category: None,
+            pattern: Some("bfs".to_string()),
+            difficulty: None,
+            source: None,
+            min_time: None,
+            max_time: None,
+        };
+
+        assert_eq!(
+            tracker.bulk_set_difficulty(&bfs, "Hard", false).unwrap(),
+            (1, 1)
+        );
+        assert_eq!(
+            tracker.bulk_set_difficulty(&bfs, "Hard", true).unwrap(),
+            (1, 0)
+        );
+        let difficulties: Vec<Option<String>> = tracker
+            .get_all_problems()
+            .unwrap()
+            .into_iter()
+            .map(|problem| problem.difficulty)
+            .collect();
+        assert_eq!(
+            difficulties,
+            [Some("Hard".to_string()), Some("Hard".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn pull_random_skips_then_starts_a_session() {
+        use rand::SeedableRng;
+
+        let tracker = tracker();
+        for description in ["Two Sum", "Three Sum", "Four Sum"] {
+            add(&tracker, description);
         }
-        Commands::Search { keyword } => {
-            let problems = tracker.search_problems(keyword)?;
-            if problems.is_empty() {
-                println!("No problems found matching '{}'", keyword);
-            } else {
-                println!("Problems matching '{}' ({})", keyword, problems.len());
-                for problem in problems {
-                    println!("\n{}", problem);
-                }
-            }
+        let problems = tracker.get_all_problems().unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        let mut out = Vec::new();
+        pull_random(
+            &tracker,
+            &problems,
+            None,
+            &mut rng,
+            &mut "n\nn\nn\n".as_bytes(),
+            &mut out,
+        )
+        .unwrap();
+        let shown = String::from_utf8(out).unwrap();
+        // Each problem comes up once before running out
+        for description in ["Two Sum", "Three Sum", "Four Sum"] {
+            assert_eq!(shown.matches(description).count(), 1);
         }
-        Commands::UpdateTime {
-            id,
-            attempt,
-            minutes,
-        } => {
-            if *attempt < 1 || *attempt > 3 {
-                println!("Attempt must be 1, 2, or 3");
-                return Ok(());
-            }
+        assert!(shown.ends_with("No more problems to pick from\n"));
 
-            match tracker.update_solve_time(*id, *attempt, *minutes) {
-                Ok(_) => println!(
-                    "Updated problem #{} with attempt {} time: {} minutes",
-                    id, attempt, minutes
-                ),
-                Err(_) => println!("Problem with ID {} not found", id),
-            }
+        let mut out = Vec::new();
+        pull_random(
+            &tracker,
+            &problems,
+            None,
+            &mut rng,
+            &mut "?\nn\ns\n".as_bytes(),
+            &mut out,
+        )
+        .unwrap();
+        let started: i64 = tracker
+            .conn
+            .query_row("SELECT problem_id FROM sessions", [], |row| row.get(0))
+            .unwrap();
+        let shown = String::from_utf8(out).unwrap();
+        assert!(shown.contains(&format!("Started problem #{}", started)));
+        assert_eq!(shown.matches("Problem #").count(), 2);
+    }
+
+    #[test]
+    fn sessions_time_whole_minutes_rounded_up() {
+        let started = Utc::now();
+        assert_eq!(elapsed_minutes(started, started), 1);
+        assert_eq!(elapsed_minutes(started, started + Duration::seconds(61)), 2);
+        assert_eq!(
+            elapsed_minutes(started, started + Duration::minutes(25)),
+            25
+        );
+
+        let tracker = tracker();
+        let id = add(&tracker, "Two Sum");
+        tracker.start_session(id).unwrap();
+        assert_eq!(tracker.get_session().unwrap().map(|(id, _)| id), Some(id));
+        tracker.clear_session().unwrap();
+        assert_eq!(tracker.get_session().unwrap(), None);
+    }
+
+    #[test]
+    fn templates_keep_problems_but_not_progress() {
+        let tracker = tracker();
+        let mut problem = Problem::new("Two Sum");
+        problem.set_link(parse_link("https://leetcode.com/problems/two-sum/").unwrap());
+        problem.pattern = Some("hash map".to_string());
+        problem.comments = Some("private".to_string());
+        problem.should_solve_again = true;
+        problem.add_tag("warmup");
+        let id = tracker.add_problem(problem).unwrap();
+        tracker
+            .update_solve_time(id, 1, 12.0, Utc::now(), false)
+            .unwrap();
+
+        let pack = ProblemTracker::new(MEMORY_DATABASE, false, None).unwrap();
+        assert_eq!(tracker.export_template(&pack).unwrap(), 1);
+
+        let copied = &pack.get_all_problems().unwrap()[0];
+        assert_eq!(copied.pattern.as_deref(), Some("hash map"));
+        assert_eq!(
+            copied.link.as_deref(),
+            Some("https://leetcode.com/problems/two-sum/")
+        );
+        assert_eq!(copied.tags, ["warmup"]);
+        assert_eq!(copied.time_to_solve_1st, None);
+        assert_eq!(copied.comments, None);
+        assert!(!copied.should_solve_again);
+        assert!(pack.get_attempts(copied.id.unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_count_matches_the_results() {
+        let tracker = tracker();
+        for description in ["Word Ladder", "Word Search", "Two Sum"] {
+            add(&tracker, description);
         }
-        Commands::ToggleReview { id } => match tracker.toggle_review_flag(*id) {
-            Ok(_) => match tracker.get_problem(*id) {
-                Ok(problem) => println!(
-                    "Problem #{} review flag set to: {}",
-                    id,
-                    if problem.should_solve_again {
-                        "Yes"
-                    } else {
-                        "No"
-                    }
-                ),
-                Err(_) => println!("Problem with ID {} not found", id),
-            },
-            Err(_) => println!("Problem with ID {} not found", id),
-        },
-        Commands::Delete { id, force } => {
-            if !*force {
-                println!("Are you sure you want to delete problem #{}? [y/N]", id);
-                let mut input = String::new();
-                std::io::stdin().read_line(&mut input)?;
-                if !input.trim().eq_ignore_ascii_case("y") {
-                    println!("Deletion cancelled");
-                    return Ok(());
-                }
-            }
 
-            match tracker.delete_problem(*id) {
-                Ok(_) => println!("Deleted problem #{}", id),
-                Err(_) => println!("Problem with ID {} not found", id),
-            }
+        for (words, mode) in [
+            (keywords(&["word"]), SearchMode::And),
+            (keywords(&["word", "sum"]), SearchMode::Or),
+            (keywords(&["word", "sum"]), SearchMode::And),
+        ] {
+            assert_eq!(
+                tracker.count_search_matches(&words, mode).unwrap(),
+                tracker.search_problems(&words, mode).unwrap().len()
+            );
         }
     }
 
-    Ok(())
+    #[test]
+    fn named_lists_collect_problems_once() {
+        let tracker = tracker();
+        let first = add(&tracker, "Two Sum");
+        let second = add(&tracker, "Three Sum");
+        add(&tracker, "Four Sum");
+
+        assert_eq!(
+            tracker.add_to_list("Blind 75", &[first, second]).unwrap(),
+            2
+        );
+        // Names ignore case and repeats are no-ops
+        assert_eq!(tracker.add_to_list("blind 75", &[second]).unwrap(), 0);
+
+        let list = tracker.get_list("BLIND 75").unwrap().unwrap();
+        let ids: Vec<Option<i64>> = list.iter().map(|problem| problem.id).collect();
+        assert_eq!(ids, [Some(first), Some(second)]);
+        assert!(tracker.get_list("Grind 169").unwrap().is_none());
+
+        tracker.delete_problem(first, false).unwrap();
+        assert_eq!(tracker.get_list("Blind 75").unwrap().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn focus_list_is_separate_from_review() {
+        let tracker = tracker();
+        let focused = add(&tracker, "Two Sum");
+        let mut review = Problem::new("Three Sum");
+        review.should_solve_again = true;
+        tracker.add_problem(review).unwrap();
+
+        tracker.set_focus(focused, true).unwrap();
+        let list = tracker.query_problems(focus_query()).unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].description, "Two Sum");
+        assert_eq!(tracker.get_problems_to_review().unwrap().len(), 1);
+
+        tracker.set_focus(focused, false).unwrap();
+        assert!(tracker.query_problems(focus_query()).unwrap().is_empty());
+        assert!(tracker.set_focus(99, true).is_err());
+    }
+
+    #[test]
+    fn since_id_leaves_out_earlier_problems() {
+        let tracker = tracker();
+        for description in ["Two Sum", "Three Sum", "Four Sum"] {
+            add(&tracker, description);
+        }
+        let filters = FilterArgs {
+            category: None,
+            pattern: None,
+            difficulty: None,
+            source: None,
+            min_time: None,
+            max_time: None,
+        };
+
+        let ids: Vec<Option<i64>> = tracker
+            .get_matching_since(1, &filters)
+            .unwrap()
+            .iter()
+            .map(|problem| problem.id)
+            .collect();
+        assert_eq!(ids, [Some(2), Some(3)]);
+        assert!(tracker.get_matching_since(3, &filters).unwrap().is_empty());
+    }
+
+    #[test]
+    fn tag_search_tags_each_match_once() {
+        let tracker = tracker();
+        for description in ["Word Ladder", "Word Search", "Two Sum"] {
+            add(&tracker, description);
+        }
+        tracker.set_locked(2, true).unwrap();
+        let keywords = keywords(&["word"]);
+
+        assert_eq!(
+            tracker
+                .tag_search_results(&keywords, SearchMode::And, "grid", false, false)
+                .unwrap(),
+            (1, 1)
+        );
+        // Already tagged problems don't count again
+        assert_eq!(
+            tracker
+                .tag_search_results(&keywords, SearchMode::And, "grid", false, true)
+                .unwrap(),
+            (1, 0)
+        );
+        assert_eq!(tracker.get_tags(2).unwrap(), ["grid"]);
+        assert!(tracker.get_tags(3).unwrap().is_empty());
+
+        assert_eq!(
+            tracker
+                .tag_search_results(&keywords, SearchMode::And, "grid", true, true)
+                .unwrap(),
+            (2, 0)
+        );
+        assert!(tracker.get_tags(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn meta_lists_distinct_labels() {
+        let tracker = tracker();
+        for (description, pattern, tag) in [
+            ("Two Sum", "hash map", "easy-win"),
+            ("Group Anagrams", "Hash Map", "strings"),
+            ("Three Sum", "two pointers", "easy-win"),
+        ] {
+            let mut problem = Problem::new(description);
+            problem.pattern = Some(pattern.to_string());
+            problem.category = Some("Arrays".to_string());
+            problem.add_tag(tag);
+            tracker.add_problem(problem).unwrap();
+        }
+
+        let meta = tracker.get_meta().unwrap();
+        assert_eq!(meta.categories, ["Arrays"]);
+        assert_eq!(meta.patterns, ["hash map", "Hash Map", "two pointers"]);
+        assert_eq!(meta.tags, ["easy-win", "strings"]);
+        assert!(meta.difficulties.is_empty());
+    }
+
+    #[test]
+    fn extremes_rank_by_first_or_best_time_and_skip_unsolved() {
+        let tracker = tracker();
+        for (description, times) in [
+            ("Two Sum", [Some(10.0), Some(5.0), None]),
+            ("Word Ladder", [Some(60.0), Some(40.0), Some(30.0)]),
+            ("Three Sum", [Some(25.0), None, None]),
+            ("Unsolved", [None, None, None]),
+        ] {
+            let mut problem = Problem::new(description);
+            [
+                problem.time_to_solve_1st,
+                problem.time_to_solve_2nd,
+                problem.time_to_solve_3rd,
+            ] = times;
+            tracker.add_problem(problem).unwrap();
+        }
+        let ranked = |basis, slowest| -> Vec<(f64, String)> {
+            tracker
+                .get_extremes(basis, slowest, 2)
+                .unwrap()
+                .into_iter()
+                .map(|(minutes, problem)| (minutes, problem.description))
+                .collect()
+        };
+
+        assert_eq!(
+            ranked(TimeBasis::First, true),
+            [
+                (60.0, "Word Ladder".to_string()),
+                (25.0, "Three Sum".to_string())
+            ]
+        );
+        assert_eq!(
+            ranked(TimeBasis::Best, false),
+            [
+                (5.0, "Two Sum".to_string()),
+                (25.0, "Three Sum".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_keep_order_and_put_missing_values_last() {
+        let problem = |description, pattern: Option<&str>| {
+            let mut problem = Problem::new(description);
+            problem.pattern = pattern.map(str::to_string);
+            problem
+        };
+        let problems = [
+            problem("Word Ladder", Some("BFS")),
+            problem("Two Sum", None),
+            problem("Three Sum", Some("two pointers")),
+            problem("Rotting Oranges", Some("bfs")),
+        ];
+
+        let groups: Vec<(&str, Vec<&str>)> = group_problems(&problems, GroupField::Pattern)
+            .into_iter()
+            .map(|(name, group)| {
+                let descriptions = group.iter().map(|p| p.description.as_str()).collect();
+                (name, descriptions)
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("BFS", vec!["Word Ladder", "Rotting Oranges"]),
+                ("two pointers", vec!["Three Sum"]),
+                (UNGROUPED, vec!["Two Sum"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn side_by_side_aligns_and_stars_differences() {
+        let rows = [
+            ("pattern", "dfs".to_string(), "bfs".to_string()),
+            ("difficulty", "Medium".to_string(), "Medium".to_string()),
+            (
+                "comments",
+                "".to_string(),
+                "a very long comment".to_string(),
+            ),
+        ];
+
+        let lines = side_by_side(("#1", "#2"), &rows, 8);
+
+        assert_eq!(
+            lines,
+            [
+                "              #1      #2",
+                "* pattern     dfs     bfs",
+                "  difficulty  Medium  Medium",
+                "* comments    -       a very …",
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_pad_columns_and_cut_the_fill_column() {
+        let rows = [
+            vec!["1".to_string(), "Two Sum".to_string(), "Easy".to_string()],
+            vec![
+                "12".to_string(),
+                "Longest Palindromic Substring".to_string(),
+                "-".to_string(),
+            ],
+        ];
+
+        let lines = format_table(&["id", "description", "difficulty"], &rows, Some(1), 30);
+
+        assert_eq!(
+            lines,
+            [
+                "id  description     difficulty",
+                "--  --------------  ----------",
+                "1   Two Sum         Easy",
+                "12  Longest Palin…  -",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_description_truncates_only_when_asked() {
+        assert_eq!(check_description("Two Sum", 5, true), "Two S");
+        assert_eq!(check_description("Two Sum", 5, false), "Two Sum");
+        assert_eq!(check_description("Two Sum", 7, true), "Two Sum");
+    }
+
+    #[test]
+    fn similar_ranks_by_shared_tags() {
+        let tracker = tracker();
+        let tagged = |description: &str, tags: &[&str]| {
+            let mut problem = Problem::new(description);
+            for tag in tags {
+                problem.add_tag(tag);
+            }
+            tracker.add_problem(problem).unwrap()
+        };
+        let id = tagged("Number of Islands", &["graphs", "bfs", "grid"]);
+        let one = tagged("Word Ladder", &["graphs", "bfs"]);
+        let two = tagged("Rotting Oranges", &["bfs", "grid", "graphs"]);
+        tagged("Two Sum", &["arrays"]);
+
+        assert_eq!(
+            tracker.get_problem(two).unwrap().tags,
+            ["bfs", "graphs", "grid"]
+        );
+        let similar: Vec<(Option<i64>, usize)> = tracker
+            .get_similar(id, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(problem, shared)| (problem.id, shared))
+            .collect();
+        assert_eq!(similar, [(Some(two), 3), (Some(one), 2)]);
+
+        tracker.delete_problem(two, false).unwrap();
+        assert_eq!(tracker.get_similar(id, 10).unwrap().len(), 1);
+    }
 }
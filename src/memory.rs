@@ -0,0 +1,88 @@
+use crate::Problem;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// How hard the problem was for the user, from 0 to 1: their own rating when
+/// set, otherwise the site's difficulty
+fn hardness(problem: &Problem) -> f64 {
+    if let Some(rating) = problem.personal_difficulty {
+        return (rating - 1) as f64 / 4.0;
+    }
+
+    match problem
+        .difficulty
+        .as_deref()
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        Some("easy") => 0.25,
+        Some("hard") => 0.75,
+        _ => 0.5,
+    }
+}
+
+/// Half-life scaled from 1.5x for the easiest problems down to 0.5x for the
+/// hardest, since hard solutions fade faster
+fn effective_half_life(problem: &Problem, half_life_days: f64) -> f64 {
+    half_life_days * (1.5 - hardness(problem))
+}
+
+/// Exponential forgetting curve: the chance of still remembering a solution
+/// after `days`, halving every `half_life_days`
+pub fn recall_probability(days: f64, half_life_days: f64) -> f64 {
+    0.5_f64.powf(days.max(0.0) / half_life_days)
+}
+
+/// Attempted problems with their estimated recall, most likely forgotten first
+pub fn most_forgotten(
+    problems: Vec<Problem>,
+    last_attempted: &HashMap<i64, DateTime<Utc>>,
+    half_life_days: f64,
+    now: DateTime<Utc>,
+) -> Vec<(f64, Problem)> {
+    let mut ranked: Vec<(f64, Problem)> = problems
+        .into_iter()
+        .filter_map(|problem| {
+            let last = last_attempted.get(&problem.id?)?;
+            let days = (now - *last).num_seconds() as f64 / 86_400.0;
+            let recall = recall_probability(days, effective_half_life(&problem, half_life_days));
+            Some((recall, problem))
+        })
+        .collect();
+
+    ranked.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn recall_halves_every_half_life() {
+        assert_eq!(recall_probability(0.0, 10.0), 1.0);
+        assert!((recall_probability(10.0, 10.0) - 0.5).abs() < 1e-9);
+        assert!((recall_probability(20.0, 10.0) - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn harder_problems_are_forgotten_first() {
+        let now = Utc::now();
+        let mut easy = Problem::new("Easy for me");
+        easy.id = Some(1);
+        easy.personal_difficulty = Some(1);
+        let mut hard = Problem::new("Hard for me");
+        hard.id = Some(2);
+        hard.personal_difficulty = Some(5);
+        let never = Problem::new("Never attempted");
+        let last_attempted =
+            HashMap::from([(1, now - Duration::days(10)), (2, now - Duration::days(10))]);
+
+        let ranked = most_forgotten(vec![easy, hard, never], &last_attempted, 14.0, now);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].1.description, "Hard for me");
+        assert!(ranked[0].0 < ranked[1].0);
+    }
+}
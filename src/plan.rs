@@ -0,0 +1,263 @@
+use crate::Problem;
+use chrono::{Datelike, NaiveDate};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Picks up to `days * per_day` problems and splits them into days.
+///
+/// Review-flagged problems come first, then the most frequently asked ones;
+/// ties are broken by a shuffle seeded with `seed`, so the same seed and data
+/// always produce the same plan. Picks rotate through patterns round-robin
+/// so no single pattern dominates a day.
+pub fn build_plan(
+    mut problems: Vec<Problem>,
+    days: usize,
+    per_day: usize,
+    seed: u64,
+) -> Vec<Vec<Problem>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    problems.shuffle(&mut rng);
+    problems.sort_by(|a, b| {
+        b.should_solve_again
+            .cmp(&a.should_solve_again)
+            .then_with(|| {
+                b.frequency
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.frequency.unwrap_or(0.0))
+            })
+    });
+
+    // Per-pattern queues, visited in order of each pattern's best problem
+    let mut pattern_order: Vec<Option<String>> = Vec::new();
+    let mut queues: HashMap<Option<String>, VecDeque<Problem>> = HashMap::new();
+    for problem in problems {
+        let pattern = problem.pattern.as_ref().map(|p| p.to_lowercase());
+        if !queues.contains_key(&pattern) {
+            pattern_order.push(pattern.clone());
+        }
+        queues.entry(pattern).or_default().push_back(problem);
+    }
+
+    let total = days * per_day;
+    let mut picked = Vec::new();
+    while picked.len() < total {
+        let mut progressed = false;
+        for pattern in &pattern_order {
+            if picked.len() == total {
+                break;
+            }
+            if let Some(problem) = queues.get_mut(pattern).and_then(VecDeque::pop_front) {
+                picked.push(problem);
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    let mut schedule = Vec::new();
+    let mut picked = picked.into_iter();
+    for _ in 0..days {
+        let day: Vec<Problem> = picked.by_ref().take(per_day).collect();
+        if day.is_empty() {
+            break;
+        }
+        schedule.push(day);
+    }
+    schedule
+}
+
+/// Today's problem: the first in an order shuffled by `date` that is still
+/// unattempted or flagged for review, else the first overall. The shuffle
+/// covers every problem by ID, so solving or flagging others doesn't move
+/// the pick. Attempt counts must be annotated.
+pub fn daily_pick(problems: &[Problem], date: NaiveDate) -> Option<&Problem> {
+    let mut order: Vec<&Problem> = problems.iter().collect();
+    order.sort_by_key(|problem| problem.id);
+    // e.g. 20261015
+    let seed = date.year() as u64 * 10_000 + date.month() as u64 * 100 + date.day() as u64;
+    order.shuffle(&mut StdRng::seed_from_u64(seed));
+
+    let is_fresh = |problem: &&&Problem| {
+        problem.should_solve_again
+            || (problem.time_to_solve_1st.is_none()
+                && problem.time_to_solve_2nd.is_none()
+                && problem.time_to_solve_3rd.is_none()
+                && problem.attempt_count.unwrap_or(0) == 0)
+    };
+    order
+        .iter()
+        .find(is_fresh)
+        .or_else(|| order.first())
+        .copied()
+}
+
+/// Any problem not among the `skipped` IDs, or `None` once all have been
+pub fn random_pick<'a>(
+    problems: &'a [Problem],
+    skipped: &HashSet<i64>,
+    rng: &mut impl Rng,
+) -> Option<&'a Problem> {
+    let pool: Vec<&Problem> = problems
+        .iter()
+        .filter(|problem| !problem.id.is_some_and(|id| skipped.contains(&id)))
+        .collect();
+    pool.choose(rng).copied()
+}
+
+/// Order problems are unlocked in within a pattern; other difficulties
+/// come after Hard
+const DIFFICULTY_TIERS: [&str; 3] = ["Easy", "Medium", "Hard"];
+
+fn tier(problem: &Problem) -> usize {
+    problem
+        .difficulty
+        .as_deref()
+        .and_then(|difficulty| {
+            DIFFICULTY_TIERS
+                .iter()
+                .position(|tier| tier.eq_ignore_ascii_case(difficulty.trim()))
+        })
+        .unwrap_or(DIFFICULTY_TIERS.len())
+}
+
+/// The problem to do next in a pattern, and how many in its tier are still
+/// unsolved, counting it
+pub struct NextUp<'a> {
+    pub problem: &'a Problem,
+    pub left_in_tier: usize,
+}
+
+/// The easiest problem left in one pattern's `problems`: nothing from a
+/// harder tier until every problem in the easier ones is solved. Within a
+/// tier, unattempted problems come first, then ones attempted but not yet
+/// solved, each in ID order. `None` once everything is solved. Attempt
+/// counts and first times must be annotated.
+pub fn next_up(problems: &[Problem]) -> Option<NextUp<'_>> {
+    let unsolved = problems
+        .iter()
+        .filter(|problem| problem.first_time.is_none());
+    let current = unsolved.clone().map(tier).min()?;
+    let mut candidates: Vec<&Problem> = unsolved
+        .filter(|problem| tier(problem) == current)
+        .collect();
+    let left_in_tier = candidates.len();
+
+    candidates.sort_by_key(|problem| (problem.attempt_count.unwrap_or(0) > 0, problem.id));
+    Some(NextUp {
+        problem: candidates[0],
+        left_in_tier,
+    })
+}
+
+fn summary(problem: &Problem) -> (i64, &str, &str, &str) {
+    (
+        problem.id.unwrap_or(0),
+        problem.description.as_str(),
+        problem.difficulty.as_deref().unwrap_or("Unknown"),
+        problem.pattern.as_deref().unwrap_or("-"),
+    )
+}
+
+pub fn print_plain(schedule: &[Vec<Problem>]) {
+    for (day, problems) in schedule.iter().enumerate() {
+        println!("Day {}", day + 1);
+        for problem in problems {
+            let (id, description, difficulty, pattern) = summary(problem);
+            println!("  #{}: {} ({}) - {}", id, description, difficulty, pattern);
+        }
+        println!();
+    }
+}
+
+pub fn print_markdown(schedule: &[Vec<Problem>]) {
+    println!("| Day | ID | Problem | Difficulty | Pattern |");
+    println!("|-----|----|---------|------------|---------|");
+    for (day, problems) in schedule.iter().enumerate() {
+        for problem in problems {
+            let (id, description, difficulty, pattern) = summary(problem);
+            println!(
+                "| {} | {} | {} | {} | {} |",
+                day + 1,
+                id,
+                description.replace('|', "\\|"),
+                difficulty,
+                pattern.replace('|', "\\|")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems() -> Vec<Problem> {
+        (1..=20)
+            .map(|id| {
+                let mut problem = Problem::new(&format!("Problem {}", id));
+                problem.id = Some(id);
+                // Only the even ones are still unattempted
+                if id % 2 == 1 {
+                    problem.time_to_solve_1st = Some(10.0);
+                }
+                problem
+            })
+            .collect()
+    }
+
+    #[test]
+    fn next_up_clears_easier_tiers_first() {
+        let problem = |id, difficulty: &str, attempts, solved: bool| {
+            let mut problem = Problem::new(&format!("Problem {}", id));
+            problem.id = Some(id);
+            problem.difficulty = Some(difficulty.to_string());
+            problem.attempt_count = Some(attempts);
+            problem.first_time = solved.then_some(20.0);
+            problem
+        };
+
+        let mut problems = vec![
+            problem(1, "Medium", 0, false),
+            problem(2, "easy", 1, false),
+            problem(3, "Easy", 0, false),
+            problem(4, "Easy", 1, true),
+        ];
+        let next = next_up(&problems).unwrap();
+        // The untouched Easy goes before the one given up on
+        assert_eq!(next.problem.id, Some(3));
+        assert_eq!(next.left_in_tier, 2);
+
+        problems[1].first_time = Some(30.0);
+        problems[2].first_time = Some(15.0);
+        assert_eq!(next_up(&problems).unwrap().problem.id, Some(1));
+
+        problems[0].first_time = Some(25.0);
+        assert!(next_up(&problems).is_none());
+    }
+
+    #[test]
+    fn daily_pick_is_stable_within_a_day_and_prefers_fresh_problems() {
+        let mut problems = problems();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+
+        let today = daily_pick(&problems, day(15)).unwrap().id;
+        assert_eq!(daily_pick(&problems, day(15)).unwrap().id, today);
+        for d in 1..=28 {
+            let id = daily_pick(&problems, day(d)).unwrap().id.unwrap();
+            assert_eq!(id % 2, 0);
+        }
+        assert!(daily_pick(&[], day(15)).is_none());
+
+        // Solving other fresh problems leaves today's pick alone
+        for problem in &mut problems {
+            if problem.id != today && problem.id.unwrap() <= 10 {
+                problem.time_to_solve_1st = Some(10.0);
+            }
+        }
+        assert_eq!(daily_pick(&problems, day(15)).unwrap().id, today);
+    }
+}
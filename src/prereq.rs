@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A prerequisite relation as `(problem_id, prereq_id)`: the prerequisite is
+/// meant to be solved before the problem
+pub type Edge = (i64, i64);
+
+/// Each problem's direct prerequisites, in ID order
+fn prereqs_by_problem(edges: &[Edge]) -> BTreeMap<i64, BTreeSet<i64>> {
+    let mut prereqs: BTreeMap<i64, BTreeSet<i64>> = BTreeMap::new();
+    for &(problem, prereq) in edges {
+        prereqs.entry(problem).or_default().insert(prereq);
+    }
+    prereqs
+}
+
+/// Whether making `prereq` a prerequisite of `problem` would close a loop:
+/// `prereq` is `problem` itself, or already needs it, directly or not
+pub fn creates_cycle(edges: &[Edge], problem: i64, prereq: i64) -> bool {
+    let prereqs = prereqs_by_problem(edges);
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![prereq];
+    while let Some(id) = pending.pop() {
+        if id == problem {
+            return true;
+        }
+        if seen.insert(id) {
+            pending.extend(prereqs.get(&id).into_iter().flatten());
+        }
+    }
+    false
+}
+
+/// The prerequisites of `id` as a tree, depth first: each entry is a
+/// problem and how deep it sits under `id`, starting at 1. A problem
+/// reached a second time is listed again but not expanded.
+pub fn chain(edges: &[Edge], id: i64) -> Vec<(usize, i64)> {
+    fn walk(
+        prereqs: &BTreeMap<i64, BTreeSet<i64>>,
+        id: i64,
+        depth: usize,
+        expanded: &mut BTreeSet<i64>,
+        out: &mut Vec<(usize, i64)>,
+    ) {
+        if !expanded.insert(id) {
+            return;
+        }
+        for &prereq in prereqs.get(&id).into_iter().flatten() {
+            out.push((depth, prereq));
+            walk(prereqs, prereq, depth + 1, expanded, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(
+        &prereqs_by_problem(edges),
+        id,
+        1,
+        &mut BTreeSet::new(),
+        &mut out,
+    );
+    out
+}
+
+/// Problems that list `id` as a direct prerequisite, in ID order
+pub fn dependents(edges: &[Edge], id: i64) -> Vec<i64> {
+    let dependents: BTreeSet<i64> = edges
+        .iter()
+        .filter(|(_, prereq)| *prereq == id)
+        .map(|(problem, _)| *problem)
+        .collect();
+    dependents.into_iter().collect()
+}
+
+/// `ids` in an order where every problem comes after its prerequisites,
+/// lowest ID first among those ready at once (Kahn's algorithm). Edges
+/// naming problems outside `ids` are ignored. Fails with the problems left
+/// unordered when prerequisites loop, which includes every problem on a
+/// cycle and everything waiting on one.
+pub fn learning_order(ids: &[i64], edges: &[Edge]) -> Result<Vec<i64>, Vec<i64>> {
+    let known: BTreeSet<i64> = ids.iter().copied().collect();
+    let mut waiting_on: BTreeMap<i64, usize> = known.iter().map(|&id| (id, 0)).collect();
+    let mut unlocks: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    let edges: BTreeSet<Edge> = edges
+        .iter()
+        .copied()
+        .filter(|(problem, prereq)| known.contains(problem) && known.contains(prereq))
+        .collect();
+    for (problem, prereq) in edges {
+        *waiting_on.entry(problem).or_default() += 1;
+        unlocks.entry(prereq).or_default().push(problem);
+    }
+
+    let mut ready: BTreeSet<i64> = waiting_on
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(id, _)| *id)
+        .collect();
+    let mut order = Vec::new();
+    while let Some(id) = ready.pop_first() {
+        order.push(id);
+        for dependent in unlocks.get(&id).into_iter().flatten() {
+            let count = waiting_on.entry(*dependent).or_default();
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(*dependent);
+            }
+        }
+    }
+
+    if order.len() == known.len() {
+        Ok(order)
+    } else {
+        Err(waiting_on
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(id, _)| id)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_are_caught_through_any_path() {
+        // 3 needs 2, which needs 1
+        let edges = [(3, 2), (2, 1)];
+        assert!(creates_cycle(&edges, 1, 3));
+        assert!(creates_cycle(&edges, 1, 2));
+        assert!(creates_cycle(&edges, 4, 4));
+        assert!(!creates_cycle(&edges, 3, 1));
+        assert!(!creates_cycle(&edges, 4, 3));
+    }
+
+    #[test]
+    fn chains_list_each_problem_under_its_dependent() {
+        // 4 needs 2 and 3, which both need 1
+        let edges = [(4, 2), (4, 3), (2, 1), (3, 1)];
+        assert_eq!(chain(&edges, 4), [(1, 2), (2, 1), (1, 3), (2, 1)]);
+        assert_eq!(chain(&edges, 1), []);
+        assert_eq!(dependents(&edges, 1), [2, 3]);
+    }
+
+    #[test]
+    fn learning_order_puts_prerequisites_first() {
+        // 1 needs 3, 3 needs 4; 2 stands alone
+        let edges = [(1, 3), (3, 4), (9, 4)];
+        assert_eq!(learning_order(&[1, 2, 3, 4], &edges), Ok(vec![2, 4, 3, 1]));
+
+        // 1 and 2 need each other, and 3 waits on 2
+        let looped = [(1, 2), (2, 1), (3, 2)];
+        assert_eq!(learning_order(&[1, 2, 3, 4], &looped), Err(vec![1, 2, 3]));
+    }
+}
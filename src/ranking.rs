@@ -0,0 +1,166 @@
+use crate::Problem;
+use chrono::{DateTime, Utc};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+/// Days since the last attempt at which staleness stops growing
+const STALE_DAYS: f64 = 30.0;
+
+/// Added to every urgency when sampling, so even a problem with none left
+/// can still come up
+const MIN_PICK_WEIGHT: f64 = 0.1;
+
+/// How much each signal counts towards a problem's urgency. Every signal is
+/// scaled to 0..1 first, so the weights compare directly.
+pub struct Weights {
+    /// Days since the last attempt; never attempted counts as fully stale
+    pub staleness: f64,
+    /// Flagged for review
+    pub review: f64,
+    /// Personal difficulty rating, with unrated problems in the middle
+    pub difficulty: f64,
+    /// On the focus list
+    pub priority: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            staleness: 1.0,
+            review: 2.0,
+            difficulty: 1.0,
+            priority: 1.5,
+        }
+    }
+}
+
+/// How urgently the problem wants practice: the weighted sum of its signals
+pub fn urgency(
+    problem: &Problem,
+    last_attempted: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    weights: &Weights,
+) -> f64 {
+    let staleness = match last_attempted {
+        Some(last) => ((now - last).num_days() as f64 / STALE_DAYS).clamp(0.0, 1.0),
+        None => 1.0,
+    };
+    let difficulty = match problem.personal_difficulty {
+        Some(rating) => (rating.clamp(1, 5) - 1) as f64 / 4.0,
+        None => 0.5,
+    };
+    let flag = |set: bool| if set { 1.0 } else { 0.0 };
+
+    weights.staleness * staleness
+        + weights.review * flag(problem.should_solve_again)
+        + weights.difficulty * difficulty
+        + weights.priority * flag(problem.focused)
+}
+
+/// Problems by urgency, most urgent first; ties keep ID order
+pub fn rank(
+    problems: Vec<Problem>,
+    last_attempted: &HashMap<i64, DateTime<Utc>>,
+    now: DateTime<Utc>,
+    weights: &Weights,
+) -> Vec<(f64, Problem)> {
+    let mut ranked: Vec<(f64, Problem)> = problems
+        .into_iter()
+        .map(|problem| {
+            let last = problem.id.and_then(|id| last_attempted.get(&id)).copied();
+            (urgency(&problem, last, now, weights), problem)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked
+}
+
+/// A problem not among the `skipped` IDs, picked with odds proportional to
+/// its urgency in `urgencies` (same order as `problems`), or `None` once all
+/// have been skipped
+pub fn weighted_pick<'a>(
+    problems: &'a [Problem],
+    urgencies: &[f64],
+    skipped: &HashSet<i64>,
+    rng: &mut impl Rng,
+) -> Option<&'a Problem> {
+    let (pool, weights): (Vec<&Problem>, Vec<f64>) = problems
+        .iter()
+        .zip(urgencies)
+        .filter(|(problem, _)| !problem.id.is_some_and(|id| skipped.contains(&id)))
+        .map(|(problem, urgency)| (problem, urgency.max(0.0) + MIN_PICK_WEIGHT))
+        .unzip();
+    let index = WeightedIndex::new(&weights).ok()?;
+    Some(pool[index.sample(rng)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn combines_weighted_signals() {
+        let now = Utc::now();
+        let weights = Weights::default();
+        let mut problem = Problem::new("Two Sum");
+        problem.personal_difficulty = Some(5);
+
+        // Solved yesterday: a day's staleness plus the hardest rating
+        let recent = urgency(&problem, Some(now - Duration::days(1)), now, &weights);
+        assert!((recent - (1.0 / 30.0 + 1.0)).abs() < 1e-9);
+
+        problem.should_solve_again = true;
+        problem.focused = true;
+        problem.personal_difficulty = None;
+        assert_eq!(
+            urgency(&problem, None, now, &weights),
+            1.0 + 2.0 + 0.5 + 1.5
+        );
+    }
+
+    #[test]
+    fn ranks_most_urgent_first() {
+        let now = Utc::now();
+        let mut fresh = Problem::new("Fresh");
+        fresh.id = Some(1);
+        let mut flagged = Problem::new("Flagged");
+        flagged.id = Some(2);
+        flagged.should_solve_again = true;
+        let last_attempted = HashMap::from([(1, now), (2, now)]);
+
+        let ranked = rank(
+            vec![fresh, flagged],
+            &last_attempted,
+            now,
+            &Weights::default(),
+        );
+        assert_eq!(ranked[0].1.description, "Flagged");
+    }
+
+    #[test]
+    fn weighted_picks_favor_urgent_problems_and_skip() {
+        use rand::SeedableRng;
+
+        let mut problems = vec![Problem::new("Calm"), Problem::new("Urgent")];
+        problems[0].id = Some(1);
+        problems[1].id = Some(2);
+        let urgencies = [0.0, 9.9];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+
+        let urgent = (0..1000)
+            .filter(|_| {
+                weighted_pick(&problems, &urgencies, &HashSet::new(), &mut rng)
+                    .is_some_and(|problem| problem.id == Some(2))
+            })
+            .count();
+        assert!(urgent > 950 && urgent < 1000);
+
+        let skipped = HashSet::from([2]);
+        let pick = weighted_pick(&problems, &urgencies, &skipped, &mut rng);
+        assert_eq!(pick.and_then(|problem| problem.id), Some(1));
+        assert!(weighted_pick(&problems, &urgencies, &HashSet::from([1, 2]), &mut rng).is_none());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::Problem;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Patterns most interview problems come down to, matched against a
+/// problem's pattern ignoring case
+pub const CORE_PATTERNS: [&str; 12] = [
+    "two pointers",
+    "sliding window",
+    "binary search",
+    "hash map",
+    "stack",
+    "linked list",
+    "tree",
+    "graph",
+    "heap",
+    "backtracking",
+    "dynamic programming",
+    "greedy",
+];
+
+/// Points out of 100 for the share of core patterns with a solved problem
+pub const COVERAGE_POINTS: f64 = 40.0;
+/// Points for the share of recent attempts that were solves
+pub const SOLVE_RATE_POINTS: f64 = 35.0;
+/// Points for how recently solved problems were last solved
+pub const FRESHNESS_POINTS: f64 = 25.0;
+
+/// Attempts in this many days count towards the solve rate
+pub const RECENT_DAYS: i64 = 30;
+
+/// Days after its last solve at which a problem adds nothing to freshness
+const FRESH_DAYS: f64 = 60.0;
+
+pub struct Readiness {
+    /// Core patterns with no solved problem yet
+    pub missing_patterns: Vec<&'static str>,
+    /// Attempts in the last `RECENT_DAYS`, and how many of them were solves
+    pub recent_attempts: usize,
+    pub recent_solves: usize,
+    /// Average days since each solved problem was last solved
+    pub average_days_since_solve: Option<f64>,
+    coverage: f64,
+    solve_rate: f64,
+    freshness: f64,
+}
+
+impl Readiness {
+    /// Weighted total of the three parts, from 0 to 100
+    pub fn score(&self) -> f64 {
+        self.parts()
+            .iter()
+            .map(|(_, points, max)| points.min(*max))
+            .sum()
+    }
+
+    /// Each part's name, points and maximum points
+    fn parts(&self) -> [(&'static str, f64, f64); 3] {
+        [
+            (
+                "Pattern coverage",
+                self.coverage * COVERAGE_POINTS,
+                COVERAGE_POINTS,
+            ),
+            (
+                "Recent solve rate",
+                self.solve_rate * SOLVE_RATE_POINTS,
+                SOLVE_RATE_POINTS,
+            ),
+            (
+                "Freshness",
+                self.freshness * FRESHNESS_POINTS,
+                FRESHNESS_POINTS,
+            ),
+        ]
+    }
+}
+
+/// Scores readiness from the problems, when each was last solved and the
+/// `(attempts, solves)` of the last `RECENT_DAYS`
+pub fn assess(
+    problems: &[Problem],
+    last_solved: &HashMap<i64, DateTime<Utc>>,
+    recent: (usize, usize),
+    now: DateTime<Utc>,
+) -> Readiness {
+    let solved: Vec<&Problem> = problems
+        .iter()
+        .filter(|problem| problem.id.is_some_and(|id| last_solved.contains_key(&id)))
+        .collect();
+    let missing_patterns: Vec<&'static str> = CORE_PATTERNS
+        .into_iter()
+        .filter(|core| {
+            !solved.iter().any(|problem| {
+                problem
+                    .pattern
+                    .as_deref()
+                    .is_some_and(|pattern| pattern.trim().eq_ignore_ascii_case(core))
+            })
+        })
+        .collect();
+
+    let days_since: Vec<f64> = solved
+        .iter()
+        .filter_map(|problem| last_solved.get(&problem.id?))
+        .map(|at| (now - *at).num_days().max(0) as f64)
+        .collect();
+    let (average_days_since_solve, freshness) = if days_since.is_empty() {
+        (None, 0.0)
+    } else {
+        let count = days_since.len() as f64;
+        (
+            Some(days_since.iter().sum::<f64>() / count),
+            days_since
+                .iter()
+                .map(|days| 1.0 - (days / FRESH_DAYS).min(1.0))
+                .sum::<f64>()
+                / count,
+        )
+    };
+
+    let (recent_attempts, recent_solves) = recent;
+    Readiness {
+        coverage: 1.0 - missing_patterns.len() as f64 / CORE_PATTERNS.len() as f64,
+        missing_patterns,
+        recent_attempts,
+        recent_solves,
+        solve_rate: if recent_attempts == 0 {
+            0.0
+        } else {
+            recent_solves as f64 / recent_attempts as f64
+        },
+        average_days_since_solve,
+        freshness,
+    }
+}
+
+impl fmt::Display for Readiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Readiness: {:.0}/100\n", self.score())?;
+        for (name, points, max) in self.parts() {
+            writeln!(f, "  {:<18} {:>3.0}/{:.0}", name, points, max)?;
+        }
+
+        // The parts losing the most points come first
+        let mut parts = self.parts();
+        parts.sort_by(|a, b| (b.2 - b.1).total_cmp(&(a.2 - a.1)));
+        let mut drags = Vec::new();
+        for (name, points, max) in parts {
+            if max - points < 0.5 {
+                continue;
+            }
+            drags.push(match name {
+                "Pattern coverage" => {
+                    format!("No solved problem in: {}", self.missing_patterns.join(", "))
+                }
+                "Recent solve rate" if self.recent_attempts == 0 => {
+                    format!("No attempts in the last {} days", RECENT_DAYS)
+                }
+                "Recent solve rate" => format!(
+                    "{} of {} attempts in the last {} days were solves",
+                    self.recent_solves, self.recent_attempts, RECENT_DAYS
+                ),
+                _ => match self.average_days_since_solve {
+                    Some(days) => format!(
+                        "Solved problems were last solved {:.0} days ago on average",
+                        days
+                    ),
+                    None => "Nothing solved yet".to_string(),
+                },
+            });
+        }
+
+        if drags.is_empty() {
+            write!(f, "\nNothing is dragging it down")
+        } else {
+            write!(f, "\nDragging it down:")?;
+            for drag in drags {
+                write!(f, "\n  - {}", drag)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn combines_coverage_solve_rate_and_freshness() {
+        let now = Utc::now();
+        let mut problems = Vec::new();
+        for (id, pattern) in (1..).zip(CORE_PATTERNS) {
+            let mut problem = Problem::new(pattern);
+            problem.id = Some(id);
+            problem.pattern = Some(pattern.to_uppercase());
+            problems.push(problem);
+        }
+        // Half the core patterns solved 30 days ago, the rest never
+        let last_solved: HashMap<i64, DateTime<Utc>> =
+            (1..=6).map(|id| (id, now - Duration::days(30))).collect();
+
+        let readiness = assess(&problems, &last_solved, (4, 3), now);
+        assert_eq!(readiness.missing_patterns.len(), 6);
+        let expected = 0.5 * COVERAGE_POINTS + 0.75 * SOLVE_RATE_POINTS + 0.5 * FRESHNESS_POINTS;
+        assert!((readiness.score() - expected).abs() < 1e-9);
+
+        let nothing = assess(&problems, &HashMap::new(), (0, 0), now);
+        assert_eq!(nothing.score(), 0.0);
+        assert!(nothing
+            .to_string()
+            .contains("No attempts in the last 30 days"));
+    }
+}
@@ -0,0 +1,364 @@
+use crate::clock;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use clap::ValueEnum;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+impl Period {
+    /// First day of the period containing `date`; weeks start on Monday
+    fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn previous(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Week => start - Duration::days(7),
+            Period::Month => start - Months::new(1),
+        }
+    }
+
+    /// e.g. "2026-W07" or "2026-02"
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Period::Week => start.format("%G-W%V").to_string(),
+            Period::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// One logged attempt: which problem, how long, and when
+pub struct AttemptRecord {
+    pub problem_id: i64,
+    pub minutes: f64,
+    pub attempted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Row {
+    pub label: String,
+    /// Distinct problems attempted
+    pub solved: usize,
+    pub minutes: f64,
+    /// Problems first seen in the period
+    pub added: usize,
+}
+
+#[derive(Default)]
+struct Bucket {
+    solved: HashSet<i64>,
+    minutes: f64,
+    added: usize,
+}
+
+/// Totals for the `periods` periods up to and including the one containing
+/// `today`, oldest first. Periods without activity still get a row so gaps
+/// show up. Timestamps are bucketed by their date in the configured zone.
+pub fn summarize(
+    period: Period,
+    attempts: &[AttemptRecord],
+    added: &[DateTime<Utc>],
+    periods: usize,
+    today: NaiveDate,
+) -> Vec<Row> {
+    let mut buckets: BTreeMap<NaiveDate, Bucket> = BTreeMap::new();
+    let mut start = period.start(today);
+    for _ in 0..periods {
+        buckets.insert(start, Bucket::default());
+        start = period.previous(start);
+    }
+
+    let bucket_of = |timestamp: &DateTime<Utc>| period.start(clock::date_of(*timestamp));
+    for attempt in attempts {
+        if let Some(bucket) = buckets.get_mut(&bucket_of(&attempt.attempted_at)) {
+            bucket.solved.insert(attempt.problem_id);
+            bucket.minutes += attempt.minutes;
+        }
+    }
+    for timestamp in added {
+        if let Some(bucket) = buckets.get_mut(&bucket_of(timestamp)) {
+            bucket.added += 1;
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(start, bucket)| Row {
+            label: period.label(start),
+            solved: bucket.solved.len(),
+            minutes: bucket.minutes,
+            added: bucket.added,
+        })
+        .collect()
+}
+
+/// How many of `timestamps` fall on each of the `days` days up to and
+/// including `today`, oldest first
+pub fn per_day(
+    timestamps: &[DateTime<Utc>],
+    days: usize,
+    today: NaiveDate,
+) -> Vec<(NaiveDate, usize)> {
+    let mut counts: BTreeMap<NaiveDate, usize> = (0..days as i64)
+        .map(|back| (today - Duration::days(back), 0))
+        .collect();
+    for timestamp in timestamps {
+        if let Some(count) = counts.get_mut(&clock::date_of(*timestamp)) {
+            *count += 1;
+        }
+    }
+    counts.into_iter().collect()
+}
+
+/// Average minutes per month, from the month of the earliest attempt to
+/// that of the latest; months without attempts are `None`
+pub fn monthly_averages(attempts: &[AttemptRecord]) -> Vec<(String, Option<f64>)> {
+    let mut months: BTreeMap<NaiveDate, (f64, i64)> = BTreeMap::new();
+    for attempt in attempts {
+        let month = Period::Month.start(clock::date_of(attempt.attempted_at));
+        let (total, count) = months.entry(month).or_default();
+        *total += attempt.minutes;
+        *count += 1;
+    }
+    let (Some(&first), Some(&last)) = (months.keys().next(), months.keys().next_back()) else {
+        return Vec::new();
+    };
+
+    let mut averages = Vec::new();
+    let mut month = first;
+    while month <= last {
+        let average = months
+            .get(&month)
+            .map(|(total, count)| *total / *count as f64);
+        averages.push((Period::Month.label(month), average));
+        month = month + Months::new(1);
+    }
+    averages
+}
+
+/// Difficulties that always get a coverage column, in this order
+const DIFFICULTIES: [&str; 3] = ["Easy", "Medium", "Hard"];
+
+/// A pattern, a difficulty and how many problems have both
+pub type CoverageGroup = (Option<String>, Option<String>, i64);
+
+/// Problem counts per pattern (rows) and difficulty (columns)
+pub struct Coverage {
+    pub difficulties: Vec<String>,
+    pub rows: Vec<(String, Vec<i64>)>,
+}
+
+/// Lays `(pattern, difficulty, count)` groups out as a grid. Easy, Medium
+/// and Hard come first whether used or not, matched ignoring case; other
+/// difficulties follow alphabetically. Missing values become "(none)".
+pub fn coverage(counts: &[CoverageGroup]) -> Coverage {
+    let name = |value: &Option<String>| value.clone().unwrap_or_else(|| "(none)".to_string());
+    let column_name = |difficulty: &Option<String>| {
+        DIFFICULTIES
+            .into_iter()
+            .find(|known| {
+                difficulty
+                    .as_deref()
+                    .is_some_and(|difficulty| known.eq_ignore_ascii_case(difficulty.trim()))
+            })
+            .map_or_else(|| name(difficulty), str::to_string)
+    };
+
+    let mut difficulties: Vec<String> = DIFFICULTIES.map(str::to_string).to_vec();
+    let others: BTreeSet<String> = counts
+        .iter()
+        .map(|(_, difficulty, _)| column_name(difficulty))
+        .filter(|difficulty| !difficulties.contains(difficulty))
+        .collect();
+    difficulties.extend(others);
+
+    let mut rows: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for (pattern, difficulty, count) in counts {
+        let column = column_name(difficulty);
+        let row = rows
+            .entry(name(pattern))
+            .or_insert_with(|| vec![0; difficulties.len()]);
+        if let Some(index) = difficulties.iter().position(|known| *known == column) {
+            row[index] += count;
+        }
+    }
+
+    Coverage {
+        difficulties,
+        rows: rows.into_iter().collect(),
+    }
+}
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One bar per value, scaled between the smallest and largest; `None`
+/// leaves a gap. Equal values all get the middle bar.
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    let present = values.iter().flatten();
+    let min = present.clone().copied().fold(f64::INFINITY, f64::min);
+    let max = present.copied().fold(f64::NEG_INFINITY, f64::max);
+
+    values
+        .iter()
+        .map(|value| match value {
+            None => ' ',
+            Some(_) if max <= min => SPARKS[SPARKS.len() / 2 - 1],
+            Some(value) => {
+                let level = (value - min) / (max - min) * (SPARKS.len() - 1) as f64;
+                SPARKS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        let noon = NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap();
+        clock::instant_of(&noon).unwrap()
+    }
+
+    fn attempt(problem_id: i64, minutes: f64, attempted_at: DateTime<Utc>) -> AttemptRecord {
+        AttemptRecord {
+            problem_id,
+            minutes,
+            attempted_at,
+        }
+    }
+
+    #[test]
+    fn buckets_by_week_including_empty_weeks() {
+        // Wednesday 2026-10-14; its week starts Monday 2026-10-12
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let attempts = [
+            attempt(1, 20.0, at(2026, 10, 12)),
+            attempt(1, 10.0, at(2026, 10, 13)),
+            attempt(2, 30.0, at(2026, 9, 29)),
+            // Outside the three weeks shown
+            attempt(3, 99.0, at(2026, 9, 1)),
+        ];
+        let added = [at(2026, 10, 12), at(2026, 9, 30)];
+
+        let rows = summarize(Period::Week, &attempts, &added, 3, today);
+
+        assert_eq!(
+            rows,
+            [
+                Row {
+                    label: "2026-W40".to_string(),
+                    solved: 1,
+                    minutes: 30.0,
+                    added: 1
+                },
+                Row {
+                    label: "2026-W41".to_string(),
+                    solved: 0,
+                    minutes: 0.0,
+                    added: 0
+                },
+                Row {
+                    label: "2026-W42".to_string(),
+                    solved: 1,
+                    minutes: 30.0,
+                    added: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn per_day_counts_include_quiet_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let added = [
+            at(2026, 10, 15),
+            at(2026, 10, 15),
+            at(2026, 10, 13),
+            at(2026, 9, 1),
+        ];
+
+        let counts: Vec<usize> = per_day(&added, 3, today)
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect();
+        assert_eq!(counts, [1, 0, 2]);
+    }
+
+    #[test]
+    fn monthly_averages_fill_gaps_between_months() {
+        let attempts = [
+            attempt(1, 40.0, at(2026, 1, 5)),
+            attempt(2, 20.0, at(2026, 1, 20)),
+            attempt(1, 15.0, at(2026, 3, 2)),
+        ];
+
+        assert_eq!(
+            monthly_averages(&attempts),
+            [
+                ("2026-01".to_string(), Some(30.0)),
+                ("2026-02".to_string(), None),
+                ("2026-03".to_string(), Some(15.0)),
+            ]
+        );
+        assert!(monthly_averages(&[]).is_empty());
+    }
+
+    #[test]
+    fn sparkline_scales_between_extremes() {
+        assert_eq!(
+            sparkline(&[Some(30.0), None, Some(15.0), Some(22.5)]),
+            "█ ▁▅"
+        );
+        assert_eq!(sparkline(&[Some(10.0), Some(10.0)]), "▄▄");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn month_periods_step_back_across_years() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+        let rows = summarize(Period::Month, &[], &[], 3, today);
+        let labels: Vec<&str> = rows.iter().map(|row| row.label.as_str()).collect();
+        assert_eq!(labels, ["2025-12", "2026-01", "2026-02"]);
+    }
+
+    #[test]
+    fn coverage_grids_patterns_by_difficulty() {
+        let group = |pattern: Option<&str>, difficulty: Option<&str>, count| {
+            (
+                pattern.map(str::to_string),
+                difficulty.map(str::to_string),
+                count,
+            )
+        };
+        let grid = coverage(&[
+            group(Some("two-pointer"), Some("Easy"), 2),
+            group(Some("two-pointer"), Some("medium"), 1),
+            group(Some("two-pointer"), Some("Medium"), 3),
+            group(Some("dp"), Some("Insane"), 1),
+            group(None, None, 4),
+        ]);
+
+        assert_eq!(
+            grid.difficulties,
+            ["Easy", "Medium", "Hard", "(none)", "Insane"]
+        );
+        assert_eq!(
+            grid.rows,
+            [
+                ("(none)".to_string(), vec![0, 0, 0, 4, 0]),
+                ("dp".to_string(), vec![0, 0, 0, 0, 1]),
+                ("two-pointer".to_string(), vec![2, 4, 0, 0, 0]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,586 @@
+use rusqlite::{Connection, Result};
+use std::collections::HashMap;
+
+type Migration = fn(&Connection) -> Result<()>;
+
+/// Ordered schema migrations; migration `n` upgrades a database from
+/// `PRAGMA user_version` `n` to `n + 1`. Append new steps, never edit old ones.
+const MIGRATIONS: &[Migration] = &[
+    baseline,
+    audit_log,
+    personal_difficulty,
+    links,
+    locked,
+    recent_views,
+    categories,
+    tags,
+    attempt_outcomes,
+    sources,
+    focus,
+    sessions,
+    named_lists,
+    trash,
+    solutions,
+    solution_languages,
+    real_minutes,
+    prerequisites,
+    slugs,
+];
+
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+/// Oldest SQLite release shikari runs against, in `rusqlite::version_number()`
+/// form. This is the floor rusqlite itself supports.
+const MIN_SQLITE_VERSION: i32 = 3_014_000;
+
+/// Renders a `rusqlite::version_number()` style number as `major.minor.patch`
+pub fn format_sqlite_version(number: i32) -> String {
+    format!(
+        "{}.{}.{}",
+        number / 1_000_000,
+        number / 1_000 % 1_000,
+        number % 1_000
+    )
+}
+
+/// Fails when the linked SQLite is older than `MIN_SQLITE_VERSION`
+pub fn check_sqlite_version() -> Result<()> {
+    if rusqlite::version_number() < MIN_SQLITE_VERSION {
+        return Err(rusqlite::Error::InvalidParameterName(format!(
+            "SQLite {} is too old; shikari needs {} or newer",
+            rusqlite::version(),
+            format_sqlite_version(MIN_SQLITE_VERSION)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Brings the database up to `SCHEMA_VERSION`, one transaction per step
+pub fn migrate(conn: &Connection) -> Result<()> {
+    let version = user_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(rusqlite::Error::InvalidParameterName(format!(
+            "Database schema version {} is newer than this version of shikari supports ({})",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.unchecked_transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Fails unless the database is at `SCHEMA_VERSION`, for connections that
+/// can't migrate it, such as read-only ones
+pub fn check_current(conn: &Connection) -> Result<()> {
+    let version = user_version(conn)?;
+    if version < SCHEMA_VERSION {
+        return Err(rusqlite::Error::InvalidParameterName(format!(
+            "Database schema version {} is older than this version of shikari expects ({}); \
+            open it once with write access to upgrade it",
+            version, SCHEMA_VERSION
+        )));
+    }
+    if version > SCHEMA_VERSION {
+        return Err(rusqlite::Error::InvalidParameterName(format!(
+            "Database schema version {} is newer than this version of shikari supports ({})",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn user_version(conn: &Connection) -> Result<usize> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let mut names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    if names.any(|name| name.as_deref() == Ok(column)) {
+        return Ok(());
+    }
+
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 1: the schema as it stood before versioning. Databases created
+/// back then may already have any subset of it, so every step is idempotent.
+fn baseline(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS problems (
+            id INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            link TEXT,
+            category TEXT,
+            pattern TEXT,
+            difficulty TEXT,
+            time_to_solve_1st INTEGER,
+            time_to_solve_2nd INTEGER,
+            time_to_solve_3rd INTEGER,
+            comments TEXT,
+            should_solve_again INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // Every recorded solve time is also logged with a timestamp
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attempts (
+            id INTEGER PRIMARY KEY,
+            problem_id INTEGER NOT NULL,
+            attempt INTEGER NOT NULL,
+            minutes INTEGER NOT NULL,
+            attempted_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS goals (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            minutes_per_day INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    add_column_if_missing(conn, "problems", "company", "TEXT")?;
+    add_column_if_missing(conn, "problems", "frequency", "REAL")?;
+
+    Ok(())
+}
+
+/// Version 2: an append-only record of every change, kept append-only by
+/// triggers rejecting updates and deletes
+fn audit_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE audit_log (
+            id INTEGER PRIMARY KEY,
+            logged_at TEXT NOT NULL,
+            command TEXT NOT NULL,
+            problem_id INTEGER,
+            description TEXT NOT NULL
+        );
+        CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+        BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;
+        CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+        BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;",
+    )
+}
+
+/// Version 3: the user's own 1-5 difficulty rating, independent of the
+/// site's Easy/Medium/Hard
+fn personal_difficulty(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE problems ADD COLUMN personal_difficulty INTEGER
+        CHECK (personal_difficulty BETWEEN 1 AND 5)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 4: any number of labelled links per problem. The existing link
+/// is copied in as "problem" and stays mirrored in `problems.link`, which
+/// dedupe, sync and older exports key on.
+fn links(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE links (
+            id INTEGER PRIMARY KEY,
+            problem_id INTEGER NOT NULL,
+            label TEXT NOT NULL,
+            url TEXT NOT NULL,
+            UNIQUE (problem_id, label)
+        );
+        INSERT INTO links (problem_id, label, url)
+        SELECT id, 'problem', link FROM problems WHERE link IS NOT NULL AND link != '';",
+    )
+}
+
+/// Version 5: locked problems refuse edits, time updates and deletion
+/// unless forced
+fn locked(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE problems ADD COLUMN locked INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 6: when each problem was last shown, for `recent`
+fn recent_views(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE recent_views (
+            id INTEGER PRIMARY KEY,
+            problem_id INTEGER NOT NULL,
+            viewed_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 7: canonical category names, matched case-insensitively. The
+/// first spelling seen becomes the canonical one and is written back to
+/// `problems.category`, which stays mirrored for filtering and exports.
+fn categories(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE categories (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        );
+        ALTER TABLE problems ADD COLUMN category_id INTEGER REFERENCES categories (id);
+        INSERT OR IGNORE INTO categories (name)
+        SELECT TRIM(category) FROM problems WHERE TRIM(category) != '' ORDER BY id;
+        UPDATE problems SET category_id =
+            (SELECT id FROM categories WHERE name = TRIM(problems.category));
+        UPDATE problems SET category =
+            (SELECT name FROM categories WHERE id = problems.category_id);",
+    )
+}
+
+/// Version 8: free-form tags, any number per problem
+fn tags(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE tags (
+            id INTEGER PRIMARY KEY,
+            problem_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            UNIQUE (problem_id, tag)
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 9: whether each attempt ended in a solve or a give-up, and a
+/// note about it. Attempts logged before this were all solves.
+fn attempt_outcomes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE attempts ADD COLUMN solved INTEGER NOT NULL DEFAULT 1;
+        ALTER TABLE attempts ADD COLUMN note TEXT;",
+    )
+}
+
+/// Version 10: the judge each problem comes from. Rows from before are
+/// LeetCode's unless their link says otherwise. Slugs are derived from
+/// links rather than stored until version 19, which indexes them.
+fn sources(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE problems ADD COLUMN source TEXT NOT NULL DEFAULT 'leetcode';
+        UPDATE problems SET source = 'codeforces' WHERE link LIKE '%codeforces.com/%';
+        UPDATE problems SET source = 'atcoder' WHERE link LIKE '%atcoder.jp/%';",
+    )
+}
+
+/// Version 11: the focus list, a hand-picked working set kept apart from
+/// the review flag
+fn focus(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE problems ADD COLUMN focus INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 12: the practice session in progress, at most one at a time
+fn sessions(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE sessions (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            problem_id INTEGER NOT NULL,
+            started_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 13: curated lists such as "Blind 75", and which problems belong
+/// to each
+fn named_lists(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE lists (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        );
+        CREATE TABLE problem_lists (
+            list_id INTEGER NOT NULL,
+            problem_id INTEGER NOT NULL,
+            PRIMARY KEY (list_id, problem_id)
+        );",
+    )
+}
+
+/// Version 14: when a problem was moved to the trash; every query but
+/// `trash` leaves such problems out until they are restored
+fn trash(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE problems ADD COLUMN deleted_at TEXT", [])?;
+
+    Ok(())
+}
+
+/// Version 15: the code of a problem's solution, kept apart from comments
+fn solutions(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE problems ADD COLUMN solution TEXT", [])?;
+
+    Ok(())
+}
+
+/// Version 16: the language a problem's solution is written in
+fn solution_languages(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE problems ADD COLUMN solution_language TEXT", [])?;
+
+    Ok(())
+}
+
+/// Version 17: solve times and attempt minutes as REAL, so they can hold
+/// fractions of a minute. SQLite can't change a column's type in place, so
+/// each table is rebuilt from its own definition with the type swapped.
+fn real_minutes(conn: &Connection) -> Result<()> {
+    for (table, columns) in [
+        (
+            "problems",
+            &[
+                "time_to_solve_1st",
+                "time_to_solve_2nd",
+                "time_to_solve_3rd",
+            ][..],
+        ),
+        ("attempts", &["minutes"][..]),
+    ] {
+        let mut sql: String = conn.query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [table],
+            |row| row.get(0),
+        )?;
+        sql = sql.replacen(table, &format!("{}_new", table), 1);
+        for column in columns {
+            sql = sql.replace(&format!("{} INTEGER", column), &format!("{} REAL", column));
+        }
+
+        conn.execute_batch(&format!(
+            "{sql};
+            INSERT INTO {table}_new SELECT * FROM {table};
+            DROP TABLE {table};
+            ALTER TABLE {table}_new RENAME TO {table};"
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Version 18: problems meant to be solved before others, as
+/// `(problem_id, prereq_id)` pairs. Cycles are refused where pairs are added.
+fn prerequisites(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE prerequisites (
+            problem_id INTEGER NOT NULL,
+            prereq_id INTEGER NOT NULL,
+            PRIMARY KEY (problem_id, prereq_id)
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Version 19: each problem's slug, stored so an index can keep
+/// `(source, slug)` unique; see `stored_slug`. Where problems already share
+/// one, the lowest ID keeps it and the others are reported and left without
+/// a slug until their link changes.
+fn slugs(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE problems ADD COLUMN slug TEXT", [])?;
+
+    // `link` mirrors the "problem" row in `links`, so it alone gives each
+    // problem's URL
+    let mut stmt = conn.prepare("SELECT id, link, source FROM problems ORDER BY id")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    let mut owners: HashMap<(String, String), i64> = HashMap::new();
+    for (id, link, source) in rows {
+        let Some(slug) = link.as_deref().and_then(crate::judge_slug) else {
+            continue;
+        };
+        match owners.get(&(source.clone(), slug.clone())) {
+            Some(owner) => eprintln!(
+                "Problems #{} and #{} share the {} slug '{}'; #{} keeps it",
+                owner, id, source, slug, owner
+            ),
+            None => {
+                conn.execute(
+                    "UPDATE problems SET slug = ? WHERE id = ?",
+                    rusqlite::params![slug, id],
+                )?;
+                owners.insert((source, slug), id);
+            }
+        }
+    }
+
+    conn.execute(
+        "CREATE UNIQUE INDEX problems_source_slug ON problems (source, slug)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sqlite_version_numbers() {
+        assert_eq!(format_sqlite_version(3_014_000), "3.14.0");
+        assert_eq!(format_sqlite_version(3_040_001), "3.40.1");
+    }
+
+    #[test]
+    fn migrate_reaches_current_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn only_the_current_version_passes_the_check() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        check_current(&conn).unwrap();
+
+        for version in [SCHEMA_VERSION - 1, SCHEMA_VERSION + 1] {
+            conn.pragma_update(None, "user_version", version).unwrap();
+            assert!(matches!(
+                check_current(&conn),
+                Err(rusqlite::Error::InvalidParameterName(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn real_minutes_keeps_rows_and_accepts_fractions() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..16] {
+            migration(&conn).unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO problems (description, time_to_solve_1st) VALUES ('a', 12);
+            INSERT INTO attempts (problem_id, attempt, minutes, attempted_at)
+            VALUES (1, 1, 12, '2026-01-01T00:00:00Z');",
+        )
+        .unwrap();
+
+        real_minutes(&conn).unwrap();
+
+        conn.execute_batch(
+            "UPDATE problems SET time_to_solve_2nd = 2.5;
+            INSERT INTO attempts (problem_id, attempt, minutes, attempted_at)
+            VALUES (1, 2, 2.5, '2026-01-02T00:00:00Z');",
+        )
+        .unwrap();
+        let times: (String, f64, f64) = conn
+            .query_row(
+                "SELECT typeof(time_to_solve_1st), time_to_solve_1st, time_to_solve_2nd
+                FROM problems",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(times, ("real".to_string(), 12.0, 2.5));
+        let minutes: f64 = conn
+            .query_row("SELECT SUM(minutes) FROM attempts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(minutes, 14.5);
+    }
+
+    #[test]
+    fn slugs_keep_the_first_of_each_duplicate() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..18] {
+            migration(&conn).unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO problems (description, link) VALUES
+                ('a', 'https://leetcode.com/problems/two-sum/'),
+                ('b', 'https://leetcode.com/problems/two-sum/description/'),
+                ('c', 'https://example.com/problems/two-sum/'),
+                ('d', NULL);",
+        )
+        .unwrap();
+
+        slugs(&conn).unwrap();
+
+        let slugs: Vec<Option<String>> = conn
+            .prepare("SELECT slug FROM problems ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(slugs, [Some("two-sum".to_string()), None, None, None]);
+        assert!(conn
+            .execute("UPDATE problems SET slug = 'two-sum' WHERE id = 2", [])
+            .is_err());
+    }
+
+    #[test]
+    fn categories_backfill_merges_case_variants() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..6] {
+            migration(&conn).unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO problems (description, category) VALUES ('a', 'DP');
+            INSERT INTO problems (description, category) VALUES ('b', ' dp ');
+            INSERT INTO problems (description, category) VALUES ('c', NULL);",
+        )
+        .unwrap();
+
+        categories(&conn).unwrap();
+
+        let rows: Vec<(Option<String>, Option<i64>)> = conn
+            .prepare("SELECT category, category_id FROM problems ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            [
+                (Some("DP".to_string()), Some(1)),
+                (Some("DP".to_string()), Some(1)),
+                (None, None),
+            ]
+        );
+    }
+}
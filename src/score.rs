@@ -0,0 +1,143 @@
+use crate::Problem;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Points per solved problem of each difficulty
+pub struct Points {
+    pub easy: i64,
+    pub medium: i64,
+    pub hard: i64,
+}
+
+impl Default for Points {
+    fn default() -> Self {
+        Points {
+            easy: 1,
+            medium: 3,
+            hard: 5,
+        }
+    }
+}
+
+impl Points {
+    fn for_difficulty(&self, difficulty: &str) -> i64 {
+        match difficulty_key(difficulty).as_str() {
+            "Easy" => self.easy,
+            "Medium" => self.medium,
+            "Hard" => self.hard,
+            _ => 0,
+        }
+    }
+}
+
+/// Groups difficulties that only differ in case or surrounding spaces, with
+/// Easy, Medium and Hard spelt the usual way
+fn difficulty_key(difficulty: &str) -> String {
+    let lower = difficulty.trim().to_lowercase();
+    match lower.as_str() {
+        "easy" => "Easy".to_string(),
+        "medium" => "Medium".to_string(),
+        "hard" => "Hard".to_string(),
+        _ => lower,
+    }
+}
+
+/// Bonus for each problem re-solved since it was last flagged for review
+const REVIEW_BONUS: i64 = 2;
+
+pub struct Score {
+    /// Solved count and points earned, per difficulty
+    pub by_difficulty: BTreeMap<String, (usize, i64)>,
+    /// Problems solved again that are no longer flagged for review
+    pub reviews_cleared: usize,
+    pub total: i64,
+}
+
+/// Scores every solved problem by its difficulty, plus a bonus for each one
+/// that was re-solved and is no longer flagged for review
+pub fn compute(problems: &[Problem], points: &Points) -> Score {
+    let mut by_difficulty: BTreeMap<String, (usize, i64)> = BTreeMap::new();
+    let mut reviews_cleared = 0;
+
+    for problem in problems
+        .iter()
+        .filter(|problem| problem.time_to_solve_1st.is_some())
+    {
+        let difficulty = problem.difficulty.as_deref().unwrap_or("Unknown");
+        let entry = by_difficulty.entry(difficulty_key(difficulty)).or_default();
+        entry.0 += 1;
+        entry.1 += points.for_difficulty(difficulty);
+
+        if problem.time_to_solve_2nd.is_some() && !problem.should_solve_again {
+            reviews_cleared += 1;
+        }
+    }
+
+    let total = by_difficulty
+        .values()
+        .map(|(_, points)| points)
+        .sum::<i64>()
+        + reviews_cleared as i64 * REVIEW_BONUS;
+
+    Score {
+        by_difficulty,
+        reviews_cleared,
+        total,
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score: {}", self.total)?;
+        for (difficulty, (solved, points)) in &self.by_difficulty {
+            writeln!(
+                f,
+                "  {:<14} {:>4} solved  {:>5} pts",
+                difficulty, solved, points
+            )?;
+        }
+        write!(
+            f,
+            "  {:<14} {:>4} cleared {:>5} pts",
+            "Review bonus",
+            self.reviews_cleared,
+            self.reviews_cleared as i64 * REVIEW_BONUS
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(difficulty: &str, times: usize, review: bool) -> Problem {
+        let mut problem = Problem::new("Two Sum");
+        problem.difficulty = Some(difficulty.to_string());
+        problem.time_to_solve_1st = Some(10.0);
+        if times > 1 {
+            problem.time_to_solve_2nd = Some(8.0);
+        }
+        problem.should_solve_again = review;
+        problem
+    }
+
+    #[test]
+    fn weights_difficulties_and_adds_review_bonus() {
+        let problems = [
+            solved("Easy", 1, false),
+            solved("Medium", 1, false),
+            solved("hard", 2, false),
+            solved("Hard", 2, true),
+            Problem::new("Unsolved"),
+        ];
+
+        let score = compute(&problems, &Points::default());
+
+        assert_eq!(score.by_difficulty["Easy"], (1, 1));
+        assert_eq!(score.by_difficulty["Medium"], (1, 3));
+        assert_eq!(score.by_difficulty["Hard"], (2, 10));
+        assert_eq!(score.by_difficulty.len(), 3);
+        assert_eq!(score.reviews_cleared, 1);
+        assert_eq!(score.total, 1 + 3 + 5 + 5 + REVIEW_BONUS);
+    }
+}
@@ -0,0 +1,501 @@
+use crate::{humanize, Problem};
+use chrono::{DateTime, Duration, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Which recorded attempts feed the time averages
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttemptSelection {
+    /// Every recorded attempt
+    All,
+    /// Only first attempts, i.e. how hard a problem was when new
+    First,
+    /// Only each problem's most recent attempt, i.e. current performance
+    Latest,
+}
+
+impl AttemptSelection {
+    fn times(self, problem: &Problem) -> Vec<f64> {
+        let times = [
+            problem.time_to_solve_1st,
+            problem.time_to_solve_2nd,
+            problem.time_to_solve_3rd,
+        ];
+        match self {
+            AttemptSelection::All => times.into_iter().flatten().collect(),
+            AttemptSelection::First => problem.time_to_solve_1st.into_iter().collect(),
+            AttemptSelection::Latest => times.into_iter().flatten().last().into_iter().collect(),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AttemptSelection::All => "all attempts",
+            AttemptSelection::First => "first attempts",
+            AttemptSelection::Latest => "latest attempts",
+        }
+    }
+}
+
+/// How the latest solve time compares to the average for the difficulty
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comfort {
+    Fast,
+    Average,
+    Slow,
+}
+
+impl fmt::Display for Comfort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Comfort::Fast => "fast",
+            Comfort::Average => "average",
+            Comfort::Slow => "slow",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Latest times under this fraction of the difficulty's average are fast
+const FAST_RATIO: f64 = 0.75;
+
+/// Latest times over this multiple of the difficulty's average are slow
+const SLOW_RATIO: f64 = 1.25;
+
+/// Buckets the problem's latest solve time against `difficulty_average`,
+/// the average solve time for problems of its difficulty
+pub fn comfort(problem: &Problem, difficulty_average: f64) -> Option<Comfort> {
+    let latest = AttemptSelection::Latest.times(problem).pop()?;
+    if difficulty_average <= 0.0 {
+        return None;
+    }
+
+    let ratio = latest / difficulty_average;
+    Some(if ratio < FAST_RATIO {
+        Comfort::Fast
+    } else if ratio > SLOW_RATIO {
+        Comfort::Slow
+    } else {
+        Comfort::Average
+    })
+}
+
+/// Where a problem stands in being learned
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Mastery {
+    /// Never attempted
+    New,
+    /// Attempted, but not yet mastered
+    Learning,
+    /// Solved recently, not slowly, and not flagged for review
+    Mastered,
+}
+
+impl fmt::Display for Mastery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Mastery::New => "New",
+            Mastery::Learning => "Learning",
+            Mastery::Mastered => "Mastered",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Solves older than this many days no longer count towards mastery
+const MASTERY_DAYS: i64 = 30;
+
+/// Classifies the problem from its flags and derived fields, so it needs
+/// `annotate`'s attempt count and comfort. A latest solve counts as fast
+/// unless its comfort is slow; without a difficulty average to compare
+/// against, only recency and the review flag decide.
+pub fn mastery(
+    problem: &Problem,
+    last_solved: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Mastery {
+    let attempted =
+        problem.attempt_count.unwrap_or(0) > 0 || !AttemptSelection::All.times(problem).is_empty();
+    if !attempted {
+        return Mastery::New;
+    }
+
+    let recent = last_solved.is_some_and(|solved| now - solved <= Duration::days(MASTERY_DAYS));
+    if recent && !problem.should_solve_again && problem.comfort != Some(Comfort::Slow) {
+        Mastery::Mastered
+    } else {
+        Mastery::Learning
+    }
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub selection: AttemptSelection,
+    pub total: usize,
+    pub attempted: usize,
+    pub to_review: usize,
+    pub by_difficulty: BTreeMap<String, usize>,
+    pub by_category: BTreeMap<String, usize>,
+    pub by_personal_difficulty: BTreeMap<i64, usize>,
+    pub unrated: usize,
+    pub average_minutes: Option<f64>,
+    /// Average of each attempted problem's first time, and of its best
+    pub average_first: Option<f64>,
+    pub average_best: Option<f64>,
+    pub average_by_difficulty: BTreeMap<String, f64>,
+    /// Problems with a saved solution in each language, filled in by the
+    /// caller since solutions aren't part of `Problem`
+    pub by_language: BTreeMap<String, usize>,
+}
+
+fn average(times: &[f64]) -> Option<f64> {
+    if times.is_empty() {
+        None
+    } else {
+        Some(times.iter().sum::<f64>() / times.len() as f64)
+    }
+}
+
+/// Expected minutes for a first attempt, from how long similar problems took
+#[derive(Debug, PartialEq)]
+pub struct Estimate {
+    pub minutes: i64,
+    /// How many similar problems the estimate averages
+    pub based_on: usize,
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "~{} min (based on {} similar problem{})",
+            self.minutes,
+            self.based_on,
+            if self.based_on == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Averages the first times of the other problems with the same pattern and
+/// difficulty, or with the same difficulty when none share the pattern.
+/// `None` without a difficulty or any similar solved problem.
+pub fn estimate_solve_time(problem: &Problem, others: &[Problem]) -> Option<Estimate> {
+    let matches = |a: &Option<String>, b: &Option<String>| match (a, b) {
+        (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+        _ => false,
+    };
+    let same_difficulty: Vec<&Problem> = others
+        .iter()
+        .filter(|other| other.id != problem.id && other.time_to_solve_1st.is_some())
+        .filter(|other| matches(&other.difficulty, &problem.difficulty))
+        .collect();
+    let same_pattern: Vec<&Problem> = same_difficulty
+        .iter()
+        .copied()
+        .filter(|other| matches(&other.pattern, &problem.pattern))
+        .collect();
+
+    let similar = if same_pattern.is_empty() {
+        same_difficulty
+    } else {
+        same_pattern
+    };
+    let times: Vec<f64> = similar
+        .iter()
+        .filter_map(|other| other.time_to_solve_1st)
+        .collect();
+    Some(Estimate {
+        minutes: average(&times)?.round() as i64,
+        based_on: times.len(),
+    })
+}
+
+/// The percentiles `percentiles` reports
+pub const PERCENTILES: [f64; 4] = [25.0, 50.0, 75.0, 90.0];
+
+/// The `p`th percentile of sorted times, interpolating between the two
+/// nearest when it falls between them
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = p / 100.0 * last as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    let fraction = rank - below as f64;
+    Some(sorted[below] + (sorted[above] - sorted[below]) * fraction)
+}
+
+/// First-attempt times of every attempted problem, sorted, under "All" and
+/// under each difficulty ("Unknown" when unset)
+pub fn first_times(problems: &[Problem]) -> (Vec<f64>, BTreeMap<String, Vec<f64>>) {
+    let mut all = Vec::new();
+    let mut by_difficulty: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for problem in problems {
+        if let Some(minutes) = problem.time_to_solve_1st {
+            all.push(minutes);
+            by_difficulty
+                .entry(
+                    problem
+                        .difficulty
+                        .as_deref()
+                        .unwrap_or("Unknown")
+                        .to_string(),
+                )
+                .or_default()
+                .push(minutes);
+        }
+    }
+
+    all.sort_by(f64::total_cmp);
+    for times in by_difficulty.values_mut() {
+        times.sort_by(f64::total_cmp);
+    }
+    (all, by_difficulty)
+}
+
+pub fn compute_stats(problems: &[Problem], selection: AttemptSelection) -> Stats {
+    let mut by_difficulty = BTreeMap::new();
+    let mut by_category = BTreeMap::new();
+    let mut by_personal_difficulty = BTreeMap::new();
+    let mut all_times = Vec::new();
+    let mut times_by_difficulty: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+
+    for problem in problems {
+        let difficulty = problem
+            .difficulty
+            .as_deref()
+            .unwrap_or("Unknown")
+            .to_string();
+        let category = problem
+            .category
+            .as_deref()
+            .unwrap_or("Uncategorized")
+            .to_string();
+        *by_difficulty.entry(difficulty.clone()).or_insert(0) += 1;
+        *by_category.entry(category).or_insert(0) += 1;
+        if let Some(rating) = problem.personal_difficulty {
+            *by_personal_difficulty.entry(rating).or_insert(0) += 1;
+        }
+
+        let times = selection.times(problem);
+        all_times.extend(&times);
+        times_by_difficulty
+            .entry(difficulty)
+            .or_default()
+            .extend(times);
+    }
+
+    Stats {
+        selection,
+        total: problems.len(),
+        attempted: problems
+            .iter()
+            .filter(|problem| problem.time_to_solve_1st.is_some())
+            .count(),
+        to_review: problems
+            .iter()
+            .filter(|problem| problem.should_solve_again)
+            .count(),
+        by_difficulty,
+        by_category,
+        unrated: problems
+            .iter()
+            .filter(|problem| problem.personal_difficulty.is_none())
+            .count(),
+        by_personal_difficulty,
+        average_minutes: average(&all_times),
+        average_first: average(
+            &problems
+                .iter()
+                .filter_map(|problem| problem.first_time)
+                .collect::<Vec<_>>(),
+        ),
+        average_best: average(
+            &problems
+                .iter()
+                .filter_map(|problem| problem.best_time)
+                .collect::<Vec<_>>(),
+        ),
+        average_by_difficulty: times_by_difficulty
+            .into_iter()
+            .filter_map(|(difficulty, times)| Some((difficulty, average(&times)?)))
+            .collect(),
+        by_language: BTreeMap::new(),
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Problems: {}", self.total)?;
+        writeln!(f, "Attempted: {}", self.attempted)?;
+        writeln!(f, "To review: {}", self.to_review)?;
+
+        match self.average_minutes {
+            Some(avg) => writeln!(
+                f,
+                "Average time ({}): {}",
+                self.selection.label(),
+                humanize::average(avg)
+            )?,
+            None => writeln!(f, "Average time ({}): -", self.selection.label())?,
+        }
+        if let (Some(first), Some(best)) = (self.average_first, self.average_best) {
+            writeln!(
+                f,
+                "Average first solve: {}, average best: {}",
+                humanize::average(first),
+                humanize::average(best)
+            )?;
+        }
+
+        writeln!(f, "\nBy difficulty:")?;
+        for (difficulty, count) in &self.by_difficulty {
+            write!(f, "  {:<14} {:>4}", difficulty, count)?;
+            if let Some(avg) = self.average_by_difficulty.get(difficulty) {
+                write!(f, "  avg {}", humanize::average(*avg))?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "\nBy category:")?;
+        for (category, count) in &self.by_category {
+            writeln!(f, "  {:<14} {:>4}", category, count)?;
+        }
+
+        write!(f, "\nBy personal difficulty:")?;
+        for (rating, count) in &self.by_personal_difficulty {
+            write!(f, "\n  {:<14} {:>4}", format!("{}/5", rating), count)?;
+        }
+        write!(f, "\n  {:<14} {:>4}", "Unrated", self.unrated)?;
+
+        if !self.by_language.is_empty() {
+            write!(f, "\n\nBy solution language:")?;
+            for (language, count) in &self.by_language {
+                write!(f, "\n  {:<14} {:>4}", language, count)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved_in(minutes: f64) -> Problem {
+        let mut problem = Problem::new("Two Sum");
+        problem.time_to_solve_1st = Some(minutes);
+        problem
+    }
+
+    #[test]
+    fn estimates_prefer_problems_with_the_same_pattern() {
+        let problem = |id, pattern: &str, difficulty: &str, minutes: f64| {
+            let mut problem = solved_in(minutes);
+            problem.id = Some(id);
+            problem.pattern = Some(pattern.to_string());
+            problem.difficulty = Some(difficulty.to_string());
+            problem
+        };
+        let target = problem(1, "Graph", "Medium", 99.0);
+        let others = [
+            problem(1, "Graph", "Medium", 99.0),
+            problem(2, "graph", "medium", 20.0),
+            problem(3, "Graph", "Medium", 25.0),
+            problem(4, "Heap", "Medium", 40.0),
+            problem(5, "Graph", "Hard", 60.0),
+        ];
+
+        let estimate = estimate_solve_time(&target, &others).unwrap();
+        assert_eq!(
+            estimate.to_string(),
+            "~23 min (based on 2 similar problems)"
+        );
+
+        let lone = problem(6, "Trie", "Medium", 10.0);
+        assert_eq!(estimate_solve_time(&lone, &others).unwrap().based_on, 4);
+        assert_eq!(
+            estimate_solve_time(&problem(7, "Trie", "Easy", 10.0), &others),
+            None
+        );
+    }
+
+    #[test]
+    fn comfort_boundaries() {
+        assert_eq!(comfort(&solved_in(74.0), 100.0), Some(Comfort::Fast));
+        assert_eq!(comfort(&solved_in(75.0), 100.0), Some(Comfort::Average));
+        assert_eq!(comfort(&solved_in(125.0), 100.0), Some(Comfort::Average));
+        assert_eq!(comfort(&solved_in(126.0), 100.0), Some(Comfort::Slow));
+    }
+
+    #[test]
+    fn mastery_boundaries() {
+        let now = Utc::now();
+        let days_ago = |days| Some(now - Duration::days(days));
+
+        let mut problem = Problem::new("Two Sum");
+        assert_eq!(mastery(&problem, None, now), Mastery::New);
+        problem.attempt_count = Some(0);
+        assert_eq!(mastery(&problem, None, now), Mastery::New);
+
+        // A give-up is an attempt without a solve
+        problem.attempt_count = Some(1);
+        assert_eq!(mastery(&problem, None, now), Mastery::Learning);
+
+        let mut problem = solved_in(20.0);
+        problem.comfort = Some(Comfort::Average);
+        assert_eq!(
+            mastery(&problem, days_ago(MASTERY_DAYS), now),
+            Mastery::Mastered
+        );
+        assert_eq!(
+            mastery(&problem, days_ago(MASTERY_DAYS + 1), now),
+            Mastery::Learning
+        );
+
+        problem.comfort = Some(Comfort::Slow);
+        assert_eq!(mastery(&problem, days_ago(1), now), Mastery::Learning);
+        problem.comfort = None;
+        problem.should_solve_again = true;
+        assert_eq!(mastery(&problem, days_ago(1), now), Mastery::Learning);
+    }
+
+    #[test]
+    fn comfort_uses_latest_attempt() {
+        let mut problem = solved_in(200.0);
+        problem.time_to_solve_2nd = Some(50.0);
+        assert_eq!(comfort(&problem, 100.0), Some(Comfort::Fast));
+    }
+
+    #[test]
+    fn stats_serialize_with_raw_minutes() {
+        let mut slow = solved_in(40.0);
+        slow.difficulty = Some("Hard".to_string());
+        let stats = compute_stats(&[solved_in(10.0), slow], AttemptSelection::First);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["selection"], "first");
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["average_minutes"], 25.0);
+        assert_eq!(json["by_difficulty"]["Hard"], 1);
+        assert_eq!(json["average_by_difficulty"]["Unknown"], 10.0);
+    }
+
+    #[test]
+    fn percentiles_interpolate() {
+        let times = [10.0, 20.0, 30.0, 40.0, 100.0];
+        assert_eq!(percentile(&times, 50.0), Some(30.0));
+        assert_eq!(percentile(&times, 25.0), Some(20.0));
+        assert_eq!(percentile(&times, 90.0), Some(76.0));
+        assert_eq!(percentile(&[7.0], 75.0), Some(7.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn comfort_needs_a_time_and_an_average() {
+        assert_eq!(comfort(&Problem::new("Two Sum"), 100.0), None);
+        assert_eq!(comfort(&solved_in(10.0), 0.0), None);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::export::{self, CSV_COLUMNS};
+use crate::Problem;
+
+/// Placeholders beyond the `CSV_COLUMNS` names, with the column each reads
+const ALIASES: [(&str, &str); 3] = [
+    ("time1", "time_to_solve_1st"),
+    ("time2", "time_to_solve_2nd"),
+    ("time3", "time_to_solve_3rd"),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Text(String),
+    /// A `CSV_COLUMNS` index
+    Column(usize),
+    Tags,
+}
+
+/// A `--template` such as "{id}\t{description} ({difficulty})", checked
+/// when parsed so a typo fails before anything is printed
+#[derive(Clone, Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+fn placeholder(name: &str) -> Result<Part, String> {
+    if name == "tags" {
+        return Ok(Part::Tags);
+    }
+    let column = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, column)| column);
+    match CSV_COLUMNS.iter().position(|known| *known == column) {
+        Some(index) => Ok(Part::Column(index)),
+        None => {
+            let aliases: Vec<&str> = ALIASES.iter().map(|(alias, _)| *alias).collect();
+            Err(format!(
+                "unknown placeholder {{{}}}, expected one of: {}, {}, tags",
+                name,
+                CSV_COLUMNS.join(", "),
+                aliases.join(", ")
+            ))
+        }
+    }
+}
+
+/// Parses a template; `{{` and `}}` stand for literal braces
+pub fn parse(template: &str) -> Result<Template, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) if c != '{' => name.push(c),
+                        _ => return Err(format!("unclosed placeholder in '{}'", template)),
+                    }
+                }
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(placeholder(name.trim())?);
+            }
+            '}' => {
+                return Err(format!(
+                    "unmatched '}}' in '{}'; write }}}} for a brace",
+                    template
+                ))
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+
+    Ok(Template { parts })
+}
+
+impl Template {
+    /// The template with the problem's values filled in; empty fields
+    /// become empty strings
+    pub fn render(&self, problem: &Problem) -> String {
+        let fields = export::csv_fields(problem);
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Column(index) => fields[*index].clone(),
+                Part::Tags => problem.tags.join(","),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_fields_and_aliases() {
+        let mut problem = Problem::new("Two Sum");
+        problem.id = Some(4);
+        problem.difficulty = Some("Easy".to_string());
+        problem.time_to_solve_1st = Some(12.0);
+        problem.add_tag("warmup");
+
+        let template =
+            parse("{id}\t{description} ({difficulty}) {time1}/{time2} {{{tags}}}").unwrap();
+        assert_eq!(template.render(&problem), "4\tTwo Sum (Easy) 12/ {warmup}");
+    }
+
+    #[test]
+    fn rejects_unknown_and_broken_placeholders() {
+        assert!(parse("{id} {title}").unwrap_err().contains("{title}"));
+        assert!(parse("{id").is_err());
+        assert!(parse("id}").is_err());
+        assert!(parse("{id {description}}").is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temp directory for one test, removed
+/// with everything in it when dropped, even if the test panics
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` keeps tests running at the same time out of each other's way
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("shikari-{}-{}", name, std::process::id()));
+        // Left over from a run killed before it could clean up
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
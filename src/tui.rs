@@ -0,0 +1,259 @@
+use crate::{DisplayOptions, Problem, ProblemTracker, SearchMode};
+use chrono::Utc;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::error::Error;
+
+enum Mode {
+    Browse,
+    /// Typing a search query
+    Search(String),
+    /// Typing the minutes for the selected problem's given attempt
+    RecordTime(usize, String),
+}
+
+struct App<'a> {
+    tracker: &'a ProblemTracker,
+    problems: Vec<Problem>,
+    state: ListState,
+    mode: Mode,
+    /// Active search query, empty for every problem
+    query: String,
+    /// Result of the last action, shown in place of the key help
+    status: Option<String>,
+}
+
+impl App<'_> {
+    fn load(&mut self) -> rusqlite::Result<()> {
+        self.problems = if self.query.is_empty() {
+            self.tracker.get_all_problems()?
+        } else {
+            self.tracker
+                .search_problems(std::slice::from_ref(&self.query), SearchMode::And)?
+        };
+        self.state.select(if self.problems.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&Problem> {
+        self.state
+            .selected()
+            .and_then(|index| self.problems.get(index))
+    }
+
+    /// Re-reads the selected problem after a change to it
+    fn refresh_selected(&mut self) -> rusqlite::Result<()> {
+        if let Some(index) = self.state.selected()
+            && let Some(id) = self.problems.get(index).and_then(|problem| problem.id)
+        {
+            self.problems[index] = self.tracker.get_problem(id)?;
+        }
+
+        Ok(())
+    }
+
+    fn toggle_review(&mut self) -> rusqlite::Result<()> {
+        let Some(id) = self.selected().and_then(|problem| problem.id) else {
+            return Ok(());
+        };
+
+        self.tracker.toggle_review_flag(id)?;
+        self.refresh_selected()?;
+        self.status = Some(format!("Toggled review flag on #{}", id));
+
+        Ok(())
+    }
+
+    /// Prompts for the time of the selected problem's next empty attempt
+    fn start_recording(&mut self) {
+        let Some(problem) = self.selected() else {
+            return;
+        };
+
+        if problem.locked {
+            self.status = problem.id.map(|id| format!("#{} is locked", id));
+            return;
+        }
+        match problem.next_attempt() {
+            Some(attempt) => self.mode = Mode::RecordTime(attempt, String::new()),
+            None => self.status = Some("All three attempts are already recorded".to_string()),
+        }
+    }
+
+    fn record_time(&mut self, attempt: usize, input: &str) -> rusqlite::Result<()> {
+        let Some(id) = self.selected().and_then(|problem| problem.id) else {
+            return Ok(());
+        };
+        let minutes = match crate::parse_minutes(input) {
+            Ok(minutes) => minutes,
+            Err(message) => {
+                self.status = Some(message);
+                return Ok(());
+            }
+        };
+
+        self.tracker
+            .update_solve_time(id, attempt, minutes, Utc::now(), false)?;
+        self.refresh_selected()?;
+        self.status = Some(format!(
+            "Recorded attempt {} of #{}: {} minutes",
+            attempt, id, minutes
+        ));
+
+        Ok(())
+    }
+
+    /// Handles one key press, returning whether to quit
+    fn handle_key(&mut self, code: KeyCode) -> rusqlite::Result<bool> {
+        self.status = None;
+
+        match &mut self.mode {
+            Mode::Browse => match code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+                KeyCode::Down | KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+                KeyCode::Home | KeyCode::Char('g') => self.state.select_first(),
+                KeyCode::End | KeyCode::Char('G') => self.state.select_last(),
+                KeyCode::Char('r') => self.toggle_review()?,
+                KeyCode::Char('t') => self.start_recording(),
+                KeyCode::Char('/') => self.mode = Mode::Search(self.query.clone()),
+                _ => {}
+            },
+            Mode::Search(input) => match code {
+                KeyCode::Enter => {
+                    self.query = input.trim().to_string();
+                    self.mode = Mode::Browse;
+                    self.load()?;
+                }
+                KeyCode::Esc => self.mode = Mode::Browse,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Mode::RecordTime(attempt, input) => match code {
+                KeyCode::Enter => {
+                    let (attempt, input) = (*attempt, std::mem::take(input));
+                    self.mode = Mode::Browse;
+                    self.record_time(attempt, &input)?;
+                }
+                KeyCode::Esc => self.mode = Mode::Browse,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+                _ => {}
+            },
+        }
+
+        Ok(false)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .problems
+            .iter()
+            .map(|problem| {
+                let marker = if problem.should_solve_again { "*" } else { " " };
+                ListItem::new(format!(
+                    "{} #{} {}",
+                    marker,
+                    problem.id.unwrap_or(0),
+                    problem.description
+                ))
+            })
+            .collect();
+        let title = if self.query.is_empty() {
+            format!("Problems ({})", self.problems.len())
+        } else {
+            format!("Search '{}' ({})", self.query, self.problems.len())
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, left, &mut self.state);
+
+        let details = self
+            .selected()
+            .map(|problem| {
+                // Styling is ratatui's job; escape codes would show up raw
+                let options = DisplayOptions {
+                    color: false,
+                    ..DisplayOptions::current()
+                };
+                problem.format_with(options).to_string()
+            })
+            .unwrap_or_else(|| "No problems".to_string());
+        let details = Paragraph::new(details)
+            .block(Block::default().borders(Borders::ALL).title("Details"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, right);
+
+        let help = match (&self.mode, &self.status) {
+            (Mode::Browse, Some(status)) => status.clone(),
+            (Mode::Browse, None) => {
+                "j/k move  g/G first/last  t record time  r toggle review  / search  q quit"
+                    .to_string()
+            }
+            (Mode::Search(input), _) => {
+                format!("Search: {}_  (Enter to apply, Esc to cancel)", input)
+            }
+            (Mode::RecordTime(attempt, input), _) => format!(
+                "Minutes for attempt {}: {}_  (Enter to save, Esc to cancel)",
+                attempt, input
+            ),
+        };
+        frame.render_widget(Paragraph::new(help), footer);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn Error>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                // Failures are reported in the status bar so the UI survives them
+                match self.handle_key(key.code) {
+                    Ok(true) => return Ok(()),
+                    Ok(false) => {}
+                    Err(error) => self.status = Some(format!("Error: {}", error)),
+                }
+            }
+        }
+    }
+}
+
+/// Full-screen browser: problem list on the left, details on the right
+pub fn run(tracker: &ProblemTracker) -> Result<(), Box<dyn Error>> {
+    let mut app = App {
+        tracker,
+        problems: Vec::new(),
+        state: ListState::default(),
+        mode: Mode::Browse,
+        query: String::new(),
+        status: None,
+    };
+    app.load()?;
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+
+    result
+}
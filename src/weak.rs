@@ -0,0 +1,60 @@
+use crate::{PatternStats, Problem};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Days of staleness after which a problem counts as fully forgotten
+const STALE_DAYS: f64 = 30.0;
+
+/// How weak each pattern is, from 0 to 1: half from its average first-attempt
+/// time relative to the slowest pattern, half from its review-flag ratio
+pub fn pattern_weakness(stats: &[PatternStats]) -> HashMap<String, f64> {
+    let slowest = stats
+        .iter()
+        .filter_map(|row| row.avg_first_time)
+        .fold(0.0, f64::max);
+
+    stats
+        .iter()
+        .filter_map(|row| {
+            let pattern = row.pattern.as_ref()?;
+            let slowness = match row.avg_first_time {
+                Some(avg) if slowest > 0.0 => avg / slowest,
+                _ => 0.0,
+            };
+            Some((pattern.clone(), 0.5 * slowness + 0.5 * row.review_ratio))
+        })
+        .collect()
+}
+
+/// Ranks problems from weak patterns, favouring ones not attempted for a
+/// while. Problems attempted within `recent_days` are left out.
+pub fn recommend(
+    problems: Vec<Problem>,
+    weakness: &HashMap<String, f64>,
+    last_attempted: &HashMap<i64, DateTime<Utc>>,
+    recent_days: i64,
+    now: DateTime<Utc>,
+) -> Vec<(f64, Problem)> {
+    let mut ranked: Vec<(f64, Problem)> = problems
+        .into_iter()
+        .filter_map(|problem| {
+            let weakness = *weakness.get(problem.pattern.as_ref()?)?;
+            let staleness = match last_attempted.get(&problem.id?) {
+                Some(last) => {
+                    let days = (now - *last).num_days();
+                    if days < recent_days {
+                        return None;
+                    }
+                    (days as f64 / STALE_DAYS).min(1.0)
+                }
+                None => 1.0,
+            };
+
+            let score = weakness * (0.5 + 0.5 * staleness);
+            (score > 0.0).then_some((score, problem))
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked
+}
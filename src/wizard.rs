@@ -0,0 +1,227 @@
+use crate::categories::{self, Category};
+use crate::Problem;
+use std::io::{self, BufRead, Write};
+
+/// Offered by number in the difficulty menu; anything else may be typed
+const DIFFICULTIES: [&str; 3] = ["Easy", "Medium", "Hard"];
+
+/// Asks `question` and reads one line. `None` at end of input; a blank
+/// answer comes back as an empty string.
+fn prompt(
+    question: &str,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Option<String>> {
+    write!(out, "{}: ", question)?;
+    out.flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Like `prompt`, but a blank answer is `Some(None)`
+fn prompt_optional(
+    question: &str,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Option<Option<String>>> {
+    Ok(
+        prompt(&format!("{} (blank to skip)", question), input, out)?
+            .map(|answer| Some(answer).filter(|answer| !answer.is_empty())),
+    )
+}
+
+/// Walks through a new problem's fields one prompt at a time. Blank answers
+/// leave optional fields empty, invalid ones are asked again, and `None`
+/// means the input ended before the problem was complete.
+pub fn ask_problem(
+    existing: &[Category],
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Option<Problem>> {
+    let description = loop {
+        match prompt("Description", input, out)? {
+            None => return Ok(None),
+            Some(answer) if answer.is_empty() => writeln!(out, "A description is required")?,
+            Some(answer) => break answer,
+        }
+    };
+    let mut problem = Problem::new(&description);
+
+    let Some(link) = prompt_optional("Link", input, out)? else {
+        return Ok(None);
+    };
+    problem.link = link;
+
+    if !existing.is_empty() {
+        let names: Vec<&str> = existing
+            .iter()
+            .map(|category| category.name.as_str())
+            .collect();
+        writeln!(out, "Existing categories: {}", names.join(", "))?;
+    }
+    let Some(category) = prompt_optional("Category", input, out)? else {
+        return Ok(None);
+    };
+    problem.category = match category {
+        Some(typed) => match categories::suggestion(&typed, existing) {
+            Some(suggested) => {
+                let question = format!("Did you mean '{}'? [Y/n]", suggested.name);
+                let Some(answer) = prompt(&question, input, out)? else {
+                    return Ok(None);
+                };
+                if answer.eq_ignore_ascii_case("n") {
+                    Some(typed)
+                } else {
+                    Some(suggested.name.clone())
+                }
+            }
+            None => Some(typed),
+        },
+        None => None,
+    };
+
+    let Some(pattern) = prompt_optional("Pattern", input, out)? else {
+        return Ok(None);
+    };
+    problem.pattern = pattern;
+
+    for (number, difficulty) in DIFFICULTIES.iter().enumerate() {
+        writeln!(out, "  {}) {}", number + 1, difficulty)?;
+    }
+    let Some(difficulty) = prompt_optional("Difficulty", input, out)? else {
+        return Ok(None);
+    };
+    problem.difficulty = difficulty.map(|answer| {
+        match answer
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| DIFFICULTIES.get(number.wrapping_sub(1)))
+        {
+            Some(difficulty) => difficulty.to_string(),
+            None => answer,
+        }
+    });
+
+    problem.time_to_solve_1st = loop {
+        let Some(answer) = prompt_optional("Time to solve in minutes", input, out)? else {
+            return Ok(None);
+        };
+        match answer.as_deref().map(crate::parse_minutes) {
+            None => break None,
+            Some(Ok(minutes)) => break Some(minutes),
+            Some(Err(message)) => writeln!(out, "{}", message)?,
+        }
+    };
+
+    let Some(comments) = prompt_optional("Comments", input, out)? else {
+        return Ok(None);
+    };
+    problem.comments = comments;
+
+    Ok(Some(problem))
+}
+
+/// Offers `existing` patterns as a numbered menu and reads a choice: a
+/// number picks from the menu and anything else is taken as a new pattern.
+/// `None` keeps the current one, on a blank answer or at end of input.
+pub fn ask_pattern(
+    existing: &[String],
+    current: Option<&str>,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Option<String>> {
+    for (number, pattern) in existing.iter().enumerate() {
+        let marker = if Some(pattern.as_str()) == current {
+            " (current)"
+        } else {
+            ""
+        };
+        writeln!(out, "  {}) {}{}", number + 1, pattern, marker)?;
+    }
+
+    loop {
+        let Some(answer) = prompt(
+            "Pattern number, or a new pattern (blank to keep)",
+            input,
+            out,
+        )?
+        else {
+            return Ok(None);
+        };
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        match answer.parse::<usize>() {
+            Ok(number) => match existing.get(number.wrapping_sub(1)) {
+                Some(pattern) => return Ok(Some(pattern.clone())),
+                None => writeln!(out, "Pick a number from 1 to {}", existing.len())?,
+            },
+            Err(_) => return Ok(Some(answer)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ask(answers: &str, existing: &[Category]) -> Option<Problem> {
+        let mut out = Vec::new();
+        ask_problem(existing, &mut answers.as_bytes(), &mut out).unwrap()
+    }
+
+    #[test]
+    fn blanks_are_null_and_bad_answers_are_asked_again() {
+        let problem = ask("\nTwo Sum\n\n\nhash map\n2\nsoon\n15\n\n", &[]).unwrap();
+
+        assert_eq!(problem.description, "Two Sum");
+        assert_eq!(problem.link, None);
+        assert_eq!(problem.category, None);
+        assert_eq!(problem.pattern.as_deref(), Some("hash map"));
+        assert_eq!(problem.difficulty.as_deref(), Some("Medium"));
+        assert_eq!(problem.time_to_solve_1st, Some(15.0));
+        assert_eq!(problem.comments, None);
+    }
+
+    #[test]
+    fn offers_an_existing_category_for_a_typo() {
+        let existing = [Category {
+            id: 1,
+            name: "Graphs".to_string(),
+            problems: 4,
+        }];
+
+        let problem = ask("Word Ladder\n\ngrpahs\n\n\n\n\n\n", &existing).unwrap();
+        assert_eq!(problem.category.as_deref(), Some("Graphs"));
+        let problem = ask("Word Ladder\n\ngrpahs\nn\n\n\n\n\n", &existing).unwrap();
+        assert_eq!(problem.category.as_deref(), Some("grpahs"));
+    }
+
+    #[test]
+    fn pattern_menu_takes_numbers_or_new_names() {
+        let existing = ["dp".to_string(), "greedy".to_string()];
+        let ask = |answers: &str| {
+            ask_pattern(
+                &existing,
+                Some("dp"),
+                &mut answers.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(ask("3\n2\n").as_deref(), Some("greedy"));
+        assert_eq!(ask("two pointers\n").as_deref(), Some("two pointers"));
+        assert_eq!(ask("\n"), None);
+        assert_eq!(ask(""), None);
+    }
+
+    #[test]
+    fn ending_input_early_adds_nothing() {
+        assert!(ask("Two Sum\nhttps://example.com\n", &[]).is_none());
+    }
+}